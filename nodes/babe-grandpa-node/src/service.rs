@@ -26,6 +26,9 @@ native_executor_instance!(
 /// be able to perform chain operations.
 macro_rules! new_full_start {
 	($config:expr) => {{
+		// A type alias we'll use for adding our RPC extension
+		type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
+
 		let mut import_setup = None;
 		let inherent_data_providers = sp_inherents::InherentDataProviders::new();
 
@@ -76,7 +79,15 @@ macro_rules! new_full_start {
 
 				Ok(import_queue)
 			},
-		)?;
+		)?
+		.with_rpc_extensions(|builder| -> Result<RpcExtension, _> {
+			let deps = node_rpc::FullDeps {
+				client: builder.client().clone(),
+				pool: builder.pool(),
+				command_sink: None,
+			};
+			Ok(node_rpc::create_full::<_, _, runtime::UncheckedExtrinsic, _>(deps))
+		})?;
 
 		(builder, import_setup, inherent_data_providers)
 		}};
@@ -8,4 +8,9 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// The account mined blocks name as their author, in SS58 or hex format. It is put in each
+	/// block's pre-runtime digest, where runtimes with the authorship pallet can find it.
+	#[structopt(long)]
+	pub author: Option<runtime::AccountId>,
 }
@@ -54,7 +54,12 @@ pub fn run() -> sc_cli::Result<()> {
 		}
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
-			runner.run_node(service::new_light, service::new_full, runtime::VERSION)
+			let author = cli.author.clone();
+			runner.run_node(
+				service::new_light,
+				|config| service::new_full(config, author),
+				runtime::VERSION,
+			)
 		}
 	}
 }
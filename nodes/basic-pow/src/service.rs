@@ -1,6 +1,7 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use runtime::{self, opaque::Block, RuntimeApi};
+use parity_scale_codec::Encode;
+use runtime::{self, opaque::Block, AccountId, RuntimeApi};
 use sc_client_api::ExecutorProvider;
 use sc_consensus::LongestChain;
 use sc_executor::native_executor_instance;
@@ -35,6 +36,9 @@ pub fn build_inherent_data_providers() -> Result<InherentDataProviders, ServiceE
 /// be able to perform chain operations.
 macro_rules! new_full_start {
 	($config:expr) => {{
+		// A type alias we'll use for adding our RPC extension
+		type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
+
 		let mut import_setup: Option<_> = None;
 		let inherent_data_providers = crate::service::build_inherent_data_providers()?;
 
@@ -77,14 +81,27 @@ macro_rules! new_full_start {
 
 				Ok(import_queue)
 			},
-		)?;
+		)?
+		.with_rpc_extensions(|builder| -> Result<RpcExtension, _> {
+			let deps = node_rpc::FullDeps {
+				client: builder.client().clone(),
+				pool: builder.pool(),
+				command_sink: None,
+			};
+			Ok(node_rpc::create_full::<_, _, runtime::UncheckedExtrinsic, _>(deps))
+		})?;
 
 		(builder, import_setup, inherent_data_providers)
 		}};
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceError> {
+///
+/// When the node mines, the `author` is encoded into the pre-runtime digest of its blocks.
+pub fn new_full(
+	config: Configuration,
+	author: Option<AccountId>,
+) -> Result<impl AbstractService, ServiceError> {
 	let is_authority = config.role.is_authority();
 
 	let (builder, mut import_setup, inherent_data_providers) = new_full_start!(config);
@@ -124,7 +141,8 @@ pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceEr
 			client,
 			MinimalSha3Algorithm,
 			proposer,
-			None, // No preruntime digests
+			// Put in a pre-runtime digest with the PoW engine id
+			author.map(|author| author.encode()),
 			rounds,
 			service.network(),
 			std::time::Duration::new(2, 0),
@@ -21,6 +21,9 @@ native_executor_instance!(
 /// be able to perform chain operations.
 macro_rules! new_full_start {
 	($config:expr) => {{
+		// A type alias we'll use for adding our RPC extension
+		type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
+
 		let builder = sc_service::ServiceBuilder::new_full::<
 			runtime::opaque::Block,
 			runtime::RuntimeApi,
@@ -43,7 +46,15 @@ macro_rules! new_full_start {
 					registry,
 				))
 			},
-		)?;
+		)?
+		.with_rpc_extensions(|builder| -> Result<RpcExtension, _> {
+			let deps = node_rpc::FullDeps {
+				client: builder.client().clone(),
+				pool: builder.pool(),
+				command_sink: None,
+			};
+			Ok(node_rpc::create_full::<_, _, runtime::UncheckedExtrinsic, _>(deps))
+		})?;
 
 		builder
 		}};
@@ -1,7 +1,7 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
 use sc_consensus::LongestChain;
-use sc_consensus_manual_seal::{self as manual_seal, rpc};
+use sc_consensus_manual_seal as manual_seal;
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sc_network::config::DummyFinalityProofRequestBuilder;
@@ -69,14 +69,15 @@ pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceEr
 
 	let service = builder
 		// manual-seal relies on receiving sealing requests aka EngineCommands over rpc.
-		.with_rpc_extensions(|_| -> Result<RpcExtension, _> {
-			let mut io = jsonrpc_core::IoHandler::default();
-			io.extend_with(
+		.with_rpc_extensions(|builder| -> Result<RpcExtension, _> {
+			let deps = node_rpc::FullDeps {
+				client: builder.client().clone(),
+				pool: builder.pool(),
 				// We provide the rpc handler with the sending end of the channel to allow the rpc
 				// send EngineCommands to the background block authorship task.
-				rpc::ManualSealApi::to_delegate(rpc::ManualSeal::new(command_sink)),
-			);
-			Ok(io)
+				command_sink: Some(command_sink),
+			};
+			Ok(node_rpc::create_full::<_, _, runtime::UncheckedExtrinsic, _>(deps))
 		})?
 		.build()?;
 
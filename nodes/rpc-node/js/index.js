@@ -29,6 +29,11 @@ const rpc = {
       description: "Gets the sum of the two storage values in sum-storage pallet via a runtime api.",
       params: [],
       type: "u32",
+    },
+    getState: {
+      description: "Gets both storage values in sum-storage pallet and their sum via a runtime api.",
+      params: [],
+      type: "SumStorageState",
     }
   }
 }
@@ -56,6 +61,10 @@ async function main() {
   // Query the custom RPC that uses the runtimeAPI
   let directSum = ( await api.rpc.sumStorage.getSum() ).toNumber();
   console.log(`The sum queried directly from the RPC is ${directSum}`);
+
+  // Query the whole pallet state at once
+  let state = await api.rpc.sumStorage.getState();
+  console.log(`The state queried from the RPC is ${state}`);
 }
 
 main().catch(console.error).finally(() => process.exit());
@@ -48,8 +48,14 @@ macro_rules! new_full_start {
 			},
 			)?
 		.with_rpc_extensions(|builder| -> Result<RpcExtension, _> {
-			// Make an io handler to be extended with individual RPCs
-			let mut io = jsonrpc_core::IoHandler::default();
+			// Make an io handler with the extensions that every recipe node serves, to be
+			// extended with the RPCs that only this node's runtime supports
+			let deps = node_rpc::FullDeps {
+				client: builder.client().clone(),
+				pool: builder.pool(),
+				command_sink: None,
+			};
+			let mut io = node_rpc::create_full::<_, _, runtime::UncheckedExtrinsic, _>(deps);
 
 			// Add the first rpc extension
 			// Use the fully qualified name starting from `crate` because we're in macro_rules!
@@ -59,8 +65,12 @@ macro_rules! new_full_start {
 
 			// Add the second RPC extension
 			// Because this one calls a Runtime API it needs a reference to the client.
+			// Its subscriptions run on the executor of the RPC server that each one came in on.
+			let subscriptions = jsonrpc_pubsub::manager::SubscriptionManager::new(
+				std::sync::Arc::new(tokio::executor::DefaultExecutor::current()),
+			);
 			io.extend_with(sum_storage_rpc::SumStorageApi::to_delegate(
-				sum_storage_rpc::SumStorage::new(builder.client().clone()),
+				sum_storage_rpc::SumStorage::new(builder.client().clone(), subscriptions),
 			));
 
 			Ok(io)
@@ -0,0 +1,93 @@
+//! The RPC extensions shared by the recipe nodes
+//!
+//! Every recipe runtime uses the same block, account, index and balance types, and implements the
+//! same common runtime APIs, so each node builds its RPC handler here rather than on its own. A
+//! node passes its runtime's `UncheckedExtrinsic`, which differs between runtimes because of their
+//! signed extensions. Nodes whose runtime implements a custom API, such as the `rpc-node`, extend
+//! the handler with that API's RPC afterwards.
+
+use futures::channel::mpsc::Sender;
+use pallet_transaction_payment_rpc::{
+	TransactionPayment, TransactionPaymentApi, TransactionPaymentRuntimeApi,
+};
+use parity_scale_codec::Codec;
+use sc_consensus_manual_seal::rpc::{EngineCommand, ManualSeal, ManualSealApi};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic,
+	traits::{BlakeTwo256, IdentifyAccount, Verify},
+	MultiSignature, OpaqueExtrinsic,
+};
+use sp_transaction_pool::TransactionPool;
+use std::sync::Arc;
+use substrate_frame_rpc_system::{AccountNonceApi, FullSystem, SystemApi};
+
+/// The opaque block type of every recipe runtime
+pub type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+/// The hash type of every recipe runtime
+pub type Hash = sp_core::H256;
+
+/// The account type of every recipe runtime
+pub type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+/// The account index (nonce) type of every recipe runtime
+pub type Index = u32;
+
+/// The balance type of every recipe runtime
+pub type Balance = u128;
+
+/// What a full node's RPC extensions need
+pub struct FullDeps<C, P> {
+	/// The client that the extensions read the chain through
+	pub client: Arc<C>,
+	/// The transaction pool, which knows about nonces that are not on chain yet
+	pub pool: Arc<P>,
+	/// The channel over which manual seal's RPC asks the authorship task to seal blocks. Only
+	/// nodes that seal blocks on command have one.
+	pub command_sink: Option<Sender<EngineCommand<Hash>>>,
+}
+
+/// Builds the RPC handler of a full node
+///
+/// It serves
+/// - `system_accountNextIndex`, the next nonce of an account, counting its transactions in the
+///   pool
+/// - `payment_queryInfo`, the estimated fee of an encoded extrinsic
+/// - `engine_createBlock` and `engine_finalizeBlock`, when the node seals blocks on command
+pub fn create_full<C, P, UE, M>(deps: FullDeps<C, P>) -> jsonrpc_core::IoHandler<M>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AccountNonceApi<Block, AccountId, Index>,
+	C::Api: TransactionPaymentRuntimeApi<Block, Balance, UE>,
+	P: TransactionPool + 'static,
+	UE: Codec + Send + Sync + 'static,
+	M: jsonrpc_core::Metadata + Default,
+{
+	let FullDeps {
+		client,
+		pool,
+		command_sink,
+	} = deps;
+	let mut io = jsonrpc_core::IoHandler::default();
+
+	io.extend_with(SystemApi::to_delegate(FullSystem::new(
+		client.clone(),
+		pool,
+	)));
+
+	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(
+		client,
+	)));
+
+	if let Some(command_sink) = command_sink {
+		io.extend_with(
+			// We provide the rpc handler with the sending end of the channel to allow the rpc
+			// send EngineCommands to the background block authorship task.
+			ManualSealApi::to_delegate(ManualSeal::new(command_sink)),
+		);
+	}
+
+	io
+}
@@ -0,0 +1,49 @@
+//! Benchmarks for the adding machine pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	add {
+		let caller: T::AccountId = account("caller", 0, 0);
+		Sum::put(1);
+	}: _(RawOrigin::Signed(caller), 7)
+	verify {
+		assert_eq!(Sum::get(), 8);
+	}
+
+	add_alternate {
+		let caller: T::AccountId = account("caller", 0, 0);
+		Sum::put(1);
+	}: _(RawOrigin::Signed(caller), 7)
+	verify {
+		assert_eq!(Sum::get(), 8);
+	}
+
+	reset {
+		let caller: T::AccountId = account("caller", 0, 0);
+		Sum::put(1);
+	}: _(RawOrigin::Signed(caller))
+	verify {
+		assert_eq!(Sum::get(), 0);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_add::<TestRuntime>());
+			assert_ok!(test_benchmark_add_alternate::<TestRuntime>());
+			assert_ok!(test_benchmark_reset::<TestRuntime>());
+		});
+	}
+}
@@ -6,10 +6,18 @@
 use frame_support::{decl_error, decl_module, decl_storage, dispatch::DispatchResult, ensure};
 use frame_system::{self as system, ensure_signed};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
 
-pub trait Trait: system::Trait {}
+pub use weights::WeightInfo;
+
+pub trait Trait: system::Trait {
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
 
 decl_storage! {
 	trait Store for Module<T: Trait> as AddingMachine {
@@ -32,7 +40,7 @@ decl_module! {
 		/// Adds the supplied value to the stored value.
 		/// Checks for unlucky number 13.
 		/// Checks for addition overflow using an explicit match
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::add()]
 		fn add(origin, val_to_add: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 
@@ -54,7 +62,7 @@ decl_module! {
 		/// Adds the supplied value to the stored value.
 		/// Checks for unlucky number 13.
 		/// Checks for addition overflow concisely using `ok_or`
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::add_alternate()]
 		fn add_alternate(origin, val_to_add: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 
@@ -69,7 +77,7 @@ decl_module! {
 		}
 
 		/// Resets the stoage value to zero
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::reset()]
 		fn reset(origin) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 
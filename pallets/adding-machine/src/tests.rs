@@ -48,7 +48,9 @@ impl system::Trait for TestRuntime {
 	type OnKilledAccount = ();
 }
 
-impl Trait for TestRuntime {}
+impl Trait for TestRuntime {
+	type WeightInfo = ();
+}
 
 pub type AddingMachine = Module<TestRuntime>;
 
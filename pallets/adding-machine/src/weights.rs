@@ -0,0 +1,46 @@
+//! Weights for the adding machine pallet, one for each benchmark in `benchmarking.rs`
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the adding machine pallet
+pub trait WeightInfo {
+	fn add() -> Weight;
+	fn add_alternate() -> Weight;
+	fn reset() -> Weight;
+}
+
+/// Weights for the adding machine pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn add() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn add_alternate() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn reset() -> Weight {
+		(12_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn add() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn add_alternate() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn reset() -> Weight {
+		(12_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
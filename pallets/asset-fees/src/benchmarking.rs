@@ -0,0 +1,39 @@
+//! Benchmarks for the asset-fees pallet
+
+use super::*;
+use frame_benchmarking::benchmarks;
+
+benchmarks! {
+	_ { }
+
+	set_rate {
+		let asset_id: AssetIdOf<T> = 13u32.into();
+		let rate = Rate::saturating_from_rational(3, 2);
+	}: _(T::RateOrigin::successful_origin(), asset_id, rate)
+	verify {
+		assert_eq!(Module::<T>::rate(asset_id), Some(rate));
+	}
+
+	remove_rate {
+		let asset_id: AssetIdOf<T> = 13u32.into();
+		Rates::<T>::insert(&asset_id, Rate::saturating_from_integer(1u32));
+	}: _(T::RateOrigin::successful_origin(), asset_id)
+	verify {
+		assert_eq!(Module::<T>::rate(asset_id), None);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_set_rate::<TestRuntime>());
+			assert_ok!(test_benchmark_remove_rate::<TestRuntime>());
+		});
+	}
+}
@@ -0,0 +1,384 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Lets transaction senders pay fees in any generic asset
+//!
+//! The `ChargeAssetTxPayment` signed extension replaces the transaction payment pallet's
+//! `ChargeTransactionPayment`. Senders that don't pick an asset pay in native currency exactly as
+//! before. Senders that do pick an asset pay the native fee converted at that asset's rate, which
+//! is set by `RateOrigin`. Assets without a rate can't be used to pay fees.
+//!
+//! The asset is paid to the pallet's exchange account, which in return pays the native fee from
+//! its own native balance. Like `ChargeTransactionPayment`, both are taken before dispatch, and
+//! the fee for unused weight is refunded after it. The native fee is then handed to the
+//! transaction payment pallet's `OnTransactionPayment`, so it is handled just like a fee paid in
+//! native currency. The exchange account must therefore hold enough native currency to cover the
+//! fees it pays.
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{
+		Currency, EnsureOrigin, ExistenceRequirement, Imbalance, OnUnbalanced, WithdrawReason,
+		WithdrawReasons,
+	},
+	weights::{DispatchInfo, PostDispatchInfo},
+};
+use frame_system as system;
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{
+		AccountIdConversion, DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SaturatedConversion,
+		Saturating, SignedExtension, Zero,
+	},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+		ValidTransaction,
+	},
+	FixedPointNumber, FixedPointOperand, FixedU128, ModuleId,
+};
+use transaction_payment::ChargeTransactionPayment;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+/// The native currency, in which the transaction payment pallet computes fees
+pub type BalanceOf<T> = <<T as transaction_payment::Trait>::Currency as Currency<
+	<T as system::Trait>::AccountId,
+>>::Balance;
+pub type NegativeImbalanceOf<T> = <<T as transaction_payment::Trait>::Currency as Currency<
+	<T as system::Trait>::AccountId,
+>>::NegativeImbalance;
+pub type AssetIdOf<T> = <T as generic_asset::Trait>::AssetId;
+pub type AssetBalanceOf<T> = <T as generic_asset::Trait>::Balance;
+
+/// The number of units of an asset that pay for one unit of native currency
+pub type Rate = FixedU128;
+
+/// Hardcoded pallet ID; used to create the exchange account
+const PALLET_ID: ModuleId = ModuleId(*b"assetfee");
+
+/// Custom validity error when the chosen asset has no rate
+pub const NO_RATE: u8 = 1;
+
+pub trait Trait: transaction_payment::Trait + generic_asset::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The origin that may set and remove rates
+	type RateOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as AssetFees {
+		/// The rate of each asset that can be used to pay fees
+		Rates get(fn rate): map hasher(twox_64_concat) AssetIdOf<T> => Option<Rate>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AssetId = AssetIdOf<T>,
+	{
+		/// Fees may be paid in an asset at a new rate (asset, rate)
+		RateSet(AssetId, Rate),
+		/// Fees may no longer be paid in an asset
+		RateRemoved(AssetId),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// A rate of zero would make fees free
+		ZeroRate,
+		/// The asset has no rate to remove
+		NoRate,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		type Error = Error<T>;
+
+		/// Allows fees to be paid in `asset_id`, at `rate` units of the asset per unit of
+		/// native currency
+		#[weight = T::WeightInfo::set_rate()]
+		fn set_rate(origin, asset_id: AssetIdOf<T>, rate: Rate) -> DispatchResult {
+			T::RateOrigin::ensure_origin(origin)?;
+			ensure!(rate > Rate::saturating_from_integer(0u32), Error::<T>::ZeroRate);
+
+			Rates::<T>::insert(&asset_id, rate);
+
+			Self::deposit_event(RawEvent::RateSet(asset_id, rate));
+			Ok(())
+		}
+
+		/// Stops fees from being paid in `asset_id`
+		#[weight = T::WeightInfo::remove_rate()]
+		fn remove_rate(origin, asset_id: AssetIdOf<T>) -> DispatchResult {
+			T::RateOrigin::ensure_origin(origin)?;
+			ensure!(Rates::<T>::contains_key(&asset_id), Error::<T>::NoRate);
+
+			Rates::<T>::remove(&asset_id);
+
+			Self::deposit_event(RawEvent::RateRemoved(asset_id));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account that receives assets and pays native fees in return
+	pub fn account_id() -> T::AccountId {
+		PALLET_ID.into_account()
+	}
+
+	/// The amount of an asset that pays for `fee` in native currency at `rate`
+	pub fn convert(rate: Rate, fee: BalanceOf<T>) -> AssetBalanceOf<T> {
+		rate.saturating_mul_int(fee.saturated_into::<u128>())
+			.saturated_into()
+	}
+}
+
+/// Charges the transaction fee in native currency, or in the generic asset chosen by the sender
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeAssetTxPayment<T: Trait> {
+	#[codec(compact)]
+	tip: BalanceOf<T>,
+	asset_id: Option<AssetIdOf<T>>,
+}
+
+/// An asset payment taken before dispatch, which is settled after dispatch
+pub struct AssetPayment<T: Trait> {
+	who: T::AccountId,
+	asset_id: AssetIdOf<T>,
+	rate: Rate,
+	paid: AssetBalanceOf<T>,
+	tip: BalanceOf<T>,
+	/// The native fee the exchange account paid in return
+	fee: BalanceOf<T>,
+	/// The native fee, withdrawn from the exchange account but not yet handled
+	imbalance: NegativeImbalanceOf<T>,
+}
+
+impl<T: Trait + Send + Sync> ChargeAssetTxPayment<T>
+where
+	<T as system::Trait>::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	BalanceOf<T>: Send + Sync + FixedPointOperand,
+{
+	/// Pays the fee and `tip` in `asset_id`, or in native currency when it is `None`
+	pub fn new(tip: BalanceOf<T>, asset_id: Option<AssetIdOf<T>>) -> Self {
+		Self { tip, asset_id }
+	}
+
+	/// The reasons to withdraw a native fee for, depending on whether it includes a tip
+	fn withdraw_reasons(tip: BalanceOf<T>) -> WithdrawReasons {
+		if tip.is_zero() {
+			WithdrawReason::TransactionPayment.into()
+		} else {
+			WithdrawReason::TransactionPayment | WithdrawReason::Tip
+		}
+	}
+
+	/// Checks that `who` can pay the fee in `asset_id`, and that the exchange account can pay the
+	/// native fee in return. Returns the native fee, the rate, and the fee in the asset.
+	fn asset_fee(
+		&self,
+		who: &T::AccountId,
+		asset_id: &AssetIdOf<T>,
+		info: &DispatchInfoOf<<T as system::Trait>::Call>,
+		len: usize,
+	) -> Result<(BalanceOf<T>, Rate, AssetBalanceOf<T>), TransactionValidityError> {
+		let rate = Module::<T>::rate(asset_id).ok_or(InvalidTransaction::Custom(NO_RATE))?;
+		let fee = transaction_payment::Module::<T>::compute_fee(len as u32, info, self.tip);
+		let amount = Module::<T>::convert(rate, fee);
+
+		if generic_asset::Module::<T>::free_balance(asset_id, who) < amount {
+			return Err(InvalidTransaction::Payment.into());
+		}
+
+		let exchange = Module::<T>::account_id();
+		let available = <T as transaction_payment::Trait>::Currency::free_balance(&exchange)
+			.saturating_sub(<T as transaction_payment::Trait>::Currency::minimum_balance());
+		if available < fee {
+			return Err(InvalidTransaction::Payment.into());
+		}
+
+		Ok((fee, rate, amount))
+	}
+}
+
+impl<T: Trait + Send + Sync> sp_std::fmt::Debug for ChargeAssetTxPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(
+			f,
+			"ChargeAssetTxPayment<{:?}, {:?}>",
+			self.tip, self.asset_id
+		)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for ChargeAssetTxPayment<T>
+where
+	<T as system::Trait>::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	BalanceOf<T>: Send + Sync + FixedPointOperand,
+	AssetIdOf<T>: Send + Sync,
+{
+	const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+	type AccountId = T::AccountId;
+	type Call = <T as system::Trait>::Call;
+	type AdditionalSigned = ();
+	/// What was taken for a native payment, or for an asset payment
+	type Pre = (
+		Option<<ChargeTransactionPayment<T> as SignedExtension>::Pre>,
+		Option<AssetPayment<T>>,
+	);
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		match self.asset_id {
+			None => ChargeTransactionPayment::<T>::from(self.tip).validate(who, call, info, len),
+			Some(ref asset_id) => {
+				let (fee, _, _) = self.asset_fee(who, asset_id, info, len)?;
+				Ok(ValidTransaction {
+					priority: fee.saturated_into::<TransactionPriority>(),
+					..Default::default()
+				})
+			}
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		match self.asset_id {
+			None => {
+				let pre = ChargeTransactionPayment::<T>::from(self.tip)
+					.pre_dispatch(who, call, info, len)?;
+				Ok((Some(pre), None))
+			}
+			Some(ref asset_id) => {
+				let (fee, rate, amount) = self.asset_fee(who, asset_id, info, len)?;
+				let exchange = Module::<T>::account_id();
+
+				// The exchange account pays the native fee up front, so that the call can't spend
+				// it during dispatch
+				let imbalance = <T as transaction_payment::Trait>::Currency::withdraw(
+					&exchange,
+					fee,
+					Self::withdraw_reasons(self.tip),
+					ExistenceRequirement::KeepAlive,
+				)
+				.map_err(|_| InvalidTransaction::Payment)?;
+
+				if generic_asset::Module::<T>::make_transfer(asset_id, who, &exchange, amount)
+					.is_err()
+				{
+					// Give the native fee back, as the transaction is rejected
+					<T as transaction_payment::Trait>::Currency::resolve_creating(
+						&exchange, imbalance,
+					);
+					return Err(InvalidTransaction::Payment.into());
+				}
+
+				let payment = AssetPayment {
+					who: who.clone(),
+					asset_id: asset_id.clone(),
+					rate,
+					paid: amount,
+					tip: self.tip,
+					fee,
+					imbalance,
+				};
+				Ok((None, Some(payment)))
+			}
+		}
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let payment = match pre {
+			(Some(pre), _) => {
+				return ChargeTransactionPayment::<T>::post_dispatch(
+					pre, info, post_info, len, result,
+				)
+			}
+			(None, Some(payment)) => payment,
+			(None, None) => return Ok(()),
+		};
+
+		// The fee for the weight that was actually used
+		let fee = transaction_payment::Module::<T>::compute_actual_fee(
+			len as u32,
+			info,
+			post_info,
+			payment.tip,
+		);
+		let exchange = Module::<T>::account_id();
+
+		// The exchange account gets back the native fee it overpaid, and the rest is handled like
+		// any other fee
+		let refund = payment.fee.saturating_sub(fee);
+		let actual_payment =
+			match <T as transaction_payment::Trait>::Currency::deposit_into_existing(
+				&exchange, refund,
+			) {
+				Ok(refund_imbalance) => payment
+					.imbalance
+					.offset(refund_imbalance)
+					.map_err(|_| InvalidTransaction::Payment)?,
+				// The exchange account is kept alive, so this only happens if the call removed it
+				Err(_) => payment.imbalance,
+			};
+		<T as transaction_payment::Trait>::OnTransactionPayment::on_unbalanced(actual_payment);
+
+		// Refund the asset paid for weight that wasn't used. The rate from before dispatch is used,
+		// in case the call changed it.
+		let actual = Module::<T>::convert(payment.rate, fee).min(payment.paid);
+		let refund = payment.paid.saturating_sub(actual);
+		if !refund.is_zero() {
+			generic_asset::Module::<T>::make_transfer(
+				&payment.asset_id,
+				&exchange,
+				&payment.who,
+				refund,
+			)
+			.map_err(|_| InvalidTransaction::Payment)?;
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,372 @@
+use crate::*;
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_dispatch, impl_outer_event, impl_outer_origin,
+	parameter_types,
+	traits::Imbalance,
+	weights::{IdentityFee, Weight},
+};
+use frame_system::{EnsureRoot, RawOrigin};
+use sp_core::H256;
+use sp_io::TestExternalities;
+use sp_runtime::{
+	testing::Header,
+	traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+use std::cell::RefCell;
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for TestRuntime where origin: Origin {
+		balances::Balances,
+	}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 1;
+	pub const TransactionByteFee: u64 = 1;
+}
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<TestRuntime>;
+}
+
+impl generic_asset::Trait for TestRuntime {
+	type Balance = u64;
+	type AssetId = u32;
+	type Event = TestEvent;
+}
+
+thread_local! {
+	static FEES_PAID: RefCell<u64> = RefCell::new(0);
+}
+
+/// Records the native fees it is handed
+pub struct RecordFees;
+impl OnUnbalanced<balances::NegativeImbalance<TestRuntime>> for RecordFees {
+	fn on_nonzero_unbalanced(amount: balances::NegativeImbalance<TestRuntime>) {
+		FEES_PAID.with(|f| *f.borrow_mut() += amount.peek());
+	}
+}
+
+fn fees_paid() -> u64 {
+	FEES_PAID.with(|f| *f.borrow())
+}
+
+impl transaction_payment::Trait for TestRuntime {
+	type Currency = Balances;
+	type OnTransactionPayment = RecordFees;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ();
+}
+
+mod asset_fees {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for TestRuntime {
+		system<T>,
+		balances<T>,
+		generic_asset<T>,
+		asset_fees<T>,
+	}
+}
+
+impl Trait for TestRuntime {
+	type Event = TestEvent;
+	type RateOrigin = EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+
+pub type System = system::Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
+pub type GenericAsset = generic_asset::Module<TestRuntime>;
+pub type AssetFees = Module<TestRuntime>;
+
+/// An asset that is endowed in genesis
+const ASSET: u32 = 13;
+/// The length of every test transaction
+const LEN: usize = 10;
+
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	pub fn build() -> TestExternalities {
+		let mut storage = system::GenesisConfig::default()
+			.build_storage::<TestRuntime>()
+			.unwrap();
+		balances::GenesisConfig::<TestRuntime> {
+			balances: vec![(1, 1_000), (AssetFees::account_id(), 1_000)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		generic_asset::GenesisConfig::<TestRuntime> {
+			assets: vec![ASSET],
+			initial_balance: 1_000,
+			endowed_accounts: vec![1, 2],
+			next_asset_id: 100,
+			staking_asset_id: 0,
+			spending_asset_id: 0,
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		FEES_PAID.with(|f| *f.borrow_mut() = 0);
+		let mut ext = TestExternalities::from(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+/// Any call will do, as the tests set its dispatch info themselves
+fn call() -> Call {
+	Call::Balances(balances::Call::transfer(2, 10))
+}
+
+/// A transaction that declares a weight of 100. With the identity weight fee and a byte fee of
+/// 1, its fee is 110.
+fn info() -> DispatchInfo {
+	DispatchInfo {
+		weight: 100,
+		..Default::default()
+	}
+}
+
+fn set_rate(numerator: u64, denominator: u64) {
+	assert_ok!(AssetFees::set_rate(
+		RawOrigin::Root.into(),
+		ASSET,
+		Rate::saturating_from_rational(numerator, denominator)
+	));
+}
+
+#[test]
+fn set_rate_works() {
+	ExtBuilder::build().execute_with(|| {
+		let rate = Rate::saturating_from_rational(3, 2);
+		assert_ok!(AssetFees::set_rate(RawOrigin::Root.into(), ASSET, rate));
+		assert_eq!(AssetFees::rate(ASSET), Some(rate));
+
+		assert_noop!(
+			AssetFees::set_rate(Origin::signed(1), ASSET, rate),
+			BadOrigin
+		);
+		assert_noop!(
+			AssetFees::set_rate(
+				RawOrigin::Root.into(),
+				ASSET,
+				Rate::saturating_from_integer(0u32)
+			),
+			Error::<TestRuntime>::ZeroRate
+		);
+	});
+}
+
+#[test]
+fn remove_rate_works() {
+	ExtBuilder::build().execute_with(|| {
+		assert_noop!(
+			AssetFees::remove_rate(RawOrigin::Root.into(), ASSET),
+			Error::<TestRuntime>::NoRate
+		);
+
+		set_rate(2, 1);
+		assert_noop!(AssetFees::remove_rate(Origin::signed(1), ASSET), BadOrigin);
+		assert_ok!(AssetFees::remove_rate(RawOrigin::Root.into(), ASSET));
+		assert_eq!(AssetFees::rate(ASSET), None);
+	});
+}
+
+#[test]
+fn native_fees_are_unchanged() {
+	ExtBuilder::build().execute_with(|| {
+		let pre = ChargeAssetTxPayment::<TestRuntime>::new(0, None)
+			.pre_dispatch(&1, &call(), &info(), LEN)
+			.unwrap();
+		assert_eq!(Balances::free_balance(1), 1_000 - 110);
+
+		let post_info: PostDispatchInfo = Some(50).into();
+		assert_ok!(ChargeAssetTxPayment::<TestRuntime>::post_dispatch(
+			pre,
+			&info(),
+			&post_info,
+			LEN,
+			&Ok(())
+		));
+		assert_eq!(Balances::free_balance(1), 1_000 - 60);
+		assert_eq!(fees_paid(), 60);
+		assert_eq!(GenericAsset::free_balance(&ASSET, &1), 1_000);
+	});
+}
+
+#[test]
+fn asset_fees_are_converted_and_refunded() {
+	ExtBuilder::build().execute_with(|| {
+		set_rate(2, 1);
+		let exchange = AssetFees::account_id();
+
+		let pre = ChargeAssetTxPayment::<TestRuntime>::new(0, Some(ASSET))
+			.pre_dispatch(&1, &call(), &info(), LEN)
+			.unwrap();
+		// The full fee of 110 is paid at twice the native amount
+		assert_eq!(GenericAsset::free_balance(&ASSET, &1), 1_000 - 220);
+		assert_eq!(GenericAsset::free_balance(&ASSET, &exchange), 220);
+		assert_eq!(Balances::free_balance(1), 1_000);
+		// The exchange account paid the full native fee up front
+		assert_eq!(Balances::free_balance(exchange), 1_000 - 110);
+		assert_eq!(fees_paid(), 0);
+
+		// Only half of the weight was used, so the fee is 60
+		let post_info: PostDispatchInfo = Some(50).into();
+		assert_ok!(ChargeAssetTxPayment::<TestRuntime>::post_dispatch(
+			pre,
+			&info(),
+			&post_info,
+			LEN,
+			&Ok(())
+		));
+		assert_eq!(GenericAsset::free_balance(&ASSET, &1), 1_000 - 120);
+		assert_eq!(GenericAsset::free_balance(&ASSET, &exchange), 120);
+
+		// The unused native fee went back to the exchange account, and the rest to
+		// `OnTransactionPayment`
+		assert_eq!(Balances::free_balance(exchange), 1_000 - 60);
+		assert_eq!(fees_paid(), 60);
+		assert_eq!(Balances::free_balance(1), 1_000);
+	});
+}
+
+#[test]
+fn native_fees_cannot_be_spent_during_dispatch() {
+	ExtBuilder::build().execute_with(|| {
+		set_rate(1, 1);
+		let exchange = AssetFees::account_id();
+
+		let pre = ChargeAssetTxPayment::<TestRuntime>::new(0, Some(ASSET))
+			.pre_dispatch(&1, &call(), &info(), LEN)
+			.unwrap();
+		// The call spends all of the exchange account's spendable balance
+		let _ = Balances::slash(&exchange, 1_000 - 110 - 1);
+
+		let post_info: PostDispatchInfo = Some(50).into();
+		assert_ok!(ChargeAssetTxPayment::<TestRuntime>::post_dispatch(
+			pre,
+			&info(),
+			&post_info,
+			LEN,
+			&Ok(())
+		));
+		// The native fee was still paid in full, and the unused part refunded
+		assert_eq!(fees_paid(), 60);
+		assert_eq!(Balances::free_balance(exchange), 1 + 50);
+		assert_eq!(GenericAsset::free_balance(&ASSET, &1), 1_000 - 60);
+	});
+}
+
+#[test]
+fn tips_are_paid_in_the_asset() {
+	ExtBuilder::build().execute_with(|| {
+		set_rate(1, 2);
+
+		let pre = ChargeAssetTxPayment::<TestRuntime>::new(10, Some(ASSET))
+			.pre_dispatch(&1, &call(), &info(), LEN)
+			.unwrap();
+		assert_eq!(GenericAsset::free_balance(&ASSET, &1), 1_000 - 60);
+
+		assert_ok!(ChargeAssetTxPayment::<TestRuntime>::post_dispatch(
+			pre,
+			&info(),
+			&Default::default(),
+			LEN,
+			&Ok(())
+		));
+		assert_eq!(GenericAsset::free_balance(&ASSET, &1), 1_000 - 60);
+		assert_eq!(fees_paid(), 120);
+	});
+}
+
+#[test]
+fn assets_without_a_rate_are_rejected() {
+	ExtBuilder::build().execute_with(|| {
+		let charge = ChargeAssetTxPayment::<TestRuntime>::new(0, Some(ASSET));
+		assert_eq!(
+			charge.validate(&1, &call(), &info(), LEN),
+			Err(InvalidTransaction::Custom(NO_RATE).into())
+		);
+		assert!(charge.pre_dispatch(&1, &call(), &info(), LEN).is_err());
+		assert_eq!(GenericAsset::free_balance(&ASSET, &1), 1_000);
+	});
+}
+
+#[test]
+fn senders_must_afford_the_asset_fee() {
+	ExtBuilder::build().execute_with(|| {
+		// At this rate the fee of 110 costs 1_100 of the asset
+		set_rate(10, 1);
+
+		let charge = ChargeAssetTxPayment::<TestRuntime>::new(0, Some(ASSET));
+		assert_eq!(
+			charge.validate(&1, &call(), &info(), LEN),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert!(charge.pre_dispatch(&1, &call(), &info(), LEN).is_err());
+		assert_eq!(GenericAsset::free_balance(&ASSET, &1), 1_000);
+	});
+}
+
+#[test]
+fn exchange_must_afford_the_native_fee() {
+	ExtBuilder::build().execute_with(|| {
+		set_rate(1, 1);
+		let exchange = AssetFees::account_id();
+		let _ = Balances::slash(&exchange, 1_000 - 100);
+
+		let charge = ChargeAssetTxPayment::<TestRuntime>::new(0, Some(ASSET));
+		assert_eq!(
+			charge.validate(&1, &call(), &info(), LEN),
+			Err(InvalidTransaction::Payment.into())
+		);
+		assert_eq!(GenericAsset::free_balance(&ASSET, &1), 1_000);
+	});
+}
@@ -0,0 +1,35 @@
+//! Weights for the asset-fees pallet, one for each benchmark in `benchmarking.rs`
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the asset-fees pallet
+pub trait WeightInfo {
+	fn set_rate() -> Weight;
+	fn remove_rate() -> Weight;
+}
+
+/// Weights for the asset-fees pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn set_rate() -> Weight {
+		(20_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn remove_rate() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn set_rate() -> Weight {
+		(20_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn remove_rate() -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
@@ -0,0 +1,43 @@
+//! Benchmarks for the basic token pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	init {
+		let caller: T::AccountId = account("caller", 0, 0);
+		Init::put(false);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Module::<T>::get_balance(&caller), Module::<T>::total_supply());
+	}
+
+	transfer {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let recipient: T::AccountId = account("recipient", 0, 0);
+		<Balances<T>>::insert(&caller, 1_000);
+		<Balances<T>>::insert(&recipient, 1_000);
+	}: _(RawOrigin::Signed(caller.clone()), recipient.clone(), 400)
+	verify {
+		assert_eq!(Module::<T>::get_balance(&caller), 600);
+		assert_eq!(Module::<T>::get_balance(&recipient), 1_400);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_init::<TestRuntime>());
+			assert_ok!(test_benchmark_transfer::<TestRuntime>());
+		});
+	}
+}
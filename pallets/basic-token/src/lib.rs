@@ -9,11 +9,19 @@ use frame_support::{
 };
 use frame_system::{self as system, ensure_signed};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -53,7 +61,7 @@ decl_module! {
 
 		/// Initialize the token
 		/// transfers the total_supply amout to the caller
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::init()]
 		fn init(origin) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 			ensure!(!Self::is_init(), <Error<T>>::AlreadyInitialized);
@@ -65,7 +73,7 @@ decl_module! {
 		}
 
 		/// Transfer tokens from one account to another
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::transfer()]
 		fn transfer(_origin, to: T::AccountId, value: u64) -> DispatchResult {
 			let sender = ensure_signed(_origin)?;
 			let sender_balance = Self::get_balance(&sender);
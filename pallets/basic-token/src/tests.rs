@@ -50,6 +50,7 @@ impl system::Trait for TestRuntime {
 
 impl Trait for TestRuntime {
 	type Event = ();
+	type WeightInfo = ();
 }
 
 pub type BasicToken = Module<TestRuntime>;
@@ -0,0 +1,43 @@
+//! Benchmarks for the charity pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	donate {
+		let donor: T::AccountId = account("donor", 0, 0);
+		let amount: BalanceOf<T> = 1_000u32u32.into();
+		T::Currency::make_free_balance_be(&donor, amount * 2u32.into());
+		T::Currency::make_free_balance_be(&Module::<T>::account_id(), amount);
+	}: _(RawOrigin::Signed(donor), amount)
+	verify {
+		assert_eq!(Module::<T>::pot(), amount * 2u32.into());
+	}
+
+	allocate {
+		let dest: T::AccountId = account("dest", 0, 0);
+		let amount: BalanceOf<T> = 1_000u32u32.into();
+		T::Currency::make_free_balance_be(&Module::<T>::account_id(), amount * 2u32.into());
+	}: _(RawOrigin::Root, dest, amount)
+	verify {
+		assert_eq!(Module::<T>::pot(), amount);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_donate::<TestRuntime>());
+			assert_ok!(test_benchmark_allocate::<TestRuntime>());
+		});
+	}
+}
@@ -18,8 +18,13 @@ use frame_support::{
 };
 use frame_system::{self as system, ensure_root, ensure_signed};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
 type NegativeImbalanceOf<T> =
@@ -34,6 +39,9 @@ pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 	/// The currency type that the charity deals in
 	type Currency: Currency<Self::AccountId>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -73,7 +81,7 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// Donate some funds to the charity
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::donate()]
 		fn donate(
 			origin,
 			amount: BalanceOf<T>
@@ -91,7 +99,7 @@ decl_module! {
 		///
 		/// Take funds from the Charity's pot and send them somewhere. This call requires root origin,
 		/// which means it must come from a governance mechanism such as Substrate's Democracy pallet.
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::allocate()]
 		fn allocate(
 			origin,
 			dest: T::AccountId,
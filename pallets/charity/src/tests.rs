@@ -76,6 +76,7 @@ impl_outer_event! {
 impl Trait for TestRuntime {
 	type Event = TestEvent;
 	type Currency = balances::Module<Self>;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
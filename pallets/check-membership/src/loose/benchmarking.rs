@@ -0,0 +1,33 @@
+//! Benchmarks for the loosely coupled check membership pallet
+//!
+//! The `AccountSet` trait cannot add members, so the benchmark calls as the first member of the
+//! `MembershipSource`. The source must have at least one member when the benchmark runs.
+
+use super::*;
+use frame_benchmarking::benchmarks;
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	check_membership {
+		let caller = T::MembershipSource::accounts_paged(None, 1)
+			.pop()
+			.ok_or("the membership source has no members")?;
+	}: _(RawOrigin::Signed(caller))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::loose::tests::{ExtBuilder, Origin, TestRuntime, VecSet};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(VecSet::add_member(Origin::signed(1)));
+			assert_ok!(test_benchmark_check_membership::<TestRuntime>());
+		});
+	}
+}
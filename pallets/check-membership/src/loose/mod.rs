@@ -10,8 +10,13 @@ use frame_support::{decl_error, decl_event, decl_module, dispatch::DispatchResul
 use frame_system::{self as system, ensure_signed};
 use account_set::AccountSet;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 /// The pallet's configuration trait
 /// Notice the loose coupling: any pallet that implements the `AccountSet` behavior works here.
@@ -21,6 +26,9 @@ pub trait Trait: system::Trait {
 
 	/// A type that will supply a set of members to check access control against
 	type MembershipSource: AccountSet<AccountId = Self::AccountId>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_event!(
@@ -46,15 +54,12 @@ decl_module! {
 
 		/// Checks whether the caller is a member of the set of account IDs provided by the
 		/// MembershipSource type. Emits an event if they are, and errors if not.
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::check_membership()]
 		fn check_membership(origin) -> DispatchResult {
 			let caller = ensure_signed(origin)?;
 
-			// Get the members from the `vec-set` pallet
-			let members = T::MembershipSource::accounts();
-
-			// Check whether the caller is a member
-			ensure!(members.contains(&caller), Error::<T>::NotAMember);
+			// Check whether the caller is a member, without reading the whole set
+			ensure!(T::MembershipSource::contains(&caller), Error::<T>::NotAMember);
 
 			// If the previous call didn't error, then the caller is a member, so emit the event
 			Self::deposit_event(RawEvent::IsAMember(caller));
@@ -0,0 +1,28 @@
+//! Weights for the loosely coupled check membership pallet, one for each benchmark in
+//! `benchmarking.rs`
+//!
+//! The cost of the membership check depends on the `MembershipSource`. These weights assume a
+//! source that answers `contains` with a single storage read.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the loosely coupled check membership pallet
+pub trait WeightInfo {
+	fn check_membership() -> Weight;
+}
+
+/// Weights for the loosely coupled check membership pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn check_membership() -> Weight {
+		(15_000_000 as Weight).saturating_add(T::DbWeight::get().reads(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn check_membership() -> Weight {
+		(15_000_000 as Weight).saturating_add(RocksDbWeight::get().reads(1))
+	}
+}
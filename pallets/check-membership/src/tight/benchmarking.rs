@@ -0,0 +1,45 @@
+//! Benchmarks for the tightly coupled check membership pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::Currency;
+use frame_system::RawOrigin;
+
+/// Adds the account to the `vec-set` pallet's members unless it is one already
+fn join<T: Trait>(who: &T::AccountId) -> Result<(), &'static str> {
+	if vec_set::Module::<T>::members().contains(who) {
+		return Ok(());
+	}
+	let deposit = <T as vec_set::Trait>::AdmissionDeposit::get()
+		.max(<T as vec_set::Trait>::Currency::minimum_balance());
+	<T as vec_set::Trait>::Currency::make_free_balance_be(who, deposit * 10u32.into());
+	vec_set::Module::<T>::add_member(RawOrigin::Signed(who.clone()).into())?;
+	Ok(())
+}
+
+benchmarks! {
+	_ { }
+
+	check_membership {
+		let m in 1 .. <T as vec_set::Trait>::MaxMembers::get();
+		for i in 1..m {
+			join::<T>(&account("member", i, 0))?;
+		}
+		let caller: T::AccountId = account("caller", 0, 0);
+		join::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tight::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_check_membership::<TestRuntime>());
+		});
+	}
+}
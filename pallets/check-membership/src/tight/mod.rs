@@ -5,16 +5,24 @@
 //! The list of approved members is provided by the `vec-set` pallet. In order for this pallet to be
 //! used, the `vec-set` pallet must also be present in the runtime.
 
-use frame_support::{decl_error, decl_event, decl_module, dispatch::DispatchResult};
+use frame_support::{decl_error, decl_event, decl_module, dispatch::DispatchResult, traits::Get};
 use frame_system::{self as system, ensure_signed};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 /// The pallet's configuration trait.
 /// Notice the explicit tight coupling to the `vec-set` pallet
 pub trait Trait: system::Trait + vec_set::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_event!(
@@ -40,7 +48,9 @@ decl_module! {
 
 		/// Checks whether the caller is a member of the set of account IDs provided by the `vec-set`
 		/// pallet. Emits an event if they are, and errors if not.
-		#[weight = 10_000]
+		#[weight = <T as Trait>::WeightInfo::check_membership(
+			<T as vec_set::Trait>::MaxMembers::get()
+		)]
 		fn check_membership(origin) -> DispatchResult {
 			let caller = ensure_signed(origin)?;
 
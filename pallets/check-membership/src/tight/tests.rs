@@ -1,6 +1,6 @@
 use crate::tight::*;
 use frame_support::{assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types};
-use frame_system as system;
+use frame_system::{self as system, EnsureRoot};
 use sp_core::H256;
 use sp_io::TestExternalities;
 use sp_runtime::{
@@ -21,6 +21,10 @@ parameter_types! {
 	pub const MaximumBlockWeight: u32 = 1024;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 1;
+	pub const AdmissionDeposit: u64 = 10;
+	pub const MaxMembers: u32 = 16;
 }
 impl system::Trait for TestRuntime {
 	type Origin = Origin;
@@ -43,7 +47,7 @@ impl system::Trait for TestRuntime {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type ModuleToIndex = ();
-	type AccountData = ();
+	type AccountData = balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 }
@@ -57,15 +61,33 @@ impl_outer_event! {
 		vec_set<T>,
 		system<T>,
 		check_membership<T>,
+		balances<T>,
 	}
 }
 
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<TestRuntime>;
+}
+
 impl vec_set::Trait for TestRuntime {
 	type Event = TestEvent;
+	type Currency = balances::Module<Self>;
+	type AddOrigin = EnsureRoot<u64>;
+	type RemoveOrigin = EnsureRoot<u64>;
+	type AdmissionDeposit = AdmissionDeposit;
+	type MaxMembers = MaxMembers;
+	type Slashed = ();
+	type MembershipChanged = ();
+	type WeightInfo = ();
 }
 
 impl Trait for TestRuntime {
 	type Event = TestEvent;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
@@ -76,9 +98,14 @@ pub struct ExtBuilder;
 
 impl ExtBuilder {
 	pub fn build() -> TestExternalities {
-		let storage = system::GenesisConfig::default()
+		let mut storage = system::GenesisConfig::default()
 			.build_storage::<TestRuntime>()
 			.unwrap();
+		balances::GenesisConfig::<TestRuntime> {
+			balances: vec![(1, 100), (2, 100)],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
 		let mut ext = TestExternalities::from(storage);
 		ext.execute_with(|| System::set_block_number(1));
 		ext
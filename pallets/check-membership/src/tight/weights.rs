@@ -0,0 +1,32 @@
+//! Weights for the tightly coupled check membership pallet, one for each benchmark in
+//! `benchmarking.rs`
+//!
+//! The membership check decodes the whole members vec of the `vec-set` pallet, so its weight is
+//! linear in the number of members `m`. The pallet charges for `MaxMembers`.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the tightly coupled check membership pallet
+pub trait WeightInfo {
+	fn check_membership(m: u32) -> Weight;
+}
+
+/// Weights for the tightly coupled check membership pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn check_membership(m: u32) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn check_membership(m: u32) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1))
+	}
+}
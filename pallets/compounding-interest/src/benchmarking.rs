@@ -0,0 +1,129 @@
+//! Benchmarks for the compounding interest pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+/// The amount each benchmark's caller has deposited in each kind of account
+fn savings<T: Trait>() -> BalanceOf<T> {
+	1_000_000u32.into()
+}
+
+/// Makes `who` a supplier and borrower in a market with interest to accrue
+///
+/// `who` deposits `savings` into both kinds of account and borrows a tenth of it, then the chain
+/// moves on by a whole discrete period, so the call being benchmarked updates every index.
+fn setup_market<T: Trait>(who: &T::AccountId) -> Result<(), &'static str> {
+	let savings = savings::<T>();
+	T::Currency::make_free_balance_be(who, savings * 10u32.into());
+	T::Currency::make_free_balance_be(&Module::<T>::account_id(), savings * 10u32.into());
+	T::Currency::make_free_balance_be(
+		&Module::<T>::discrete_reserve_id(),
+		savings * 10u32.into(),
+	);
+
+	let origin: T::Origin = RawOrigin::Signed(who.clone()).into();
+	Module::<T>::deposit_continuous(origin.clone(), savings)?;
+	Module::<T>::deposit_discrete(origin.clone(), savings)?;
+	Module::<T>::borrow(origin, savings / 10u32.into())?;
+
+	let now = system::Module::<T>::block_number();
+	system::Module::<T>::set_block_number(now + DISCRETE_PERIOD.into());
+	Ok(())
+}
+
+benchmarks! {
+	_ { }
+
+	deposit_continuous {
+		let caller: T::AccountId = account("caller", 0, 0);
+		setup_market::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), savings::<T>())
+	verify {
+		assert_eq!(Module::<T>::last_accrual(), system::Module::<T>::block_number());
+	}
+
+	withdraw_continuous {
+		let caller: T::AccountId = account("caller", 0, 0);
+		setup_market::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), savings::<T>() / 2u32.into())
+	verify {
+		assert_eq!(Module::<T>::last_accrual(), system::Module::<T>::block_number());
+	}
+
+	deposit_discrete {
+		let caller: T::AccountId = account("caller", 0, 0);
+		setup_market::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), savings::<T>())
+	verify {
+		let account = Module::<T>::discrete_account(&caller);
+		assert_eq!(account.discrete_index, Module::<T>::discrete_index());
+	}
+
+	withdraw_discrete {
+		let caller: T::AccountId = account("caller", 0, 0);
+		setup_market::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), savings::<T>() / 2u32.into())
+	verify {
+		let account = Module::<T>::discrete_account(&caller);
+		assert_eq!(account.discrete_index, Module::<T>::discrete_index());
+	}
+
+	borrow {
+		let caller: T::AccountId = account("caller", 0, 0);
+		setup_market::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), savings::<T>() / 10u32.into())
+	verify {
+		assert_eq!(Module::<T>::debt(&caller).borrow_index, Module::<T>::borrow_index());
+	}
+
+	repay {
+		let caller: T::AccountId = account("caller", 0, 0);
+		setup_market::<T>(&caller)?;
+	}: _(RawOrigin::Signed(caller.clone()), savings::<T>() / 20u32.into())
+	verify {
+		assert_eq!(Module::<T>::debt(&caller).borrow_index, Module::<T>::borrow_index());
+	}
+
+	set_discrete_interest_rate {
+		let caller: T::AccountId = account("caller", 0, 0);
+		setup_market::<T>(&caller)?;
+		let rate = Permill::from_parts(20);
+	}: _(RawOrigin::Root, rate)
+	verify {
+		assert_eq!(Module::<T>::discrete_interest_rate(), rate);
+	}
+
+	set_interest_rate_model {
+		let caller: T::AccountId = account("caller", 0, 0);
+		setup_market::<T>(&caller)?;
+		let model = InterestRateModel {
+			base_rate: Permill::from_parts(2),
+			..Module::<T>::interest_rate_model()
+		};
+	}: _(RawOrigin::Root, model)
+	verify {
+		assert_eq!(Module::<T>::interest_rate_model(), model);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_deposit_continuous::<TestRuntime>());
+			assert_ok!(test_benchmark_withdraw_continuous::<TestRuntime>());
+			assert_ok!(test_benchmark_deposit_discrete::<TestRuntime>());
+			assert_ok!(test_benchmark_withdraw_discrete::<TestRuntime>());
+			assert_ok!(test_benchmark_borrow::<TestRuntime>());
+			assert_ok!(test_benchmark_repay::<TestRuntime>());
+			assert_ok!(test_benchmark_set_discrete_interest_rate::<TestRuntime>());
+			assert_ok!(test_benchmark_set_interest_rate_model::<TestRuntime>());
+		});
+	}
+}
@@ -1,197 +1,780 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
-//! A pallet that demonstrates Fixed Point arithmetic in the context of two simple bank accounts
-//! that accrue compounding interest.
+//! A pallet that demonstrates Fixed Point arithmetic in the context of a simple lending market
+//! whose savings accounts accrue compounding interest.
 //!
-//! The discrete account accrues interest every ten blocks and is implemented using
-//! Substrate's `Percent` implementation of fixed point.
+//! Each user may hold one discrete and one continuous savings position. Deposits are moved from
+//! the user's `Currency` balance into accounts owned by the pallet, and withdrawals are paid back
+//! out of them.
 //!
-//! The continuous account accrues interest continuously and is implemented using
+//! The discrete account accrues interest every ten blocks at a flat rate, expressed using
+//! Substrate's `Permill` implementation of fixed point. Discrete savings are kept in their own
+//! reserve, apart from the continuous market. Their interest is paid from whatever the reserve
+//! holds beyond the savings themselves, which anyone may top up with a plain transfer. When the
+//! reserve cannot cover the interest an account is owed, the account is paid what is available
+//! and the rest is forfeited.
+//!
+//! The continuous accounts supply the funds that users may borrow against their own savings.
+//! Borrowers pay interest continuously according to a utilization-based rate model, and that
+//! interest is shared among the suppliers. Continuous compounding is implemented using
 //! Substrate-fixed's `I32F32` implementation of fixed point.
-
-use frame_support::{decl_event, decl_module, decl_storage, dispatch::DispatchResult};
-use frame_system::{self as system, ensure_signed};
+//!
+//! Both rates are stored on-chain and may be changed by the root origin.
+//!
+//! No account is updated in the background. Instead the pallet tracks a global interest index
+//! for each kind of account, and each account remembers the index at which it was last adjusted.
+//! An account's current value is calculated "just in time" by comparing the two indices, so
+//! touching an account costs the same no matter how many accounts exist. An index stops growing
+//! once it reaches the largest value it can hold, so accruing interest never fails.
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, ExistenceRequirement::AllowDeath, Get},
+};
+use frame_system::{self as system, ensure_root, ensure_signed};
 use parity_scale_codec::{Decode, Encode};
-use sp_arithmetic::Percent;
-use sp_runtime::traits::Zero;
-use sp_std::convert::TryInto;
-use substrate_fixed::{transcendental::exp, types::I32F32};
-
+use sp_arithmetic::Permill;
+use sp_runtime::{
+	traits::{
+		AccountIdConversion, Bounded, CheckedAdd, CheckedSub, Saturating, UniqueSaturatedInto, Zero,
+	},
+	ModuleId, RuntimeDebug,
+};
+use sp_std::convert::{TryFrom, TryInto};
+use substrate_fixed::types::{I32F32, I64F64};
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+/// Hardcoded pallet ID; used to create the special Pot Account that holds all savings
+/// Must be exactly 8 characters long
+const PALLET_ID: ModuleId = ModuleId(*b"Interest");
+
+/// Identifies the account that holds the discrete savings and pays their interest
+/// Must be exactly 8 characters long
+const DISCRETE_RESERVE_ID: ModuleId = ModuleId(*b"Discrete");
+
+/// The discrete account pays interest once every this many blocks
+const DISCRETE_PERIOD: u32 = 10;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
 
 pub trait Trait: system::Trait {
-	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+	/// The overarching event type
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency in which savings are denominated
+	type Currency: Currency<Self::AccountId>;
+
+	/// The interest rate applied to discrete accounts, per block, until governance sets one
+	type DefaultDiscreteInterestRate: Get<Permill>;
+
+	/// The rate model applied to continuous accounts and debts until governance sets one
+	type DefaultInterestRateModel: Get<InterestRateModel>;
+
+	/// The portion of a user's continuous savings that they may borrow against
+	type CollateralFactor: Get<Permill>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
-#[derive(Encode, Decode, Default)]
-pub struct ContinuousAccountData<BlockNumber> {
+/// A utilization-based interest rate model with a single kink.
+///
+/// The borrow rate rises gently with utilization until the market reaches the kink, and steeply
+/// after it. That encourages suppliers to deposit and borrowers to repay when liquidity runs low.
+/// All rates are per block.
+#[derive(Encode, Decode, Default, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct InterestRateModel {
+	/// The borrow rate when nothing is borrowed
+	pub base_rate: Permill,
+	/// The rate added between zero utilization and the kink
+	pub slope_low: Permill,
+	/// The rate added between the kink and full utilization
+	pub slope_high: Permill,
+	/// The utilization at which the steep slope begins
+	pub kink: Permill,
+}
+
+impl InterestRateModel {
+	/// The interest rate paid by borrowers at the given utilization
+	pub fn borrow_rate(&self, utilization: Permill) -> Permill {
+		if utilization <= self.kink {
+			let ramp = Self::portion(utilization, self.kink);
+			self.base_rate.saturating_add(self.slope_low * ramp)
+		} else {
+			let excess = utilization.saturating_sub(self.kink);
+			let ramp = Self::portion(excess, Permill::one().saturating_sub(self.kink));
+			self.base_rate
+				.saturating_add(self.slope_low)
+				.saturating_add(self.slope_high * ramp)
+		}
+	}
+
+	/// The interest rate earned by suppliers at the given utilization. Suppliers share the
+	/// interest paid by borrowers, so they earn the borrow rate on the utilized portion only.
+	pub fn supply_rate(&self, utilization: Permill) -> Permill {
+		self.borrow_rate(utilization) * utilization
+	}
+
+	/// How far `part` has progressed towards `whole`. An empty range counts as fully progressed.
+	fn portion(part: Permill, whole: Permill) -> Permill {
+		if whole.deconstruct() == 0 {
+			Permill::one()
+		} else {
+			Permill::from_rational_approximation(part.deconstruct(), whole.deconstruct())
+		}
+	}
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct ContinuousAccountData<Balance> {
 	/// The balance of the account after last manual adjustment
-	principal: I32F32,
-	/// The time (block height) at which the balance was last adjusted
-	deposit_date: BlockNumber,
+	principal: Balance,
+	/// The supply index at the time the balance was last adjusted
+	supply_index: I64F64,
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct DiscreteAccountData<Balance> {
+	/// The balance of the account after last manual adjustment
+	principal: Balance,
+	/// The discrete index at the time the balance was last adjusted
+	discrete_index: I64F64,
+}
+
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct BorrowData<Balance> {
+	/// The debt of the account after last manual adjustment
+	principal: Balance,
+	/// The borrow index at the time the debt was last adjusted
+	borrow_index: I64F64,
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Example {
-		/// Balance for the continuously compounded account
-		ContinuousAccount get(fn balance_compound): ContinuousAccountData<T::BlockNumber>;
-		/// Balance for the discrete interest account
-		DiscreteAccount get(fn discrete_account): u64;
+		/// Balances for the continuously compounded accounts
+		ContinuousAccounts get(fn continuous_account):
+			map hasher(blake2_128_concat) T::AccountId => ContinuousAccountData<BalanceOf<T>>;
+		/// Balances for the discrete interest accounts
+		DiscreteAccounts get(fn discrete_account):
+			map hasher(blake2_128_concat) T::AccountId => DiscreteAccountData<BalanceOf<T>>;
+		/// Outstanding debts of borrowers
+		Borrows get(fn debt):
+			map hasher(blake2_128_concat) T::AccountId => BorrowData<BalanceOf<T>>;
+
+		/// The discrete interest rate set by governance, if any
+		DiscreteRate: Option<Permill>;
+		/// The continuous interest rate model set by governance, if any
+		RateModel: Option<InterestRateModel>;
+
+		/// Growth of a discrete account since genesis, starts at 1 (multiplicative identity)
+		DiscreteIndex get(fn discrete_index): I64F64 = I64F64::from_num(1);
+		/// The number of discrete periods that had elapsed when the discrete index was updated
+		DiscretePeriodsAccrued get(fn discrete_periods_accrued): T::BlockNumber;
+		/// The combined principal of all discrete accounts, not counting interest until it is paid
+		TotalDiscretePrincipal get(fn total_discrete_principal): BalanceOf<T>;
+
+		/// Growth of a continuous account since genesis, starts at 1 (multiplicative identity)
+		SupplyIndex get(fn supply_index): I64F64 = I64F64::from_num(1);
+		/// Growth of a debt since genesis, starts at 1 (multiplicative identity)
+		BorrowIndex get(fn borrow_index): I64F64 = I64F64::from_num(1);
+		/// The block at which the supply and borrow indices were last updated
+		LastAccrual get(fn last_accrual): T::BlockNumber;
+
+		/// The combined value of all continuous accounts as of the last accrual
+		TotalSupplied get(fn total_supplied): BalanceOf<T>;
+		/// The combined value of all debts as of the last accrual
+		TotalBorrowed get(fn total_borrowed): BalanceOf<T>;
 	}
 }
 
 decl_event!(
-	pub enum Event {
+	pub enum Event<T>
+	where
+		AccountId = <T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+	{
 		/// Deposited some balance into the compounding interest account
-		DepositedContinuous(u64),
+		DepositedContinuous(AccountId, Balance),
 		/// Withdrew some balance from the compounding interest account
-		WithdrewContinuous(u64),
+		WithdrewContinuous(AccountId, Balance),
 		/// Deposited some balance into the discrete interest account
-		DepositedDiscrete(u64),
+		DepositedDiscrete(AccountId, Balance),
 		/// Withdrew some balance from the discrete interest account
-		WithdrewDiscrete(u64),
-		/// Some interest has been applied to the discrete interest account
+		WithdrewDiscrete(AccountId, Balance),
+		/// Some interest has been applied to a discrete interest account
 		/// The associated data is just the interest amout (not the new or old balance)
-		/// This happens every ten blocks
-		DiscreteInterestApplied(u64),
+		/// Interest is paid every ten blocks, and applied when the account is next touched
+		DiscreteInterestApplied(AccountId, Balance),
+		/// The discrete reserve could not cover all of the interest owed to an account
+		/// The associated data is the unpaid interest, which is forfeited
+		DiscreteInterestShortfall(AccountId, Balance),
+		/// Borrowed some balance against continuous savings
+		Borrowed(AccountId, Balance),
+		/// Repaid some borrowed balance
+		Repaid(AccountId, Balance),
+		/// Governance has changed the discrete interest rate
+		DiscreteInterestRateSet(Permill),
+		/// Governance has changed the continuous interest rate model
+		InterestRateModelSet(InterestRateModel),
 	}
 );
 
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Some math operation overflowed
+		Overflow,
+		/// Cannot withdraw more than the current value of the account
+		InsufficientSavings,
+		/// The pallet does not hold enough funds to pay out the withdrawal
+		InsufficientLiquidity,
+		/// The account's debt would exceed the amount its savings allow it to borrow
+		InsufficientCollateral,
+		/// Cannot repay a debt that does not exist
+		NoDebt,
+	}
+}
+
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
-		/// Deposit some funds into the compounding interest account
-		#[weight = 10_000]
-		fn deposit_continuous(origin, val_to_add: u64) -> DispatchResult {
-			ensure_signed(origin)?;
+		type Error = Error<T>;
+
+		/// Deposit some funds into the caller's compounding interest account
+		#[weight = T::WeightInfo::deposit_continuous()]
+		fn deposit_continuous(origin, val_to_add: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
 			let current_block = system::Module::<T>::block_number();
-			let old_value = Self::value_of_continuous_account(&current_block);
+			Self::accrue_interest(&current_block);
+			let old_value = Self::value_of_continuous_account(&who, &current_block)?;
+			let new_value = old_value.checked_add(&val_to_add).ok_or(Error::<T>::Overflow)?;
+			let total_supplied = Self::total_supplied()
+				.checked_add(&val_to_add)
+				.ok_or(Error::<T>::Overflow)?;
+
+			// Move the funds into the pot
+			T::Currency::transfer(&who, &Self::account_id(), val_to_add, AllowDeath)?;
 
 			// Update storage for compounding account
-			ContinuousAccount::<T>::put(
+			ContinuousAccounts::<T>::insert(
+				&who,
 				ContinuousAccountData {
-					principal: old_value + I32F32::from_num(val_to_add),
-					deposit_date: current_block,
+					principal: new_value,
+					supply_index: Self::supply_index(),
 				}
 			);
+			TotalSupplied::<T>::put(total_supplied);
 
 			// Emit event
-			Self::deposit_event(Event::DepositedContinuous(val_to_add));
+			Self::deposit_event(RawEvent::DepositedContinuous(who, val_to_add));
 			Ok(())
 		}
 
-		/// Withdraw some funds from the compounding interest account
-		#[weight = 10_000]
-		fn withdraw_continuous(origin, val_to_take: u64) -> DispatchResult {
-			ensure_signed(origin)?;
+		/// Withdraw some funds from the caller's compounding interest account
+		#[weight = T::WeightInfo::withdraw_continuous()]
+		fn withdraw_continuous(origin, val_to_take: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
 			let current_block = system::Module::<T>::block_number();
-			let old_value = Self::value_of_continuous_account(&current_block);
+			Self::accrue_interest(&current_block);
+			let old_value = Self::value_of_continuous_account(&who, &current_block)?;
+			let new_value = old_value.checked_sub(&val_to_take)
+				.ok_or(Error::<T>::InsufficientSavings)?;
+
+			// Make sure the remaining savings still cover the account's debt
+			let debt = Self::value_of_borrow(&who, &current_block)?;
+			ensure!(
+				debt <= T::CollateralFactor::get() * new_value,
+				Error::<T>::InsufficientCollateral
+			);
+
+			// Pay the funds out of the pot
+			T::Currency::transfer(&Self::account_id(), &who, val_to_take, AllowDeath)
+				.map_err(|_| Error::<T>::InsufficientLiquidity)?;
 
 			// Update storage for compounding account
-			ContinuousAccount::<T>::put(
-				ContinuousAccountData {
-					principal: old_value - I32F32::from_num(val_to_take),
-					deposit_date: current_block,
-				}
-			);
+			if new_value.is_zero() {
+				ContinuousAccounts::<T>::remove(&who);
+			} else {
+				ContinuousAccounts::<T>::insert(
+					&who,
+					ContinuousAccountData {
+						principal: new_value,
+						supply_index: Self::supply_index(),
+					}
+				);
+			}
+			TotalSupplied::<T>::mutate(|total| *total = total.saturating_sub(val_to_take));
 
 			// Emit event
-			Self::deposit_event(Event::WithdrewContinuous(val_to_take));
+			Self::deposit_event(RawEvent::WithdrewContinuous(who, val_to_take));
 			Ok(())
 		}
 
-		/// Deposit some funds into the discrete interest account
-		#[weight = 10_000]
-		fn deposit_discrete(origin, val_to_add: u64) -> DispatchResult {
-			ensure_signed(origin)?;
+		/// Deposit some funds into the caller's discrete interest account
+		#[weight = T::WeightInfo::deposit_discrete()]
+		fn deposit_discrete(origin, val_to_add: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			let old_value = DiscreteAccount::get();
+			let current_block = system::Module::<T>::block_number();
+			Self::accrue_interest(&current_block);
+			let (old_value, interest, shortfall) =
+				Self::discrete_value_and_interest(&who, &current_block)?;
+			let new_value = old_value.checked_add(&val_to_add).ok_or(Error::<T>::Overflow)?;
+			let total_principal = Self::total_discrete_principal()
+				.checked_add(&interest)
+				.and_then(|total| total.checked_add(&val_to_add))
+				.ok_or(Error::<T>::Overflow)?;
+
+			// Move the funds into the discrete reserve
+			T::Currency::transfer(&who, &Self::discrete_reserve_id(), val_to_add, AllowDeath)?;
 
 			// Update storage for discrete account
-			DiscreteAccount::put(old_value + val_to_add);
+			DiscreteAccounts::<T>::insert(
+				&who,
+				DiscreteAccountData {
+					principal: new_value,
+					discrete_index: Self::discrete_index(),
+				}
+			);
+			TotalDiscretePrincipal::<T>::put(total_principal);
 
-			// Emit event
-			Self::deposit_event(Event::DepositedDiscrete(val_to_add));
+			// Emit events
+			Self::note_discrete_interest(&who, interest, shortfall);
+			Self::deposit_event(RawEvent::DepositedDiscrete(who, val_to_add));
 			Ok(())
 		}
 
-		/// Withdraw some funds from the discrete interest account
-		#[weight = 10_000]
-		fn withdraw_discrete(origin, val_to_take: u64) -> DispatchResult {
-			ensure_signed(origin)?;
+		/// Withdraw some funds from the caller's discrete interest account
+		#[weight = T::WeightInfo::withdraw_discrete()]
+		fn withdraw_discrete(origin, val_to_take: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-			let old_value = DiscreteAccount::get();
+			let current_block = system::Module::<T>::block_number();
+			Self::accrue_interest(&current_block);
+			let (old_value, interest, shortfall) =
+				Self::discrete_value_and_interest(&who, &current_block)?;
+			let new_value = old_value.checked_sub(&val_to_take)
+				.ok_or(Error::<T>::InsufficientSavings)?;
+
+			// Pay the funds out of the discrete reserve
+			T::Currency::transfer(&Self::discrete_reserve_id(), &who, val_to_take, AllowDeath)
+				.map_err(|_| Error::<T>::InsufficientLiquidity)?;
 
 			// Update storage for discrete account
-			DiscreteAccount::put(old_value - val_to_take);
+			if new_value.is_zero() {
+				DiscreteAccounts::<T>::remove(&who);
+			} else {
+				DiscreteAccounts::<T>::insert(
+					&who,
+					DiscreteAccountData {
+						principal: new_value,
+						discrete_index: Self::discrete_index(),
+					}
+				);
+			}
+			TotalDiscretePrincipal::<T>::mutate(|total| {
+				*total = total.saturating_add(interest).saturating_sub(val_to_take)
+			});
 
-			// Emit event
-			Self::deposit_event(Event::WithdrewDiscrete(val_to_take));
+			// Emit events
+			Self::note_discrete_interest(&who, interest, shortfall);
+			Self::deposit_event(RawEvent::WithdrewDiscrete(who, val_to_take));
 			Ok(())
 		}
 
-		fn on_finalize(n: T::BlockNumber) {
-			// Apply newly-accrued discrete interest every ten blocks
-			if (n % 10.into()).is_zero() {
+		/// Borrow some funds from the pot against the caller's continuous savings
+		#[weight = T::WeightInfo::borrow()]
+		fn borrow(origin, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
 
-				// Calculate interest Interest = principal * rate * time
-				// We can use the `*` operator for multiplying a `Percent` by a u64
-				// because `Percent` implements the trait Mul<u64>
-				let interest = Self::discrete_interest_rate() * DiscreteAccount::get() * 10;
+			let current_block = system::Module::<T>::block_number();
+			Self::accrue_interest(&current_block);
+			let old_debt = Self::value_of_borrow(&who, &current_block)?;
+			let new_debt = old_debt.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+			let total_borrowed = Self::total_borrowed()
+				.checked_add(&amount)
+				.ok_or(Error::<T>::Overflow)?;
+
+			// Make sure the account's savings cover the new debt
+			let savings = Self::value_of_continuous_account(&who, &current_block)?;
+			ensure!(
+				new_debt <= T::CollateralFactor::get() * savings,
+				Error::<T>::InsufficientCollateral
+			);
 
-				// The following line, although similar, does not work because
-				// u64 does not implement the trait Mul<Percent>
-				// let interest = DiscreteAccount::get() * Self::discrete_interest_rate() * 10;
+			// Pay the funds out of the pot
+			T::Currency::transfer(&Self::account_id(), &who, amount, AllowDeath)
+				.map_err(|_| Error::<T>::InsufficientLiquidity)?;
 
-				// Update the balance
-				let old_balance = DiscreteAccount::get();
-				DiscreteAccount::put(old_balance + interest);
+			// Update storage for the debt
+			Borrows::<T>::insert(
+				&who,
+				BorrowData {
+					principal: new_debt,
+					borrow_index: Self::borrow_index(),
+				}
+			);
+			TotalBorrowed::<T>::put(total_borrowed);
 
-				// Emit the event
-				Self::deposit_event(Event::DiscreteInterestApplied(interest));
+			Self::deposit_event(RawEvent::Borrowed(who, amount));
+			Ok(())
+		}
+
+		/// Repay some of the caller's debt. Offering more than is owed only repays the debt.
+		#[weight = T::WeightInfo::repay()]
+		fn repay(origin, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let current_block = system::Module::<T>::block_number();
+			Self::accrue_interest(&current_block);
+			let old_debt = Self::value_of_borrow(&who, &current_block)?;
+			ensure!(!old_debt.is_zero(), Error::<T>::NoDebt);
+			let repayment = amount.min(old_debt);
+			let new_debt = old_debt - repayment;
+
+			// Move the funds into the pot
+			T::Currency::transfer(&who, &Self::account_id(), repayment, AllowDeath)?;
+
+			// Update storage for the debt
+			if new_debt.is_zero() {
+				Borrows::<T>::remove(&who);
+			} else {
+				Borrows::<T>::insert(
+					&who,
+					BorrowData {
+						principal: new_debt,
+						borrow_index: Self::borrow_index(),
+					}
+				);
 			}
+			TotalBorrowed::<T>::mutate(|total| *total = total.saturating_sub(repayment));
+
+			Self::deposit_event(RawEvent::Repaid(who, repayment));
+			Ok(())
+		}
+
+		/// Set the interest rate paid to discrete accounts
+		///
+		/// Interest accrued before this call is paid at the old rate.
+		#[weight = T::WeightInfo::set_discrete_interest_rate()]
+		fn set_discrete_interest_rate(origin, rate: Permill) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let current_block = system::Module::<T>::block_number();
+			Self::accrue_interest(&current_block);
+			DiscreteRate::put(rate);
+
+			Self::deposit_event(RawEvent::DiscreteInterestRateSet(rate));
+			Ok(())
+		}
+
+		/// Set the model that determines the continuous interest rates
+		///
+		/// Interest accrued before this call is paid at the old rates.
+		#[weight = T::WeightInfo::set_interest_rate_model()]
+		fn set_interest_rate_model(origin, model: InterestRateModel) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let current_block = system::Module::<T>::block_number();
+			Self::accrue_interest(&current_block);
+			RateModel::put(model);
+
+			Self::deposit_event(RawEvent::InterestRateModelSet(model));
+			Ok(())
 		}
 	}
 }
 
 impl<T: Trait> Module<T> {
-	/// A helper function to evaluate the current value of the continuously compounding interest
+	/// The account ID of the pot that holds the continuous savings
+	pub fn account_id() -> T::AccountId {
+		PALLET_ID.into_account()
+	}
+
+	/// The account ID of the reserve that holds the discrete savings and pays their interest
+	pub fn discrete_reserve_id() -> T::AccountId {
+		DISCRETE_RESERVE_ID.into_account()
+	}
+
+	/// The funds in the discrete reserve beyond the savings it holds, which pay discrete interest
+	pub fn discrete_interest_available() -> BalanceOf<T> {
+		T::Currency::free_balance(&Self::discrete_reserve_id())
+			.saturating_sub(Self::total_discrete_principal())
+	}
+
+	/// The interest rate currently paid to discrete accounts, per block
+	pub fn discrete_interest_rate() -> Permill {
+		DiscreteRate::get().unwrap_or_else(T::DefaultDiscreteInterestRate::get)
+	}
+
+	/// The model currently used to determine continuous interest rates
+	pub fn interest_rate_model() -> InterestRateModel {
+		RateModel::get().unwrap_or_else(T::DefaultInterestRateModel::get)
+	}
+
+	/// The portion of the supplied funds that is currently borrowed
+	pub fn utilization() -> Permill {
+		Permill::from_rational_approximation(Self::total_borrowed(), Self::total_supplied())
+	}
+
+	/// A helper function to evaluate the current value of a continuously compounding interest
 	/// account
-	fn value_of_continuous_account(now: &<T as system::Trait>::BlockNumber) -> I32F32 {
+	pub fn value_of_continuous_account(
+		who: &T::AccountId,
+		now: &<T as system::Trait>::BlockNumber,
+	) -> Result<BalanceOf<T>, Error<T>> {
 		// Get the old state of the accout
 		let ContinuousAccountData {
 			principal,
-			deposit_date,
-		} = ContinuousAccount::<T>::get();
+			supply_index,
+		} = ContinuousAccounts::<T>::get(who);
+
+		if principal.is_zero() {
+			return Ok(principal);
+		}
+
+		// Return the result interest = principal * current index / index at deposit
+		let (current_index, _) = Self::pending_continuous_indices(now);
+		let growth = current_index.checked_div(supply_index).ok_or(Error::<T>::Overflow)?;
+		Self::apply_growth(principal, growth)
+	}
+
+	/// A helper function to evaluate the current debt of a borrower
+	pub fn value_of_borrow(
+		who: &T::AccountId,
+		now: &<T as system::Trait>::BlockNumber,
+	) -> Result<BalanceOf<T>, Error<T>> {
+		let BorrowData {
+			principal,
+			borrow_index,
+		} = Borrows::<T>::get(who);
+
+		if principal.is_zero() {
+			return Ok(principal);
+		}
+
+		let (_, current_index) = Self::pending_continuous_indices(now);
+		let growth = current_index.checked_div(borrow_index).ok_or(Error::<T>::Overflow)?;
+		Self::apply_growth(principal, growth)
+	}
+
+	/// A helper function to evaluate the current value of a discrete interest account
+	///
+	/// Interest is paid once at every block that is a multiple of ten. Each payment is
+	/// `principal * rate * 10` which compounds to `principal * (1 + rate * 10) ^ periods`.
+	/// This is the value the account is owed, before it is limited by the discrete reserve.
+	pub fn value_of_discrete_account(
+		who: &T::AccountId,
+		now: &<T as system::Trait>::BlockNumber,
+	) -> Result<BalanceOf<T>, Error<T>> {
+		let DiscreteAccountData {
+			principal,
+			discrete_index,
+		} = DiscreteAccounts::<T>::get(who);
+
+		if principal.is_zero() {
+			return Ok(principal);
+		}
+
+		let current_index = Self::pending_discrete_index(now);
+		let growth = current_index.checked_div(discrete_index).ok_or(Error::<T>::Overflow)?;
+		Self::apply_growth(principal, growth)
+	}
+
+	/// A helper function to evaluate the current value of a discrete interest account along with
+	/// the interest paid into it since it was last touched, and the interest it was owed that the
+	/// discrete reserve could not pay
+	fn discrete_value_and_interest(
+		who: &T::AccountId,
+		now: &<T as system::Trait>::BlockNumber,
+	) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), Error<T>> {
+		let old_principal = DiscreteAccounts::<T>::get(who).principal;
+		// A value too large to represent is owed more than any reserve can pay anyway
+		let owed = Self::value_of_discrete_account(who, now)
+			.unwrap_or_else(|_| BalanceOf::<T>::max_value())
+			.saturating_sub(old_principal);
+		let paid = owed.min(Self::discrete_interest_available());
+		let current_value = old_principal.checked_add(&paid).ok_or(Error::<T>::Overflow)?;
+
+		Ok((current_value, paid, owed.saturating_sub(paid)))
+	}
+
+	/// Emit the events for the interest paid into a discrete account, and any that went unpaid
+	fn note_discrete_interest(who: &T::AccountId, paid: BalanceOf<T>, shortfall: BalanceOf<T>) {
+		if !paid.is_zero() {
+			Self::deposit_event(RawEvent::DiscreteInterestApplied(who.clone(), paid));
+		}
+		if !shortfall.is_zero() {
+			Self::deposit_event(RawEvent::DiscreteInterestShortfall(who.clone(), shortfall));
+		}
+	}
+
+	/// Bring the global indices and totals up to date with the current rates
+	///
+	/// This must be called before anything that changes the rates, so that interest accrued
+	/// until now is paid at the rates that were in effect. It never fails, so the rates can always
+	/// be changed.
+	fn accrue_interest(now: &<T as system::Trait>::BlockNumber) {
+		// Update the discrete index
+		let period: T::BlockNumber = DISCRETE_PERIOD.into();
+		let periods_elapsed = *now / period;
+		if periods_elapsed > Self::discrete_periods_accrued()
+			|| Self::total_discrete_principal().is_zero()
+		{
+			DiscreteIndex::put(Self::pending_discrete_index(now));
+			DiscretePeriodsAccrued::<T>::put(periods_elapsed);
+		}
 
-		// Calculate the exponential function (lots of type conversion)
-		let elapsed_time_block_number = *now - deposit_date;
-		let elapsed_time_u32 = TryInto::try_into(elapsed_time_block_number)
+		// Update the continuous indices, and grow the totals along with them
+		if *now <= Self::last_accrual() {
+			return;
+		}
+
+		let old_supply_index = Self::supply_index();
+		let old_borrow_index = Self::borrow_index();
+		let (supply_index, borrow_index) = Self::pending_continuous_indices(now);
+
+		let total_supplied = Self::saturating_growth(
+			Self::total_supplied(),
+			supply_index.checked_div(old_supply_index),
+		);
+		let total_borrowed = Self::saturating_growth(
+			Self::total_borrowed(),
+			borrow_index.checked_div(old_borrow_index),
+		);
+
+		SupplyIndex::put(supply_index);
+		BorrowIndex::put(borrow_index);
+		TotalSupplied::<T>::put(total_supplied);
+		TotalBorrowed::<T>::put(total_borrowed);
+		LastAccrual::<T>::put(*now);
+	}
+
+	/// Calculate the supply and borrow indices as of `now` without writing them to storage
+	///
+	/// The rates stay constant between accruals, so the indices grow by `e ^ (rate * time)`.
+	fn pending_continuous_indices(now: &<T as system::Trait>::BlockNumber) -> (I64F64, I64F64) {
+		let supply_index = Self::supply_index();
+		let borrow_index = Self::borrow_index();
+
+		// Calculate the elapsed time (lots of type conversion)
+		let elapsed_time_block_number = now.saturating_sub(Self::last_accrual());
+		if elapsed_time_block_number.is_zero() {
+			return (supply_index, borrow_index);
+		}
+		let elapsed_time_u32: u32 =
+			TryInto::try_into(elapsed_time_block_number).unwrap_or_else(|_| u32::max_value());
+
+		let model = Self::interest_rate_model();
+		let utilization = Self::utilization();
+
+		// Nobody owes or earns interest in an empty market, so leave the indices alone. This also
+		// keeps a long idle period from overflowing the exponential function.
+		let supply_index = if Self::total_supplied().is_zero() {
+			supply_index
+		} else {
+			let rate = model.supply_rate(utilization);
+			Self::grow_continuous_index(supply_index, rate, elapsed_time_u32)
+		};
+		let borrow_index = if Self::total_borrowed().is_zero() {
+			borrow_index
+		} else {
+			let rate = model.borrow_rate(utilization);
+			Self::grow_continuous_index(borrow_index, rate, elapsed_time_u32)
+		};
+
+		(supply_index, borrow_index)
+	}
+
+	/// Calculate the discrete index as of `now` without writing it to storage
+	fn pending_discrete_index(now: &<T as system::Trait>::BlockNumber) -> I64F64 {
+		// Nobody earns interest while there are no discrete savings, so the index starts over. No
+		// account recorded the old index, as only accounts holding savings are stored. Starting
+		// over keeps long idle periods from growing the index towards its limit.
+		if Self::total_discrete_principal().is_zero() {
+			return I64F64::from_num(1);
+		}
+
+		// Count how many multiples of ten we have passed since the last accrual
+		let period: T::BlockNumber = DISCRETE_PERIOD.into();
+		let elapsed_periods = (*now / period).saturating_sub(Self::discrete_periods_accrued());
+		if elapsed_periods.is_zero() {
+			return Self::discrete_index();
+		}
+		let elapsed_periods_u32: u32 =
+			TryInto::try_into(elapsed_periods).unwrap_or_else(|_| u32::max_value());
+
+		// Permill represents parts per million, so we divide by a million to express the
+		// same rate in the fancy substrate-fixed format. Scaling the rate to a whole period
+		// before dividing keeps whole percentages exact.
+		let parts = u64::from(Self::discrete_interest_rate().deconstruct())
+			* u64::from(DISCRETE_PERIOD);
+		let rate_per_period = I32F32::from_num(parts) / 1_000_000;
+
+		// The index stops growing once it reaches the largest value it can hold
+		fixed_math::pow(I32F32::from_num(1) + rate_per_period, elapsed_periods_u32)
 			.ok()
-			.expect("blockchain will not exceed 2^32 blocks; qed");
-		let elapsed_time_i32f32 = I32F32::from_num(elapsed_time_u32);
-		let exponent: I32F32 = Self::continuous_interest_rate() * elapsed_time_i32f32;
-		let exp_result : I32F32 = exp(exponent)
-			.expect("Interest will not overflow account (at least not until the learner has learned enough about fixed point :)");
+			.and_then(|growth| Self::discrete_index().checked_mul(I64F64::from_num(growth)))
+			.unwrap_or_else(I64F64::max_value)
+	}
+
+	/// Multiply a continuous index by `e ^ (rate * time)` for a per-block rate. The index stops
+	/// growing once it reaches the largest value it can hold.
+	fn grow_continuous_index(index: I64F64, rate: Permill, blocks: u32) -> I64F64 {
+		Self::continuous_growth(rate, blocks)
+			.ok()
+			.and_then(|growth| index.checked_mul(growth))
+			.unwrap_or_else(I64F64::max_value)
+	}
+
+	/// Calculate `e ^ (rate * time)` for a per-block rate
+	fn continuous_growth(rate: Permill, blocks: u32) -> Result<I64F64, Error<T>> {
+		let rate_i32f32 = I32F32::from_num(rate.deconstruct()) / 1_000_000;
+		let exponent = rate_i32f32
+			.checked_mul(I32F32::from_num(blocks))
+			.ok_or(Error::<T>::Overflow)?;
+		let exp_result = fixed_math::exp(exponent).map_err(|_| Error::<T>::Overflow)?;
 
-		// Return the result interest = principal * e ^ (rate * time)
-		principal * exp_result
+		Ok(I64F64::from_num(exp_result))
 	}
 
-	/// A helper function to return the hard-coded 5% interest rate
-	fn discrete_interest_rate() -> Percent {
-		Percent::from_percent(5)
+	/// Multiply a total by a growth factor, saturating when either is too large to represent
+	fn saturating_growth(total: BalanceOf<T>, growth: Option<I64F64>) -> BalanceOf<T> {
+		growth
+			.and_then(|growth| Self::apply_growth(total, growth).ok())
+			.unwrap_or_else(BalanceOf::<T>::max_value)
 	}
 
-	/// A helper function to return the hard-coded 5% interest rate
-	fn continuous_interest_rate() -> I32F32 {
-		// 1 / 20 is 5%. Same interest rate as the discrete account, but in the
-		// fancy substrate-fixed format. This I32F32 type represents a 32 bit
-		// signed integer where all 32 bits are fractional.
-		I32F32::from_num(1) / 20
+	/// Multiply a balance by a non-negative growth factor, rounding down
+	fn apply_growth(principal: BalanceOf<T>, growth: I64F64) -> Result<BalanceOf<T>, Error<T>> {
+		// The growth factor has 64 fractional bits. We multiply the balance by the integer and
+		// fractional parts separately, so that the intermediate products fit in a u128.
+		let growth_bits: u128 = TryInto::try_into(growth.to_bits())
+			.map_err(|_| Error::<T>::Overflow)?;
+		let whole = growth_bits >> 64;
+		let fraction = growth_bits & u128::from(u64::max_value());
+
+		let principal_u128: u128 = principal.unique_saturated_into();
+		let whole_product = principal_u128.checked_mul(whole).ok_or(Error::<T>::Overflow)?;
+		let fraction_product = principal_u128.checked_mul(fraction).ok_or(Error::<T>::Overflow)?;
+		let value = whole_product
+			.checked_add(fraction_product >> 64)
+			.ok_or(Error::<T>::Overflow)?;
+
+		BalanceOf::<T>::try_from(value).map_err(|_| Error::<T>::Overflow)
 	}
 }
@@ -1,9 +1,11 @@
-use super::Event;
-use crate::{Module, Trait};
+use crate::{Error, InterestRateModel, Module, RawEvent, Trait};
+use balances;
 use frame_support::{
-	assert_ok, impl_outer_event, impl_outer_origin, parameter_types, traits::OnFinalize,
+	assert_err, assert_noop, assert_ok, dispatch::DispatchError, impl_outer_event, impl_outer_origin,
+	parameter_types,
 };
-use frame_system::{self as system, EventRecord, Phase};
+use frame_system::{self as system, RawOrigin};
+use sp_arithmetic::Permill;
 use sp_core::H256;
 use sp_io::TestExternalities;
 use sp_runtime::{
@@ -24,6 +26,8 @@ parameter_types! {
 	pub const MaximumBlockWeight: u32 = 1024;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 1;
 }
 impl system::Trait for TestRuntime {
 	type Origin = Origin;
@@ -46,70 +50,127 @@ impl system::Trait for TestRuntime {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type ModuleToIndex = ();
-	type AccountData = ();
+	type AccountData = balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 }
 
-mod fixed_point {
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<TestRuntime>;
+}
+
+mod compounding_interest {
 	pub use crate::Event;
 }
 
 impl_outer_event! {
 	pub enum TestEvent for TestRuntime {
-		fixed_point,
+		compounding_interest<T>,
 		system<T>,
+		balances<T>,
 	}
 }
 
+parameter_types! {
+	pub const DefaultDiscreteInterestRate: Permill = Permill::from_percent(5);
+	// A flat borrow rate keeps the expected values in these tests easy to calculate
+	pub const DefaultInterestRateModel: InterestRateModel = InterestRateModel {
+		base_rate: Permill::from_percent(5),
+		slope_low: Permill::from_percent(0),
+		slope_high: Permill::from_percent(0),
+		kink: Permill::from_percent(100),
+	};
+	pub const CollateralFactor: Permill = Permill::from_percent(50);
+}
+
 impl Trait for TestRuntime {
 	type Event = TestEvent;
+	type Currency = balances::Module<Self>;
+	type DefaultDiscreteInterestRate = DefaultDiscreteInterestRate;
+	type DefaultInterestRateModel = DefaultInterestRateModel;
+	type CollateralFactor = CollateralFactor;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
-pub type FixedPoint = Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
+pub type CompoundingInterest = Module<TestRuntime>;
 
 pub struct ExtBuilder;
 
 impl ExtBuilder {
 	pub fn build() -> TestExternalities {
-		let storage = system::GenesisConfig::default()
+		let mut storage = system::GenesisConfig::default()
 			.build_storage::<TestRuntime>()
 			.unwrap();
+		balances::GenesisConfig::<TestRuntime> {
+			// Provide some initial balances, some liquidity in the pot, and some funds in the
+			// discrete reserve to pay interest
+			balances: vec![
+				(1, 10000),
+				(2, 10000),
+				(CompoundingInterest::account_id(), 1000),
+				(CompoundingInterest::discrete_reserve_id(), 1000),
+			],
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
 		let mut ext = TestExternalities::from(storage);
 		ext.execute_with(|| System::set_block_number(1));
 		ext
 	}
 }
 
+fn event_deposited(event: RawEvent<u64, u64>) -> bool {
+	let expected = TestEvent::compounding_interest(event);
+	System::events().iter().any(|a| a.event == expected)
+}
+
 #[test]
 fn deposit_withdraw_discrete_works() {
 	ExtBuilder::build().execute_with(|| {
 		// Deposit 10 tokens
-		assert_ok!(FixedPoint::deposit_discrete(Origin::signed(1), 10));
+		assert_ok!(CompoundingInterest::deposit_discrete(Origin::signed(1), 10));
+		assert!(event_deposited(RawEvent::DepositedDiscrete(1, 10)));
 
 		// Withdraw 5 tokens
-		assert_ok!(FixedPoint::withdraw_discrete(Origin::signed(1), 5));
+		assert_ok!(CompoundingInterest::withdraw_discrete(Origin::signed(1), 5));
+		assert!(event_deposited(RawEvent::WithdrewDiscrete(1, 5)));
 
-		// Check for the correct event
-		assert_eq!(
-			System::events(),
-			vec![
-				EventRecord {
-					phase: Phase::Initialization,
-					event: TestEvent::fixed_point(Event::DepositedDiscrete(10,)),
-					topics: vec![],
-				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: TestEvent::fixed_point(Event::WithdrewDiscrete(5,)),
-					topics: vec![],
-				},
-			]
+		// Check that five tokens are still there, and that they are held by the discrete reserve
+		assert_eq!(CompoundingInterest::discrete_account(1).principal, 5);
+		assert_eq!(Balances::free_balance(&1), 9995);
+		assert_eq!(Balances::free_balance(&CompoundingInterest::discrete_reserve_id()), 1005);
+		assert_eq!(Balances::free_balance(&CompoundingInterest::account_id()), 1000);
+	})
+}
+
+#[test]
+fn accounts_are_independent() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(CompoundingInterest::deposit_discrete(Origin::signed(1), 10));
+		assert_ok!(CompoundingInterest::deposit_discrete(Origin::signed(2), 20));
+
+		assert_eq!(CompoundingInterest::discrete_account(1).principal, 10);
+		assert_eq!(CompoundingInterest::discrete_account(2).principal, 20);
+
+		// Account 1 cannot dip into account 2's savings
+		assert_noop!(
+			CompoundingInterest::withdraw_discrete(Origin::signed(1), 11),
+			Error::<TestRuntime>::InsufficientSavings
 		);
+	})
+}
 
-		// Check that five tokens are still there
-		assert_eq!(FixedPoint::discrete_account(), 5);
+#[test]
+fn cannot_deposit_more_than_free_balance() {
+	ExtBuilder::build().execute_with(|| {
+		assert!(CompoundingInterest::deposit_continuous(Origin::signed(1), 10001).is_err());
+		assert_eq!(CompoundingInterest::continuous_account(1).principal, 0);
 	})
 }
 
@@ -117,33 +178,244 @@ fn deposit_withdraw_discrete_works() {
 fn discrete_interest_works() {
 	ExtBuilder::build().execute_with(|| {
 		// Deposit 100 tokens
-		assert_ok!(FixedPoint::deposit_discrete(Origin::signed(1), 100));
+		assert_ok!(CompoundingInterest::deposit_discrete(Origin::signed(1), 100));
+
+		// balance should not change before the 10th block
+		assert_eq!(CompoundingInterest::value_of_discrete_account(&1, &9).ok(), Some(100));
+
+		// interest is paid at the 10th block
+		assert_eq!(CompoundingInterest::value_of_discrete_account(&1, &10).ok(), Some(150));
 
-		// balance should not change after the 3rd block
-		FixedPoint::on_finalize(3);
-		assert_eq!(FixedPoint::discrete_account(), 100);
+		// and compounds at the 20th block
+		assert_eq!(CompoundingInterest::value_of_discrete_account(&1, &20).ok(), Some(225));
 
-		// on_finalize should compute interest on 10th block
-		FixedPoint::on_finalize(10);
+		// Withdraw everything at the 10th block
+		System::set_block_number(10);
+		assert_ok!(CompoundingInterest::withdraw_discrete(Origin::signed(1), 150));
 
 		// Check for the correct event
-		assert_eq!(
-			System::events(),
-			vec![
-				EventRecord {
-					phase: Phase::Initialization,
-					event: TestEvent::fixed_point(Event::DepositedDiscrete(100,)),
-					topics: vec![],
-				},
-				EventRecord {
-					phase: Phase::Initialization,
-					event: TestEvent::fixed_point(Event::DiscreteInterestApplied(50,)),
-					topics: vec![],
-				},
-			]
-		);
+		assert!(event_deposited(RawEvent::DiscreteInterestApplied(1, 50)));
 
 		// Check that the balance has updated
-		assert_eq!(FixedPoint::discrete_account(), 150);
+		assert_eq!(CompoundingInterest::discrete_account(1).principal, 0);
+		assert_eq!(Balances::free_balance(&1), 10050);
+	})
+}
+
+#[test]
+fn continuous_account_without_borrowers_earns_nothing() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(CompoundingInterest::deposit_continuous(Origin::signed(1), 100));
+
+		// Suppliers only share the interest paid by borrowers
+		assert_eq!(CompoundingInterest::value_of_continuous_account(&1, &21).ok(), Some(100));
+	})
+}
+
+#[test]
+fn borrowing_requires_collateral() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(CompoundingInterest::deposit_continuous(Origin::signed(2), 200));
+
+		// Half of the savings may be borrowed
+		assert_noop!(
+			CompoundingInterest::borrow(Origin::signed(2), 101),
+			Error::<TestRuntime>::InsufficientCollateral
+		);
+		assert_ok!(CompoundingInterest::borrow(Origin::signed(2), 100));
+		assert!(event_deposited(RawEvent::Borrowed(2, 100)));
+		assert_eq!(Balances::free_balance(&2), 9900);
+
+		// The savings that back the debt cannot be withdrawn
+		assert_noop!(
+			CompoundingInterest::withdraw_continuous(Origin::signed(2), 1),
+			Error::<TestRuntime>::InsufficientCollateral
+		);
 	})
 }
+
+#[test]
+fn borrowers_pay_suppliers_continuously() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(CompoundingInterest::deposit_continuous(Origin::signed(2), 200));
+		assert_ok!(CompoundingInterest::borrow(Origin::signed(2), 100));
+		assert_eq!(CompoundingInterest::utilization(), Permill::from_percent(50));
+
+		// 100 * e ^ (0.05 * 20) = 271.8...
+		assert_eq!(CompoundingInterest::value_of_borrow(&2, &21).ok(), Some(271));
+
+		// Suppliers earn the borrow rate on the utilized half of their savings
+		// 200 * e ^ (0.025 * 20) = 329.7...
+		assert_eq!(CompoundingInterest::value_of_continuous_account(&2, &21).ok(), Some(329));
+	})
+}
+
+#[test]
+fn repay_works() {
+	ExtBuilder::build().execute_with(|| {
+		assert_noop!(
+			CompoundingInterest::repay(Origin::signed(2), 100),
+			Error::<TestRuntime>::NoDebt
+		);
+
+		assert_ok!(CompoundingInterest::deposit_continuous(Origin::signed(2), 200));
+		assert_ok!(CompoundingInterest::borrow(Origin::signed(2), 100));
+
+		// Offering more than is owed only repays the debt
+		System::set_block_number(21);
+		assert_ok!(CompoundingInterest::repay(Origin::signed(2), 1000));
+		assert!(event_deposited(RawEvent::Repaid(2, 271)));
+		assert_eq!(Balances::free_balance(&2), 9629);
+		assert_eq!(CompoundingInterest::debt(2).principal, 0);
+		assert_eq!(CompoundingInterest::total_borrowed(), 0);
+
+		// Now the savings may be withdrawn along with the interest they earned
+		assert_ok!(CompoundingInterest::withdraw_continuous(Origin::signed(2), 329));
+		assert_eq!(Balances::free_balance(&2), 9958);
+	})
+}
+
+#[test]
+fn discrete_interest_is_limited_by_the_reserve() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(CompoundingInterest::deposit_discrete(Origin::signed(1), 100));
+
+		// Leave the reserve with nothing beyond the savings it holds
+		System::set_block_number(10);
+		Balances::make_free_balance_be(&CompoundingInterest::discrete_reserve_id(), 100);
+		assert_eq!(CompoundingInterest::discrete_interest_available(), 0);
+
+		// The interest cannot be paid, so only the savings may be withdrawn. Bringing the market
+		// up to date is not undone by the failure, so this is not a noop.
+		assert_err!(
+			CompoundingInterest::withdraw_discrete(Origin::signed(1), 150),
+			Error::<TestRuntime>::InsufficientSavings
+		);
+		assert_ok!(CompoundingInterest::withdraw_discrete(Origin::signed(1), 100));
+		assert!(event_deposited(RawEvent::DiscreteInterestShortfall(1, 50)));
+		assert_eq!(Balances::free_balance(&1), 10000);
+		assert_eq!(CompoundingInterest::total_discrete_principal(), 0);
+	})
+}
+
+#[test]
+fn discrete_interest_does_not_touch_continuous_savings() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(CompoundingInterest::deposit_continuous(Origin::signed(2), 100));
+		assert_ok!(CompoundingInterest::deposit_discrete(Origin::signed(1), 100));
+
+		System::set_block_number(10);
+		assert_ok!(CompoundingInterest::withdraw_discrete(Origin::signed(1), 150));
+		assert_eq!(Balances::free_balance(&CompoundingInterest::discrete_reserve_id()), 950);
+		assert_eq!(Balances::free_balance(&CompoundingInterest::account_id()), 1100);
+	})
+}
+
+#[test]
+fn idle_periods_do_not_grow_the_discrete_index() {
+	ExtBuilder::build().execute_with(|| {
+		// Nobody holds discrete savings for a hundred periods
+		System::set_block_number(1000);
+		assert_ok!(CompoundingInterest::deposit_discrete(Origin::signed(1), 100));
+		assert_eq!(CompoundingInterest::discrete_index(), 1);
+
+		// Interest is only paid for the periods after the deposit
+		assert_eq!(CompoundingInterest::value_of_discrete_account(&1, &1010).ok(), Some(150));
+	})
+}
+
+#[test]
+fn rates_can_be_set_after_the_index_saturates() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(CompoundingInterest::deposit_discrete(Origin::signed(1), 100));
+
+		// A thousand periods at 50% each grows the index past the largest value it can hold
+		System::set_block_number(10_000);
+		assert_ok!(CompoundingInterest::set_discrete_interest_rate(
+			RawOrigin::Root.into(),
+			Permill::from_percent(1)
+		));
+		assert_ok!(CompoundingInterest::set_interest_rate_model(
+			RawOrigin::Root.into(),
+			InterestRateModel::default()
+		));
+
+		// The account is paid all the reserve holds, and can still withdraw its savings
+		assert_ok!(CompoundingInterest::withdraw_discrete(Origin::signed(1), 100));
+		assert!(event_deposited(RawEvent::DiscreteInterestApplied(1, 1000)));
+		assert_eq!(CompoundingInterest::discrete_account(1).principal, 1000);
+	})
+}
+
+#[test]
+fn only_root_sets_rates() {
+	ExtBuilder::build().execute_with(|| {
+		assert_noop!(
+			CompoundingInterest::set_discrete_interest_rate(
+				Origin::signed(1),
+				Permill::from_percent(10)
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			CompoundingInterest::set_interest_rate_model(
+				Origin::signed(1),
+				InterestRateModel::default()
+			),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(CompoundingInterest::set_discrete_interest_rate(
+			RawOrigin::Root.into(),
+			Permill::from_percent(10)
+		));
+		assert_eq!(CompoundingInterest::discrete_interest_rate(), Permill::from_percent(10));
+		assert!(event_deposited(RawEvent::DiscreteInterestRateSet(Permill::from_percent(10))));
+
+		assert_ok!(CompoundingInterest::set_interest_rate_model(
+			RawOrigin::Root.into(),
+			InterestRateModel::default()
+		));
+		assert_eq!(CompoundingInterest::interest_rate_model(), InterestRateModel::default());
+	})
+}
+
+#[test]
+fn rate_changes_do_not_apply_retroactively() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(CompoundingInterest::deposit_discrete(Origin::signed(1), 100));
+
+		// One period at 5% per block
+		System::set_block_number(10);
+		assert_ok!(CompoundingInterest::set_discrete_interest_rate(
+			RawOrigin::Root.into(),
+			Permill::from_percent(10)
+		));
+		assert_eq!(CompoundingInterest::value_of_discrete_account(&1, &10).ok(), Some(150));
+
+		// One more period at 10% per block
+		assert_eq!(CompoundingInterest::value_of_discrete_account(&1, &20).ok(), Some(300));
+	})
+}
+
+#[test]
+fn borrow_rate_follows_kinked_curve() {
+	let model = InterestRateModel {
+		base_rate: Permill::from_percent(1),
+		slope_low: Permill::from_percent(4),
+		slope_high: Permill::from_percent(50),
+		kink: Permill::from_percent(80),
+	};
+
+	// The base rate applies to an unused market
+	assert_eq!(model.borrow_rate(Permill::zero()), Permill::from_percent(1));
+	// The gentle slope applies up to the kink
+	assert_eq!(model.borrow_rate(Permill::from_percent(40)), Permill::from_percent(3));
+	assert_eq!(model.borrow_rate(Permill::from_percent(80)), Permill::from_percent(5));
+	// The steep slope applies after the kink
+	assert_eq!(model.borrow_rate(Permill::from_percent(90)), Permill::from_percent(30));
+	assert_eq!(model.borrow_rate(Permill::one()), Permill::from_percent(55));
+
+	// Suppliers earn the borrow rate on the utilized portion
+	assert_eq!(model.supply_rate(Permill::from_percent(40)), Permill::from_parts(12_000));
+}
@@ -0,0 +1,40 @@
+//! Benchmarks for the constant config pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	add_value {
+		let caller: T::AccountId = account("caller", 0, 0);
+		SingleValue::put(1);
+		let addend = T::MaxAddend::get();
+	}: _(RawOrigin::Signed(caller), addend)
+	verify {
+		assert_eq!(SingleValue::get(), addend + 1);
+	}
+
+	set_value {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller), 42)
+	verify {
+		assert_eq!(SingleValue::get(), 42);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_add_value::<TestRuntime>());
+			assert_ok!(test_benchmark_set_value::<TestRuntime>());
+		});
+	}
+}
@@ -17,8 +17,13 @@ use frame_support::{
 use frame_system::{self as system, ensure_signed};
 use sp_runtime::traits::Zero;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
@@ -28,6 +33,9 @@ pub trait Trait: system::Trait {
 
 	/// Frequency with which the stored value is deleted
 	type ClearFrequency: Get<Self::BlockNumber>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -55,7 +63,7 @@ decl_module! {
 		const ClearFrequency: T::BlockNumber = T::ClearFrequency::get();
 
 		/// Add to the stored value. The `val_to_add` parameter cannot exceed the specified manimum.
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::add_value()]
 		fn add_value(origin, val_to_add: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 			ensure!(val_to_add <= T::MaxAddend::get(), "value must be <= maximum add amount constant");
@@ -75,7 +83,7 @@ decl_module! {
 
 		/// For testing purposes
 		/// Sets the stored value to a given value
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::set_value()]
 		fn set_value(origin, value: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 			<SingleValue>::put(value);
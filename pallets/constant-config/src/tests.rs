@@ -70,6 +70,7 @@ impl Trait for TestRuntime {
 	type Event = TestEvent;
 	type MaxAddend = MaxAddend;
 	type ClearFrequency = ClearFrequency;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
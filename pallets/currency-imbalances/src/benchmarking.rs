@@ -0,0 +1,84 @@
+//! Benchmarks for the currency imbalances pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+use sp_std::vec;
+
+/// An account with reserved funds for a slash to hold
+fn offender<T: Trait>() -> Result<T::AccountId, &'static str> {
+	let offender: T::AccountId = account("offender", 0, 0);
+	T::Currency::make_free_balance_be(&offender, 1_000_000u32.into());
+	T::Currency::reserve(&offender, 1_000u32.into())?;
+	Ok(offender)
+}
+
+/// Reports a slash of `offender` and returns its id
+fn report_slash<T: Trait>(offender: T::AccountId) -> Result<SlashId, &'static str> {
+	let id = Module::<T>::next_slash_id();
+	Module::<T>::slash_funds(
+		T::SlashOrigin::successful_origin(),
+		offender,
+		100u32.into(),
+		Default::default(),
+	)?;
+	Ok(id)
+}
+
+benchmarks! {
+	_ { }
+
+	slash_funds {
+		let offender = offender::<T>()?;
+		let id = Module::<T>::next_slash_id();
+	}: _(T::SlashOrigin::successful_origin(), offender.clone(), 100u32.into(), Default::default())
+	verify {
+		assert!(Module::<T>::pending_slash(id).is_some());
+		assert_eq!(Module::<T>::held(&offender), 100u32.into());
+	}
+
+	cancel_slash {
+		let offender = offender::<T>()?;
+		let id = report_slash::<T>(offender)?;
+	}: _(T::CancelOrigin::successful_origin(), id)
+	verify {
+		assert!(Module::<T>::pending_slash(id).is_none());
+	}
+
+	reward_funds {
+		let beneficiary: T::AccountId = account("beneficiary", 0, 0);
+		let balance: BalanceOf<T> = 1_000u32.into();
+		T::Currency::make_free_balance_be(&beneficiary, balance);
+	}: _(T::RewardOrigin::successful_origin(), beneficiary.clone(), balance)
+	verify {
+		assert_eq!(T::Currency::free_balance(&beneficiary), balance * 2u32.into());
+	}
+
+	set_pool_shares {
+		let shares = vec![
+			(PoolDestination::Treasury, 1),
+			(PoolDestination::Author, 1),
+			(PoolDestination::Burn, 1),
+		];
+	}: _(RawOrigin::Root, shares.clone())
+	verify {
+		assert_eq!(Module::<T>::pool_shares(), shares);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_slash_funds::<TestRuntime>());
+			assert_ok!(test_benchmark_cancel_slash::<TestRuntime>());
+			assert_ok!(test_benchmark_reward_funds::<TestRuntime>());
+			assert_ok!(test_benchmark_set_pool_shares::<TestRuntime>());
+		});
+	}
+}
@@ -3,13 +3,45 @@
 //! A Pallet to demonstrate using currency imbalances
 //!
 //! WARNING: never use this code in production (for demonstration/teaching purposes only)
-//! it only checks for signed extrinsics to enable arbitrary minting/slashing!!!
+//!
+//! Rewards are minted by a configurable `RewardOrigin`. Slashes are reported by a configurable
+//! `SlashOrigin` along with a hash of the evidence, and are deferred for `SlashDeferDuration`
+//! blocks. During that time the `CancelOrigin` may cancel the slash, for example after an appeal.
+//! Slashes that are not cancelled are applied in `on_initialize`. While a slash is pending, the
+//! reserved funds it will take are held: the pallet unreserves them and locks them in the
+//! offender's free balance, so nothing else can unreserve and move them away. The lock still lets
+//! the offender pay transaction fees, and does not touch the rest of their free balance.
+//! At most `MaxSlashesPerBlock` slashes may be due at the same block.
+//!
+//! The `RewardPool` type is an `OnUnbalanced` handler that splits the funds of a negative
+//! imbalance, such as a slash, among several beneficiaries according to weights set by the root
+//! origin.
 
 use frame_support::{
-	decl_event, decl_module,
-	traits::{Currency, Imbalance, OnUnbalanced, ReservableCurrency},
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{
+		Currency, EnsureOrigin, Get, Imbalance, LockIdentifier, LockableCurrency, OnUnbalanced,
+		ReservableCurrency, WithdrawReason, WithdrawReasons,
+	},
+	weights::Weight,
 };
-use frame_system::{self as system, ensure_signed};
+use frame_system::{self as system, ensure_root};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{One, Saturating, Zero},
+	RuntimeDebug,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 // balance type using reservable currency type
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
@@ -17,19 +49,106 @@ type PositiveImbalanceOf<T> =
 	<<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::PositiveImbalance;
 type NegativeImbalanceOf<T> =
 	<<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+type PendingSlashOf<T> = PendingSlash<
+	<T as system::Trait>::AccountId,
+	BalanceOf<T>,
+	<T as system::Trait>::Hash,
+	<T as system::Trait>::BlockNumber,
+>;
+
+/// Pending slashes are identified by a sequential number
+pub type SlashId = u32;
+
+/// Locks the funds held for an offender's pending slashes
+const SLASH_ID: LockIdentifier = *b"slashing";
 
 pub trait Trait: system::Trait + Sized {
 	/// The overarching event type
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 
 	/// Currency type for this pallet.
-	type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
+	type Currency: Currency<Self::AccountId>
+		+ ReservableCurrency<Self::AccountId>
+		+ LockableCurrency<Self::AccountId>;
 
 	/// Handler for the unbalanced increment when rewarding (minting rewards)
 	type Reward: OnUnbalanced<PositiveImbalanceOf<Self>>;
 
 	/// Handler for the unbalanced decrement when slashing (burning collateral)
 	type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// The origin that may mint rewards
+	type RewardOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The origin that may report slashes
+	type SlashOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The origin that may cancel pending slashes
+	type CancelOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The number of blocks a slash waits before it is applied
+	type SlashDeferDuration: Get<Self::BlockNumber>;
+
+	/// The most slashes that may be due at any one block
+	type MaxSlashesPerBlock: Get<u32>;
+
+	/// Receives the reward pool's treasury share, for example `charity::Module`
+	type Treasury: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// Receives the reward pool's block author share
+	type Author: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+/// The beneficiaries among which the reward pool splits its funds
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum PoolDestination {
+	/// Paid to the `Treasury` handler
+	Treasury,
+	/// Paid to the `Author` handler
+	Author,
+	/// Destroyed, reducing the total issuance
+	Burn,
+}
+
+/// A slash that has been reported but not yet applied
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct PendingSlash<AccountId, Balance, Hash, BlockNumber> {
+	/// The account whose reserved funds will be slashed
+	pub offender: AccountId,
+	/// The amount to slash
+	pub amount: Balance,
+	/// The part of `amount` the offender had reserved when the slash was reported. It is moved
+	/// into their free balance and locked until the slash is applied or cancelled.
+	pub held: Balance,
+	/// A hash of the evidence that justifies the slash
+	pub evidence: Hash,
+	/// The block at whose start the slash is applied
+	pub execute_at: BlockNumber,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as CurrencyImbalances {
+		/// The id the next pending slash will receive
+		NextSlashId get(fn next_slash_id): SlashId;
+
+		/// Slashes that have been reported but neither applied nor cancelled
+		PendingSlashes get(fn pending_slash):
+			map hasher(twox_64_concat) SlashId => Option<PendingSlashOf<T>>;
+
+		/// The pending slashes due at each block
+		SlashQueue get(fn slash_queue):
+			map hasher(twox_64_concat) T::BlockNumber => Vec<SlashId>;
+
+		/// The funds held for each offender's pending slashes, which are unreserved and locked
+		Held get(fn held): map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+
+		/// How the reward pool splits its funds. Each destination receives its non-zero weight
+		/// divided by the total weight. With no shares set, the pool burns everything.
+		PoolShares get(fn pool_shares): Vec<(PoolDestination, u32)>;
+	}
 }
 
 decl_event!(
@@ -38,32 +157,126 @@ decl_event!(
 		AccountId = <T as system::Trait>::AccountId,
 		Balance = BalanceOf<T>,
 		BlockNumber = <T as system::Trait>::BlockNumber,
+		Hash = <T as system::Trait>::Hash,
 	{
 		SlashFunds(AccountId, Balance, BlockNumber),
 		RewardFunds(AccountId, Balance, BlockNumber),
+		/// A slash was reported (id, offender, amount, evidence, block at which it applies)
+		SlashReported(SlashId, AccountId, Balance, Hash, BlockNumber),
+		/// A pending slash was cancelled
+		SlashCancelled(SlashId),
+		/// The reward pool's shares were changed
+		PoolSharesSet(Vec<(PoolDestination, u32)>),
+		/// The reward pool paid some funds to a destination
+		PoolPaid(PoolDestination, Balance),
 	}
 );
 
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// No pending slash exists with the given id
+		UnknownSlash,
+		/// Each destination may only appear once in the reward pool's shares
+		DuplicateDestination,
+		/// Every reward pool weight must be non-zero, and they must add up to a total that fits in
+		/// a u32
+		InvalidWeights,
+		/// The block at which the slash would apply already has `MaxSlashesPerBlock` slashes due
+		TooManySlashes,
+	}
+}
+
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
-		/// Slashes the specified amount of funds from the specified account
-		#[weight = 10_000]
-		pub fn slash_funds(origin, to_punish: T::AccountId, collateral: BalanceOf<T>) {
-			let _ = ensure_signed(origin)?;
+		type Error = Error<T>;
+
+		/// Applies the slashes that are due at this block
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let due = SlashQueue::<T>::take(n);
+			// Each slash takes its pending slash, and updates the offender's account, held funds
+			// and lock
+			let db = T::DbWeight::get();
+			let weight = db.reads_writes(1, 1)
+				.saturating_add(db.reads_writes(4, 4).saturating_mul(due.len() as Weight));
+
+			for id in due {
+				if let Some(slash) = PendingSlashes::<T>::take(id) {
+					Self::apply_slash(slash, n);
+				}
+			}
+
+			weight
+		}
 
-			let imbalance = T::Currency::slash_reserved(&to_punish, collateral).0;
-			T::Slash::on_unbalanced(imbalance);
+		/// Reports a slash of the specified amount of reserved funds from the specified account.
+		/// The slash is applied after `SlashDeferDuration` blocks unless it is cancelled.
+		#[weight = T::WeightInfo::slash_funds()]
+		pub fn slash_funds(
+			origin,
+			to_punish: T::AccountId,
+			collateral: BalanceOf<T>,
+			evidence: T::Hash,
+		) -> DispatchResult {
+			T::SlashOrigin::ensure_origin(origin)?;
 
+			// Slashes are applied in `on_initialize`, so the earliest they can apply is next block
 			let now = <system::Module<T>>::block_number();
-			Self::deposit_event(RawEvent::SlashFunds(to_punish, collateral, now));
+			let delay = T::SlashDeferDuration::get().max(One::one());
+			let execute_at = now.saturating_add(delay);
+			let mut queue = Self::slash_queue(execute_at);
+			ensure!(
+				queue.len() < T::MaxSlashesPerBlock::get() as usize,
+				Error::<T>::TooManySlashes
+			);
+
+			// Hold the reserved funds the slash will take. Once they are unreserved and locked,
+			// no other pallet can unreserve them and let the offender move them away.
+			let held = T::Currency::reserved_balance(&to_punish).min(collateral);
+			T::Currency::unreserve(&to_punish, held);
+			Self::set_held(&to_punish, Self::held(&to_punish).saturating_add(held));
+
+			let id = Self::next_slash_id();
+			NextSlashId::put(id.wrapping_add(1));
+			PendingSlashes::<T>::insert(id, PendingSlash {
+				offender: to_punish.clone(),
+				amount: collateral,
+				held,
+				evidence,
+				execute_at,
+			});
+			queue.push(id);
+			SlashQueue::<T>::insert(execute_at, queue);
+
+			Self::deposit_event(
+				RawEvent::SlashReported(id, to_punish, collateral, evidence, execute_at)
+			);
+			Ok(())
+		}
+
+		/// Cancels a pending slash
+		#[weight = T::WeightInfo::cancel_slash()]
+		pub fn cancel_slash(origin, id: SlashId) -> DispatchResult {
+			T::CancelOrigin::ensure_origin(origin)?;
+			let slash = PendingSlashes::<T>::take(id).ok_or(Error::<T>::UnknownSlash)?;
+
+			SlashQueue::<T>::mutate(slash.execute_at, |ids| ids.retain(|&queued| queued != id));
+			Self::set_held(&slash.offender, Self::held(&slash.offender).saturating_sub(slash.held));
+
+			// Put the held funds back into the offender's reserve. Fees may have spent some of
+			// them, and another lock may keep them free, in which case they simply stay free.
+			let free = T::Currency::free_balance(&slash.offender);
+			let _ = T::Currency::reserve(&slash.offender, slash.held.min(free));
+
+			Self::deposit_event(RawEvent::SlashCancelled(id));
+			Ok(())
 		}
 
 		/// Awards the specified amount of funds to the specified accoutn
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::reward_funds()]
 		pub fn reward_funds(origin, to_reward: T::AccountId, reward: BalanceOf<T>) {
-			let _ = ensure_signed(origin)?;
+			T::RewardOrigin::ensure_origin(origin)?;
 
 			let mut total_imbalance = <PositiveImbalanceOf<T>>::zero();
 
@@ -74,5 +287,100 @@ decl_module! {
 			let now = <system::Module<T>>::block_number();
 			Self::deposit_event(RawEvent::RewardFunds(to_reward, reward, now));
 		}
+
+		/// Sets how the reward pool splits its funds
+		#[weight = T::WeightInfo::set_pool_shares()]
+		pub fn set_pool_shares(origin, shares: Vec<(PoolDestination, u32)>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			for (i, (destination, weight)) in shares.iter().enumerate() {
+				ensure!(
+					!shares[..i].iter().any(|(earlier, _)| earlier == destination),
+					Error::<T>::DuplicateDestination
+				);
+				// A zero weight would make the last destination receive nothing, and the
+				// rounding dust meant for it would be burned instead
+				ensure!(*weight > 0, Error::<T>::InvalidWeights);
+			}
+			let total = shares
+				.iter()
+				.try_fold(0u32, |total, (_, weight)| total.checked_add(*weight));
+			ensure!(total.is_some(), Error::<T>::InvalidWeights);
+
+			PoolShares::put(&shares);
+
+			Self::deposit_event(RawEvent::PoolSharesSet(shares));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Slashes reserved funds and hands the imbalance to the `Slash` handler
+	fn apply_slash(slash: PendingSlashOf<T>, now: T::BlockNumber) {
+		let to_punish = slash.offender;
+
+		// The held funds are locked in the offender's free balance
+		Self::set_held(&to_punish, Self::held(&to_punish).saturating_sub(slash.held));
+		let (mut imbalance, _) = T::Currency::slash(&to_punish, slash.held);
+		// The rest of the slash comes from whatever the offender has reserved since. They may
+		// have fewer reserved funds than the slash asks for.
+		let rest = slash.amount.saturating_sub(slash.held);
+		imbalance.subsume(T::Currency::slash_reserved(&to_punish, rest).0);
+
+		let slashed = imbalance.peek();
+		T::Slash::on_unbalanced(imbalance);
+
+		Self::deposit_event(RawEvent::SlashFunds(to_punish, slashed, now));
+	}
+
+	/// Records the funds held for an offender's pending slashes, and locks them. The lock does
+	/// not apply to transaction fees, so the offender can still pay for their appeal.
+	fn set_held(offender: &T::AccountId, held: BalanceOf<T>) {
+		if held.is_zero() {
+			Held::<T>::remove(offender);
+			T::Currency::remove_lock(SLASH_ID, offender);
+		} else {
+			Held::<T>::insert(offender, held);
+			let reasons = WithdrawReasons::except(WithdrawReason::TransactionPayment);
+			T::Currency::set_lock(SLASH_ID, offender, held, reasons);
+		}
+	}
+}
+
+/// An `OnUnbalanced` handler that splits funds among the destinations in `PoolShares`
+///
+/// Any rounding dust ends up with the last destination.
+pub struct RewardPool<T>(PhantomData<T>);
+
+impl<T: Trait> OnUnbalanced<NegativeImbalanceOf<T>> for RewardPool<T> {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T>) {
+		let shares = PoolShares::get();
+		let mut remaining_weight = shares
+			.iter()
+			.fold(0u32, |total, (_, weight)| total.saturating_add(*weight));
+		let mut remaining = amount;
+
+		for (destination, weight) in shares {
+			if remaining_weight == 0 {
+				break;
+			}
+
+			// Give this destination its proportion of what the remaining destinations share
+			let (share, rest) = remaining.ration(weight, remaining_weight.saturating_sub(weight));
+			remaining_weight = remaining_weight.saturating_sub(weight);
+			remaining = rest;
+
+			Module::<T>::deposit_event(RawEvent::PoolPaid(destination, share.peek()));
+			match destination {
+				PoolDestination::Treasury => T::Treasury::on_unbalanced(share),
+				PoolDestination::Author => T::Author::on_unbalanced(share),
+				// Dropping a negative imbalance reduces the total issuance
+				PoolDestination::Burn => drop(share),
+			}
+		}
+
+		// With no shares set, nothing has been paid out, so everything is burned
+		drop(remaining);
 	}
 }
@@ -0,0 +1,467 @@
+use crate::*;
+use balances;
+use frame_support::{
+	assert_noop, assert_ok, dispatch::DispatchError, impl_outer_event, impl_outer_origin,
+	ord_parameter_types, parameter_types,
+	traits::{
+		Currency, ExistenceRequirement, OnInitialize, OnUnbalanced, ReservableCurrency,
+		WithdrawReason,
+	},
+};
+use frame_system::{self as system, EnsureRoot, EnsureSignedBy, RawOrigin};
+use sp_core::H256;
+use sp_io;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 1;
+	pub const SlashDeferDuration: u64 = 5;
+	pub const MaxSlashesPerBlock: u32 = 2;
+}
+ord_parameter_types! {
+	pub const Slasher: u64 = 9;
+}
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<TestRuntime>;
+}
+
+impl charity::Trait for TestRuntime {
+	type Event = TestEvent;
+	type Currency = balances::Module<Self>;
+	type WeightInfo = ();
+}
+
+mod currency_imbalances {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for TestRuntime {
+		system<T>,
+		currency_imbalances<T>,
+		balances<T>,
+		charity<T>,
+	}
+}
+
+/// The block author in tests
+const AUTHOR: u64 = 7;
+
+/// Pays the reward pool's author share to `AUTHOR`
+pub struct TestAuthor;
+impl OnUnbalanced<NegativeImbalanceOf<TestRuntime>> for TestAuthor {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<TestRuntime>) {
+		let _ = Balances::resolve_creating(&AUTHOR, amount);
+	}
+}
+
+impl Trait for TestRuntime {
+	type Event = TestEvent;
+	type Currency = balances::Module<Self>;
+	type Reward = ();
+	type Slash = RewardPool<Self>;
+	type RewardOrigin = EnsureRoot<u64>;
+	type SlashOrigin = EnsureSignedBy<Slasher, u64>;
+	type CancelOrigin = EnsureRoot<u64>;
+	type SlashDeferDuration = SlashDeferDuration;
+	type MaxSlashesPerBlock = MaxSlashesPerBlock;
+	type Treasury = charity::Module<Self>;
+	type Author = TestAuthor;
+	type WeightInfo = ();
+}
+
+pub type System = system::Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
+pub type CurrencyImbalances = Module<TestRuntime>;
+pub type Charity = charity::Module<TestRuntime>;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default()
+		.build_storage::<TestRuntime>()
+		.unwrap();
+	balances::GenesisConfig::<TestRuntime> {
+		balances: vec![(1, 10000), (2, 10000), (9, 10000)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+#[test]
+fn only_slash_origin_reports_slashes() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			CurrencyImbalances::slash_funds(Origin::signed(2), 1, 300, H256::repeat_byte(1)),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			300,
+			H256::repeat_byte(1)
+		));
+
+		assert_eq!(
+			CurrencyImbalances::pending_slash(0),
+			Some(PendingSlash {
+				offender: 1,
+				amount: 300,
+				held: 0,
+				evidence: H256::repeat_byte(1),
+				execute_at: 6,
+			})
+		);
+		assert_eq!(CurrencyImbalances::slash_queue(6), vec![0]);
+
+		let expected_event = TestEvent::currency_imbalances(RawEvent::SlashReported(
+			0,
+			1,
+			300,
+			H256::repeat_byte(1),
+			6,
+		));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn slash_applies_after_deferral() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::reserve(&1, 500));
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			300,
+			H256::repeat_byte(1)
+		));
+
+		// The held funds leave the reserve straight away, but are only taken after the deferral
+		CurrencyImbalances::on_initialize(5);
+		assert_eq!(Balances::reserved_balance(&1), 200);
+		assert_eq!(Balances::free_balance(&1), 9800);
+		assert_eq!(Balances::total_issuance(), 30000);
+
+		CurrencyImbalances::on_initialize(6);
+		assert_eq!(Balances::reserved_balance(&1), 200);
+		assert_eq!(Balances::free_balance(&1), 9500);
+		assert_eq!(Balances::total_issuance(), 29700);
+		assert_eq!(CurrencyImbalances::pending_slash(0), None);
+
+		let expected_event = TestEvent::currency_imbalances(RawEvent::SlashFunds(1, 300, 6));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn slash_is_limited_to_reserved_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::reserve(&1, 100));
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			300,
+			H256::repeat_byte(1)
+		));
+
+		CurrencyImbalances::on_initialize(6);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 9900);
+
+		let expected_event = TestEvent::currency_imbalances(RawEvent::SlashFunds(1, 100, 6));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn cancelled_slash_is_not_applied() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::reserve(&1, 500));
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			300,
+			H256::repeat_byte(1)
+		));
+
+		assert_noop!(
+			CurrencyImbalances::cancel_slash(Origin::signed(9), 0),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(CurrencyImbalances::cancel_slash(RawOrigin::Root.into(), 0));
+		assert_eq!(CurrencyImbalances::slash_queue(6), Vec::<SlashId>::new());
+		assert_noop!(
+			CurrencyImbalances::cancel_slash(RawOrigin::Root.into(), 0),
+			Error::<TestRuntime>::UnknownSlash
+		);
+
+		CurrencyImbalances::on_initialize(6);
+		assert_eq!(Balances::reserved_balance(&1), 500);
+
+		let expected_event = TestEvent::currency_imbalances(RawEvent::SlashCancelled(0));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn pending_slash_holds_reserved_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::reserve(&1, 500));
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			300,
+			H256::repeat_byte(1)
+		));
+		assert_eq!(CurrencyImbalances::held(1), 300);
+
+		// The held funds can no longer be unreserved, only the rest of the reserve can
+		assert_eq!(Balances::reserved_balance(&1), 200);
+		assert_eq!(Balances::unreserve(&1, 500), 300);
+
+		// The offender can not move the held funds away
+		assert_noop!(
+			Balances::transfer(Origin::signed(1), 2, 9701),
+			balances::Error::<TestRuntime, _>::LiquidityRestrictions
+		);
+		assert_ok!(Balances::transfer(Origin::signed(1), 2, 9700));
+
+		// The slash takes the held funds from the free balance
+		CurrencyImbalances::on_initialize(6);
+		assert_eq!(Balances::free_balance(&1), 0);
+		assert_eq!(CurrencyImbalances::held(1), 0);
+		let expected_event = TestEvent::currency_imbalances(RawEvent::SlashFunds(1, 300, 6));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn pending_slash_does_not_freeze_other_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::reserve(&1, 500));
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			300,
+			H256::repeat_byte(1)
+		));
+
+		// All of the offender's free funds from before the report are still spendable
+		assert_ok!(Balances::transfer(Origin::signed(1), 2, 9500));
+		assert_eq!(Balances::free_balance(&1), 300);
+
+		// Transaction fees may be paid from the held funds
+		assert_ok!(Balances::withdraw(
+			&1,
+			10,
+			WithdrawReason::TransactionPayment.into(),
+			ExistenceRequirement::KeepAlive
+		));
+		assert_noop!(
+			Balances::transfer(Origin::signed(1), 2, 10),
+			balances::Error::<TestRuntime, _>::LiquidityRestrictions
+		);
+	})
+}
+
+#[test]
+fn cancelled_slash_unlocks_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::reserve(&1, 500));
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			300,
+			H256::repeat_byte(1)
+		));
+		assert_ok!(CurrencyImbalances::cancel_slash(RawOrigin::Root.into(), 0));
+		assert_eq!(CurrencyImbalances::held(1), 0);
+
+		Balances::unreserve(&1, 500);
+		assert_ok!(Balances::transfer(Origin::signed(1), 2, 10000));
+	})
+}
+
+#[test]
+fn slashes_per_block_are_limited() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..2 {
+			assert_ok!(CurrencyImbalances::slash_funds(
+				Origin::signed(9),
+				1,
+				100,
+				H256::repeat_byte(1)
+			));
+		}
+		assert_noop!(
+			CurrencyImbalances::slash_funds(Origin::signed(9), 1, 100, H256::repeat_byte(1)),
+			Error::<TestRuntime>::TooManySlashes
+		);
+
+		// The next block's slashes apply at a later block, which has room
+		System::set_block_number(2);
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			100,
+			H256::repeat_byte(1)
+		));
+	})
+}
+
+#[test]
+fn only_reward_origin_mints() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			CurrencyImbalances::reward_funds(Origin::signed(1), 1, 500),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(CurrencyImbalances::reward_funds(RawOrigin::Root.into(), 1, 500));
+
+		assert_eq!(Balances::free_balance(&1), 10500);
+		assert_eq!(Balances::total_issuance(), 30500);
+	})
+}
+
+#[test]
+fn pool_splits_slashes_by_weight() {
+	new_test_ext().execute_with(|| {
+		let shares = vec![
+			(PoolDestination::Treasury, 2),
+			(PoolDestination::Author, 1),
+			(PoolDestination::Burn, 1),
+		];
+		assert_ok!(CurrencyImbalances::set_pool_shares(RawOrigin::Root.into(), shares.clone()));
+		assert_eq!(CurrencyImbalances::pool_shares(), shares);
+
+		assert_ok!(Balances::reserve(&1, 1000));
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			1000,
+			H256::repeat_byte(1)
+		));
+		CurrencyImbalances::on_initialize(6);
+
+		assert_eq!(Balances::free_balance(&Charity::account_id()), 500);
+		assert_eq!(Balances::free_balance(&AUTHOR), 250);
+		// Only the burned share leaves the total issuance
+		assert_eq!(Balances::total_issuance(), 29750);
+
+		let expected_event =
+			TestEvent::currency_imbalances(RawEvent::PoolPaid(PoolDestination::Burn, 250));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn pool_burns_without_shares() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::reserve(&1, 1000));
+		assert_ok!(CurrencyImbalances::slash_funds(
+			Origin::signed(9),
+			1,
+			1000,
+			H256::repeat_byte(1)
+		));
+		CurrencyImbalances::on_initialize(6);
+
+		assert_eq!(Balances::free_balance(&Charity::account_id()), 0);
+		assert_eq!(Balances::free_balance(&AUTHOR), 0);
+		assert_eq!(Balances::total_issuance(), 29000);
+	})
+}
+
+#[test]
+fn pool_shares_are_validated() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			CurrencyImbalances::set_pool_shares(
+				Origin::signed(1),
+				vec![(PoolDestination::Treasury, 1)]
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			CurrencyImbalances::set_pool_shares(
+				RawOrigin::Root.into(),
+				vec![(PoolDestination::Treasury, 1), (PoolDestination::Treasury, 2)]
+			),
+			Error::<TestRuntime>::DuplicateDestination
+		);
+		assert_noop!(
+			CurrencyImbalances::set_pool_shares(
+				RawOrigin::Root.into(),
+				vec![(PoolDestination::Treasury, 0), (PoolDestination::Burn, 0)]
+			),
+			Error::<TestRuntime>::InvalidWeights
+		);
+		// A zero weight last destination would leave the rounding dust to be burned
+		assert_noop!(
+			CurrencyImbalances::set_pool_shares(
+				RawOrigin::Root.into(),
+				vec![(PoolDestination::Treasury, 1), (PoolDestination::Author, 0)]
+			),
+			Error::<TestRuntime>::InvalidWeights
+		);
+		assert_noop!(
+			CurrencyImbalances::set_pool_shares(
+				RawOrigin::Root.into(),
+				vec![(PoolDestination::Treasury, u32::max_value()), (PoolDestination::Burn, 1)]
+			),
+			Error::<TestRuntime>::InvalidWeights
+		);
+
+		// An empty list restores the default of burning everything
+		assert_ok!(CurrencyImbalances::set_pool_shares(RawOrigin::Root.into(), vec![]));
+		let expected_event = TestEvent::currency_imbalances(RawEvent::PoolSharesSet(vec![]));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
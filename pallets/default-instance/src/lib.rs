@@ -5,6 +5,10 @@
 use frame_support::{decl_event, decl_module, decl_storage, dispatch::DispatchResult};
 use frame_system::{self as system, ensure_signed};
 
+pub mod weights;
+
+pub use weights::WeightInfo;
+
 // The pallet's configuration trait takes an instance as a type parameter. The instance type is
 // created by the `decl_storage!` macro below. Giving it a value of `DefaultInstance` allows us
 // to use the pallet in a runtime where only a single instance is desired without the extra syntax
@@ -12,6 +16,9 @@ use frame_system::{self as system, ensure_signed};
 pub trait Trait<I: Instance = DefaultInstance>: system::Trait {
 	// The ubiquitous event type's From bound needs updated to support the instance.
 	type Event: From<Event<Self, I>> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 // It is necessary for instantiable pallets to call `decl_storage!` even if no storage items
@@ -43,7 +50,7 @@ decl_module! {
 
 		/// The only dispatchable call, updates the single storage item,
 		/// and emits an event.
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::call()]
 		fn call(origin) -> DispatchResult {
 			let caller = ensure_signed(origin)?;
 
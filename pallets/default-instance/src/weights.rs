@@ -0,0 +1,28 @@
+//! Weights for the default instance pallet
+//!
+//! These weights are not benchmarked, because the `benchmarks!` macro does not support
+//! instantiable pallets yet. The only call writes a single storage value, so it is weighed like
+//! `set_account` in the single value pallet.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the default instance pallet
+pub trait WeightInfo {
+	fn call() -> Weight;
+}
+
+/// Weights for the default instance pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn call() -> Weight {
+		(10_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn call() -> Weight {
+		(10_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
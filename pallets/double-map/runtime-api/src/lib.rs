@@ -0,0 +1,15 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+
+use parity_scale_codec::Codec;
+use sp_std::prelude::*;
+
+// Here we declare the runtime API. It is implemented it the `impl` block in
+// runtime amalgamator file (the `runtime/src/lib.rs`)
+sp_api::decl_runtime_apis! {
+	pub trait DoubleMapApi<AccountId> where AccountId: Codec {
+		/// The members of a group and their scores, highest score first. The group is identified
+		/// by its `double_map::GroupIndex`.
+		fn leaderboard(group: u32) -> Vec<(AccountId, u32)>;
+	}
+}
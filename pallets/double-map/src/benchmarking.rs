@@ -0,0 +1,148 @@
+//! Benchmarks for the double map pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+/// Adds `who` to `AllMembers`
+fn register<T: Trait>(who: &T::AccountId) {
+	<AllMembers<T>>::insert(who, true);
+}
+
+/// Creates a group with the largest cap, administered by `admin`, and approves `members` new
+/// members into it. Returns the group's index.
+fn create_group_with<T: Trait>(
+	admin: &T::AccountId,
+	members: u32,
+) -> Result<GroupIndex, &'static str> {
+	register::<T>(admin);
+	let index = Module::<T>::next_group_index();
+	Module::<T>::create_group(RawOrigin::Signed(admin.clone()).into(), T::MaxGroupSize::get())?;
+
+	for i in 0..members {
+		// Seeding with the group index keeps the members of each group distinct
+		let member: T::AccountId = account("member", i, index);
+		register::<T>(&member);
+		Module::<T>::join_a_group(RawOrigin::Signed(member.clone()).into(), index)?;
+		Module::<T>::approve_member(RawOrigin::Signed(admin.clone()).into(), index, member)?;
+	}
+	Ok(index)
+}
+
+/// Has `requests` new members ask to join the group
+fn request_to_join<T: Trait>(index: GroupIndex, requests: u32) -> Result<(), &'static str> {
+	for i in 0..requests {
+		let requester: T::AccountId = account("requester", i, index);
+		register::<T>(&requester);
+		Module::<T>::join_a_group(RawOrigin::Signed(requester).into(), index)?;
+	}
+	Ok(())
+}
+
+benchmarks! {
+	_ { }
+
+	join_all_members {
+		let caller: T::AccountId = account("caller", 0, 0);
+		<AllMembers<T>>::remove(&caller);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Module::<T>::all_members(&caller));
+	}
+
+	create_group {
+		let caller: T::AccountId = account("caller", 0, 0);
+		register::<T>(&caller);
+		let index = Module::<T>::next_group_index();
+	}: _(RawOrigin::Signed(caller), T::MaxGroupSize::get())
+	verify {
+		assert!(Module::<T>::group(index).is_some());
+	}
+
+	join_a_group {
+		let admin: T::AccountId = account("admin", 0, 0);
+		let earlier = create_group_with::<T>(&admin, 0)?;
+		let index = create_group_with::<T>(&admin, 0)?;
+		let caller: T::AccountId = account("caller", index, 0);
+		register::<T>(&caller);
+		// The worst case withdraws an earlier request to another group
+		Module::<T>::join_a_group(RawOrigin::Signed(caller.clone()).into(), earlier)?;
+	}: _(RawOrigin::Signed(caller.clone()), index)
+	verify {
+		assert!(Module::<T>::join_requested(index, &caller));
+		assert!(!Module::<T>::join_requested(earlier, &caller));
+	}
+
+	approve_member {
+		let admin: T::AccountId = account("admin", 0, 0);
+		let index = create_group_with::<T>(&admin, T::MaxGroupSize::get().saturating_sub(1))?;
+		let member: T::AccountId = account("joiner", index, 0);
+		register::<T>(&member);
+		Module::<T>::join_a_group(RawOrigin::Signed(member.clone()).into(), index)?;
+	}: _(RawOrigin::Signed(admin), index, member.clone())
+	verify {
+		assert_eq!(Module::<T>::group_membership(&member), Some(index));
+	}
+
+	set_score {
+		let admin: T::AccountId = account("admin", 0, 0);
+		let index = create_group_with::<T>(&admin, 1)?;
+		let member: T::AccountId = account("member", 0, index);
+	}: _(RawOrigin::Signed(admin), index, member.clone(), 100)
+	verify {
+		assert_eq!(Module::<T>::member_score(index, &member), 100);
+	}
+
+	remove_member {
+		let admin: T::AccountId = account("admin", 0, 0);
+		let index = create_group_with::<T>(&admin, 1)?;
+		let member: T::AccountId = account("member", 0, index);
+	}: _(RawOrigin::Signed(member.clone()))
+	verify {
+		assert_eq!(Module::<T>::group_membership(&member), None);
+	}
+
+	kick_member {
+		let admin: T::AccountId = account("admin", 0, 0);
+		let index = create_group_with::<T>(&admin, 1)?;
+		let member: T::AccountId = account("member", 0, index);
+	}: _(RawOrigin::Signed(admin), index, member.clone())
+	verify {
+		assert_eq!(Module::<T>::group_membership(&member), None);
+	}
+
+	remove_group_score {
+		let m in 0 .. T::MaxGroupSize::get();
+		let r in 0 .. T::MaxJoinRequests::get();
+		let admin: T::AccountId = account("admin", 0, 0);
+		let index = create_group_with::<T>(&admin, m)?;
+		request_to_join::<T>(index, r)?;
+	}: _(RawOrigin::Signed(admin), index)
+	verify {
+		assert!(Module::<T>::group(index).is_none());
+		assert!(Module::<T>::leaderboard(index).is_empty());
+		let requester: T::AccountId = account("requester", 0, index);
+		assert_eq!(Module::<T>::pending_request(&requester), None);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_join_all_members::<TestRuntime>());
+			assert_ok!(test_benchmark_create_group::<TestRuntime>());
+			assert_ok!(test_benchmark_join_a_group::<TestRuntime>());
+			assert_ok!(test_benchmark_approve_member::<TestRuntime>());
+			assert_ok!(test_benchmark_set_score::<TestRuntime>());
+			assert_ok!(test_benchmark_remove_member::<TestRuntime>());
+			assert_ok!(test_benchmark_kick_member::<TestRuntime>());
+			assert_ok!(test_benchmark_remove_group_score::<TestRuntime>());
+		});
+	}
+}
@@ -3,36 +3,114 @@
 //! the first key might be a group identifier
 //! the second key might be a unique identifier
 //! `remove_prefix` enables clean removal of all values with the group identifier
+//!
+//! Groups are created by a registered member, who becomes the group's admin. Other registered
+//! members ask to join, and the admin approves them until the group's member cap is reached.
+//! Scores are set by the group admin or by a configurable oracle origin.
+//!
+//! A member waits on at most one request to join at a time, and each group holds at most
+//! `MaxJoinRequests` requests, so disbanding a group removes a bounded number of entries.
+//!
+//! `AllMembers` was once a single `Vec` of accounts, and groups had no admin. The pallet records a
+//! `StorageVersion`, and in `on_runtime_upgrade` migrates the old `Vec` into the map and clears the
+//! old groups. See the `migrations` module.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod migrations;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 use frame_support::{
-	decl_event, decl_module, decl_storage,
+	decl_error, decl_event, decl_module, decl_storage,
 	dispatch::DispatchResult,
 	ensure,
-	storage::{StorageDoubleMap, StorageMap, StorageValue},
+	storage::{IterableStorageDoubleMap, StorageDoubleMap, StorageMap, StorageValue},
+	traits::{EnsureOrigin, Get},
+	weights::Weight,
 };
 use frame_system::{self as system, ensure_signed};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
 use sp_std::prelude::*;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The oracle origin that may set the score of any group member
+	type ScoreOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The largest member cap a group may have
+	type MaxGroupSize: Get<u32>;
+
+	/// The most requests to join that may await a group's admin at once
+	type MaxJoinRequests: Get<u32>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 pub type GroupIndex = u32; // this is Encode (which is necessary for double_map)
 
+/// A group of members whose scores are tracked together
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Group<AccountId> {
+	/// The account that approves members and sets scores
+	pub admin: AccountId,
+	/// The maximum number of members
+	pub cap: u32,
+	/// The current number of members
+	pub member_count: u32,
+	/// The number of requests to join that await the admin
+	pub request_count: u32,
+}
+
+/// The layouts the pallet's storage has had. Each new layout needs a migration.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	/// The original layout, in which `AllMembers` was a `Vec` and there was no `StorageVersion`
+	V1,
+	/// `AllMembers` is a map
+	V2,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		// Chains that stored members before the version was introduced have no version stored
+		Releases::V1
+	}
+}
+
 decl_storage! {
 	trait Store for Module<T: Trait> as Dmap {
-		/// Member score (double map)
+		/// Member score (double map). An entry exists for every member of a group.
 		MemberScore get(fn member_score):
 			double_map hasher(blake2_128_concat) GroupIndex, hasher(blake2_128_concat) T::AccountId => u32;
 		/// Get group ID for member
-		GroupMembership get(fn group_membership): map hasher(blake2_128_concat) T::AccountId => GroupIndex;
+		GroupMembership get(fn group_membership):
+			map hasher(blake2_128_concat) T::AccountId => Option<GroupIndex>;
 		/// For fast membership checks, see check-membership recipe for more details
-		AllMembers get(fn all_members): Vec<T::AccountId>;
+		AllMembers get(fn all_members): map hasher(blake2_128_concat) T::AccountId => bool;
+		/// The groups that exist
+		Groups get(fn group): map hasher(twox_64_concat) GroupIndex => Option<Group<T::AccountId>>;
+		/// The index the next group will receive
+		NextGroupIndex get(fn next_group_index): GroupIndex;
+		/// Requests to join a group that await the admin's approval
+		JoinRequests get(fn join_requested):
+			double_map hasher(twox_64_concat) GroupIndex,
+			hasher(blake2_128_concat) T::AccountId => bool;
+		/// The group each account has asked to join, if any
+		PendingRequest get(fn pending_request):
+			map hasher(blake2_128_concat) T::AccountId => Option<GroupIndex>;
+
+		/// The layout of the pallet's storage. New chains start with the latest one.
+		StorageVersion get(fn storage_version) build(|_| Releases::V2): Releases;
 	}
 }
 
@@ -43,8 +121,14 @@ decl_event!(
 	{
 		/// New member for `AllMembers` group
 		NewMember(AccountId),
-		/// Put member score (id, index, score)
-		MemberJoinsGroup(AccountId, GroupIndex, u32),
+		/// A group was created (index, admin, cap)
+		GroupCreated(GroupIndex, AccountId, u32),
+		/// A member asked to join a group
+		JoinRequested(AccountId, GroupIndex),
+		/// The admin approved a member's request to join a group
+		MemberJoinsGroup(AccountId, GroupIndex),
+		/// A member's score was set (id, index, score)
+		ScoreSet(AccountId, GroupIndex, u32),
 		/// Remove a single member with AccountId
 		RemoveMember(AccountId),
 		/// Remove all members with GroupId
@@ -52,58 +136,191 @@ decl_event!(
 	}
 );
 
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Already a member, can't join
+		AlreadyMember,
+		/// Not a member of `AllMembers`
+		NotMember,
+		/// Already a member of a group
+		AlreadyInGroup,
+		/// Not a member of the group
+		NotInGroup,
+		/// No group exists with the given index
+		UnknownGroup,
+		/// Only the group's admin may do this
+		NotGroupAdmin,
+		/// The group has reached its member cap
+		GroupFull,
+		/// A group's cap must be between one and `MaxGroupSize`
+		InvalidCap,
+		/// Already asked to join the group
+		AlreadyRequested,
+		/// The account has not asked to join the group
+		NoJoinRequest,
+		/// The group already has `MaxJoinRequests` requests awaiting its admin
+		TooManyRequests,
+	}
+}
+
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
-		/// Join the `AllMembers` vec before joining a group
-		#[weight = 10_000]
+		type Error = Error<T>;
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate_to_v2::<T>()
+		}
+
+		/// Join the `AllMembers` set before joining a group
+		#[weight = T::WeightInfo::join_all_members()]
 		fn join_all_members(origin) -> DispatchResult {
 			let new_member = ensure_signed(origin)?;
-			ensure!(!Self::is_member(&new_member), "already a member, can't join");
-			<AllMembers<T>>::append(&new_member);
+			ensure!(!Self::is_member(&new_member), Error::<T>::AlreadyMember);
+			<AllMembers<T>>::insert(&new_member, true);
 
 			Self::deposit_event(RawEvent::NewMember(new_member));
 			Ok(())
 		}
 
-		/// Put MemberScore (for testing purposes)
-		#[weight = 10_000]
-		fn join_a_group(origin, index: GroupIndex, score: u32) -> DispatchResult {
+		/// Create a group with the caller as its admin
+		#[weight = T::WeightInfo::create_group()]
+		fn create_group(origin, cap: u32) -> DispatchResult {
+			let admin = ensure_signed(origin)?;
+			ensure!(Self::is_member(&admin), Error::<T>::NotMember);
+			ensure!(cap > 0 && cap <= T::MaxGroupSize::get(), Error::<T>::InvalidCap);
+
+			let index = Self::next_group_index();
+			<NextGroupIndex>::put(index.wrapping_add(1));
+			<Groups<T>>::insert(index, Group {
+				admin: admin.clone(),
+				cap,
+				member_count: 0,
+				request_count: 0,
+			});
+
+			Self::deposit_event(RawEvent::GroupCreated(index, admin, cap));
+			Ok(())
+		}
+
+		/// Ask to join a group. The group's admin must approve the request.
+		///
+		/// Asking to join a group withdraws any earlier request to join another group.
+		#[weight = T::WeightInfo::join_a_group()]
+		fn join_a_group(origin, index: GroupIndex) -> DispatchResult {
 			let member = ensure_signed(origin)?;
-			ensure!(Self::is_member(&member), "not a member, can't remove");
-			<MemberScore<T>>::insert(&index, &member, score);
-			<GroupMembership<T>>::insert(&member, &index);
+			ensure!(Self::is_member(&member), Error::<T>::NotMember);
+			let mut group = Self::group(index).ok_or(Error::<T>::UnknownGroup)?;
+			ensure!(!<GroupMembership<T>>::contains_key(&member), Error::<T>::AlreadyInGroup);
+			ensure!(!<JoinRequests<T>>::contains_key(index, &member), Error::<T>::AlreadyRequested);
+			ensure!(group.request_count < T::MaxJoinRequests::get(), Error::<T>::TooManyRequests);
+
+			if let Some(earlier) = <PendingRequest<T>>::get(&member) {
+				Self::withdraw_request(earlier, &member);
+			}
+			group.request_count += 1;
+			<Groups<T>>::insert(index, group);
+			<JoinRequests<T>>::insert(index, &member, true);
+			<PendingRequest<T>>::insert(&member, index);
+
+			Self::deposit_event(RawEvent::JoinRequested(member, index));
+			Ok(())
+		}
+
+		/// Approve a request to join a group (admin only)
+		#[weight = T::WeightInfo::approve_member()]
+		fn approve_member(origin, index: GroupIndex, member: T::AccountId) -> DispatchResult {
+			let admin = ensure_signed(origin)?;
+			let mut group = Self::group(index).ok_or(Error::<T>::UnknownGroup)?;
+			ensure!(group.admin == admin, Error::<T>::NotGroupAdmin);
+			ensure!(<JoinRequests<T>>::contains_key(index, &member), Error::<T>::NoJoinRequest);
+			// The member may have joined another group since asking
+			ensure!(!<GroupMembership<T>>::contains_key(&member), Error::<T>::AlreadyInGroup);
+			ensure!(group.member_count < group.cap, Error::<T>::GroupFull);
+
+			group.member_count += 1;
+			group.request_count = group.request_count.saturating_sub(1);
+			<Groups<T>>::insert(index, group);
+			<JoinRequests<T>>::remove(index, &member);
+			<PendingRequest<T>>::remove(&member);
+			<MemberScore<T>>::insert(index, &member, 0);
+			<GroupMembership<T>>::insert(&member, index);
+
+			Self::deposit_event(RawEvent::MemberJoinsGroup(member, index));
+			Ok(())
+		}
+
+		/// Set a member's score (group admin or the oracle origin)
+		#[weight = T::WeightInfo::set_score()]
+		fn set_score(
+			origin,
+			index: GroupIndex,
+			member: T::AccountId,
+			score: u32,
+		) -> DispatchResult {
+			let group = Self::group(index).ok_or(Error::<T>::UnknownGroup)?;
+			if let Err(origin) = T::ScoreOrigin::try_origin(origin) {
+				let caller = ensure_signed(origin)?;
+				ensure!(group.admin == caller, Error::<T>::NotGroupAdmin);
+			}
+			ensure!(<MemberScore<T>>::contains_key(index, &member), Error::<T>::NotInGroup);
+
+			<MemberScore<T>>::insert(index, &member, score);
 
-			Self::deposit_event(RawEvent::MemberJoinsGroup(member, index, score));
+			Self::deposit_event(RawEvent::ScoreSet(member, index, score));
 			Ok(())
 		}
 
 		/// Remove a member
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::remove_member()]
 		fn remove_member(origin) -> DispatchResult {
 			let member_to_remove = ensure_signed(origin)?;
-			ensure!(Self::is_member(&member_to_remove), "not a member, can't remove");
-			let group_id = <GroupMembership<T>>::take(member_to_remove.clone());
-			<MemberScore<T>>::remove(&group_id, &member_to_remove);
+			ensure!(Self::is_member(&member_to_remove), Error::<T>::NotMember);
+			let group_id = <GroupMembership<T>>::get(&member_to_remove)
+				.ok_or(Error::<T>::NotInGroup)?;
+
+			Self::leave_group(group_id, &member_to_remove);
 
 			Self::deposit_event(RawEvent::RemoveMember(member_to_remove));
 			Ok(())
 		}
 
-		/// Remove group score
-		#[weight = 10_000]
-		fn remove_group_score(origin, group: GroupIndex) -> DispatchResult {
-			let member = ensure_signed(origin)?;
+		/// Remove a member from a group (admin only)
+		#[weight = T::WeightInfo::kick_member()]
+		fn kick_member(origin, index: GroupIndex, member: T::AccountId) -> DispatchResult {
+			let admin = ensure_signed(origin)?;
+			let group = Self::group(index).ok_or(Error::<T>::UnknownGroup)?;
+			ensure!(group.admin == admin, Error::<T>::NotGroupAdmin);
+			ensure!(<MemberScore<T>>::contains_key(index, &member), Error::<T>::NotInGroup);
+
+			Self::leave_group(index, &member);
+
+			Self::deposit_event(RawEvent::RemoveMember(member));
+			Ok(())
+		}
 
-			let group_id = <GroupMembership<T>>::get(member);
-			// check that the member is in the group
-			ensure!(group_id == group, "member isn't in the group, can't remove it");
+		/// Disband a group, removing all of its members and pending join requests (admin only)
+		#[weight = T::WeightInfo::remove_group_score(
+			T::MaxGroupSize::get(),
+			T::MaxJoinRequests::get(),
+		)]
+		fn remove_group_score(origin, index: GroupIndex) -> DispatchResult {
+			let admin = ensure_signed(origin)?;
+			let group = Self::group(index).ok_or(Error::<T>::UnknownGroup)?;
+			ensure!(group.admin == admin, Error::<T>::NotGroupAdmin);
 
-			// remove all group members from MemberScore at once
-			<MemberScore<T>>::remove_prefix(&group_id);
+			// remove all group members from MemberScore, at most `cap` of them
+			for (member, _) in <MemberScore<T>>::drain_prefix(index) {
+				<GroupMembership<T>>::remove(&member);
+			}
+			// remove all pending join requests, at most `MaxJoinRequests` of them
+			for (member, _) in <JoinRequests<T>>::drain_prefix(index) {
+				<PendingRequest<T>>::remove(&member);
+			}
+			<Groups<T>>::remove(index);
 
-			Self::deposit_event(RawEvent::RemoveGroup(group_id));
+			Self::deposit_event(RawEvent::RemoveGroup(index));
 			Ok(())
 		}
 	}
@@ -112,6 +329,39 @@ decl_module! {
 impl<T: Trait> Module<T> {
 	// for fast membership checks (see check-membership recipe for more details)
 	fn is_member(who: &T::AccountId) -> bool {
-		Self::all_members().contains(who)
+		<AllMembers<T>>::contains_key(who)
+	}
+
+	/// Withdraws an account's request to join a group
+	fn withdraw_request(index: GroupIndex, member: &T::AccountId) {
+		<JoinRequests<T>>::remove(index, member);
+		<PendingRequest<T>>::remove(member);
+		<Groups<T>>::mutate(index, |maybe_group| {
+			if let Some(group) = maybe_group {
+				group.request_count = group.request_count.saturating_sub(1);
+			}
+		});
+	}
+
+	/// Removes a member from a group, along with their score
+	fn leave_group(index: GroupIndex, member: &T::AccountId) {
+		<MemberScore<T>>::remove(index, member);
+		<GroupMembership<T>>::remove(member);
+		<Groups<T>>::mutate(index, |maybe_group| {
+			if let Some(group) = maybe_group {
+				group.member_count = group.member_count.saturating_sub(1);
+			}
+		});
+	}
+
+	/// The members of a group and their scores, highest score first
+	///
+	/// Members with equal scores are ordered by account. The leaderboard reads one storage entry
+	/// per member, which is bounded by the group's cap.
+	pub fn leaderboard(index: GroupIndex) -> Vec<(T::AccountId, u32)> {
+		let mut board = <MemberScore<T> as IterableStorageDoubleMap<_, _, _>>::iter_prefix(index)
+			.collect::<Vec<_>>();
+		board.sort_by(|(a, a_score), (b, b_score)| b_score.cmp(a_score).then_with(|| a.cmp(b)));
+		board
 	}
 }
@@ -0,0 +1,50 @@
+//! Storage migrations for the double map pallet
+//!
+//! Each migration checks the `StorageVersion` first, so it only ever runs once, however many
+//! runtime upgrades follow it.
+
+use super::*;
+use frame_support::storage::{
+	unhashed, IterableStorageDoubleMap, IterableStorageMap, StoragePrefixedMap,
+};
+
+/// Moves the members from the `Vec` that `AllMembers` used to be into the `AllMembers` map, and
+/// clears the groups of the old layout
+///
+/// The old value was stored under the storage item's own key, which is the prefix of every key
+/// in the new map, so it is taken from there as raw bytes.
+///
+/// Groups used to be nothing more than the `MemberScore` and `GroupMembership` entries that
+/// members wrote for themselves. They have no admin who could approve, score or disband them, and
+/// disbanding one left its `GroupMembership` entries behind, which would keep those members out of
+/// every new group. So all of those entries are removed, and `NextGroupIndex` starts after the
+/// highest index the old groups used, so new groups never share an index with them.
+pub fn migrate_to_v2<T: Trait>() -> Weight {
+	let db = T::DbWeight::get();
+	if StorageVersion::get() != Releases::V1 {
+		return db.reads(1);
+	}
+
+	let old_members =
+		unhashed::take::<Vec<T::AccountId>>(&<AllMembers<T>>::final_prefix()).unwrap_or_default();
+	let migrated = old_members.len() as Weight;
+	for member in old_members {
+		<AllMembers<T>>::insert(&member, true);
+	}
+
+	// Both maps have the same layout as before, so they can be drained with the new types
+	let mut next_index = NextGroupIndex::get();
+	let mut cleared: Weight = 0;
+	for (index, _, _) in <MemberScore<T>>::drain() {
+		next_index = next_index.max(index.saturating_add(1));
+		cleared += 1;
+	}
+	for (_, index) in <GroupMembership<T>>::drain() {
+		next_index = next_index.max(index.saturating_add(1));
+		cleared += 1;
+	}
+	NextGroupIndex::put(next_index);
+	StorageVersion::put(Releases::V2);
+
+	db.reads_writes(cleared + 3, migrated + cleared + 3)
+}
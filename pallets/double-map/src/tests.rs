@@ -1,6 +1,12 @@
 use super::*;
-use frame_support::{assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types};
-use frame_system as system;
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::DispatchError,
+	impl_outer_event, impl_outer_origin, ord_parameter_types, parameter_types,
+	storage::{unhashed, StoragePrefixedMap},
+	traits::OnRuntimeUpgrade,
+};
+use frame_system::{self as system, EnsureSignedBy};
 use sp_core::H256;
 use sp_io::TestExternalities;
 use sp_runtime::{
@@ -21,6 +27,12 @@ parameter_types! {
 	pub const MaximumBlockWeight: u32 = 1024;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const MaxGroupSize: u32 = 3;
+	pub const MaxJoinRequests: u32 = 2;
+}
+ord_parameter_types! {
+	pub const Oracle: u64 = 9;
 }
 impl system::Trait for TestRuntime {
 	type Origin = Origin;
@@ -61,6 +73,10 @@ impl_outer_event! {
 
 impl Trait for TestRuntime {
 	type Event = TestEvent;
+	type ScoreOrigin = EnsureSignedBy<Oracle, u64>;
+	type MaxGroupSize = MaxGroupSize;
+	type MaxJoinRequests = MaxJoinRequests;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
@@ -79,95 +95,356 @@ impl ExtBuilder {
 	}
 }
 
+/// Registers accounts 1 to 5, and has 1 create group 0 with a cap of 3
+fn setup_group() {
+	for i in 1..=5 {
+		assert_ok!(DoubleMap::join_all_members(Origin::signed(i)));
+	}
+	assert_ok!(DoubleMap::create_group(Origin::signed(1), 3));
+}
+
+/// Has the accounts ask to join group 0, and its admin approve them
+fn add_to_group(members: &[u64]) {
+	for &member in members {
+		assert_ok!(DoubleMap::join_a_group(Origin::signed(member), 0));
+		assert_ok!(DoubleMap::approve_member(Origin::signed(1), 0, member));
+	}
+}
+
 #[test]
 fn join_all_members_works() {
 	ExtBuilder::build().execute_with(|| {
 		assert_ok!(DoubleMap::join_all_members(Origin::signed(1)));
-		// correct panic upon existing member trying to join
+		// correct error upon existing member trying to join
 		assert_noop!(
 			DoubleMap::join_all_members(Origin::signed(1)),
-			"already a member, can't join"
+			Error::<TestRuntime>::AlreadyMember
 		);
 
 		// correct event emission
 		let expected_event = TestEvent::double_map(RawEvent::NewMember(1));
 		assert!(System::events().iter().any(|a| a.event == expected_event));
 		// correct storage changes
-		assert_eq!(DoubleMap::all_members(), vec![1]);
+		assert!(DoubleMap::all_members(1));
 	})
 }
 
 #[test]
-fn group_join_works() {
+fn create_group_works() {
 	ExtBuilder::build().execute_with(|| {
-		// expected panic
 		assert_noop!(
-			DoubleMap::join_a_group(Origin::signed(1), 3, 5),
-			"not a member, can't remove"
+			DoubleMap::create_group(Origin::signed(1), 3),
+			Error::<TestRuntime>::NotMember
 		);
 
 		assert_ok!(DoubleMap::join_all_members(Origin::signed(1)));
-		assert_ok!(DoubleMap::join_a_group(Origin::signed(1), 3, 5));
+		assert_noop!(
+			DoubleMap::create_group(Origin::signed(1), 0),
+			Error::<TestRuntime>::InvalidCap
+		);
+		assert_noop!(
+			DoubleMap::create_group(Origin::signed(1), 4),
+			Error::<TestRuntime>::InvalidCap
+		);
+		assert_ok!(DoubleMap::create_group(Origin::signed(1), 3));
+
+		assert_eq!(
+			DoubleMap::group(0),
+			Some(Group {
+				admin: 1,
+				cap: 3,
+				member_count: 0,
+				request_count: 0,
+			})
+		);
+		assert_eq!(DoubleMap::next_group_index(), 1);
+
+		let expected_event = TestEvent::double_map(RawEvent::GroupCreated(0, 1, 3));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn group_join_works() {
+	ExtBuilder::build().execute_with(|| {
+		// expected error
+		assert_noop!(
+			DoubleMap::join_a_group(Origin::signed(2), 0),
+			Error::<TestRuntime>::NotMember
+		);
+
+		setup_group();
+		assert_noop!(
+			DoubleMap::join_a_group(Origin::signed(2), 1),
+			Error::<TestRuntime>::UnknownGroup
+		);
+
+		// Asking to join is not enough
+		assert_ok!(DoubleMap::join_a_group(Origin::signed(2), 0));
+		assert!(DoubleMap::join_requested(0, 2));
+		assert_eq!(DoubleMap::group_membership(2), None);
+		assert_noop!(
+			DoubleMap::join_a_group(Origin::signed(2), 0),
+			Error::<TestRuntime>::AlreadyRequested
+		);
+
+		// Only the admin may approve
+		assert_noop!(
+			DoubleMap::approve_member(Origin::signed(2), 0, 2),
+			Error::<TestRuntime>::NotGroupAdmin
+		);
+		assert_noop!(
+			DoubleMap::approve_member(Origin::signed(1), 0, 3),
+			Error::<TestRuntime>::NoJoinRequest
+		);
+		assert_ok!(DoubleMap::approve_member(Origin::signed(1), 0, 2));
 
 		// correct event emission
-		let expected_event = TestEvent::double_map(RawEvent::MemberJoinsGroup(1, 3, 5));
+		let expected_event = TestEvent::double_map(RawEvent::MemberJoinsGroup(2, 0));
 		assert!(System::events().iter().any(|a| a.event == expected_event));
 
 		// correct storage changes
-		assert_eq!(DoubleMap::group_membership(1), 3);
-		assert_eq!(DoubleMap::member_score(3, 1), 5);
+		assert_eq!(DoubleMap::group_membership(2), Some(0));
+		assert_eq!(DoubleMap::member_score(0, 2), 0);
+		assert!(<MemberScore<TestRuntime>>::contains_key(0, 2));
+		assert!(!DoubleMap::join_requested(0, 2));
+		assert_eq!(DoubleMap::group(0).unwrap().member_count, 1);
+	})
+}
+
+#[test]
+fn groups_are_capped() {
+	ExtBuilder::build().execute_with(|| {
+		setup_group();
+		add_to_group(&[2, 3, 4]);
+
+		assert_ok!(DoubleMap::join_a_group(Origin::signed(5), 0));
+		assert_noop!(
+			DoubleMap::approve_member(Origin::signed(1), 0, 5),
+			Error::<TestRuntime>::GroupFull
+		);
+
+		// Once a member leaves, there is room again
+		assert_ok!(DoubleMap::remove_member(Origin::signed(4)));
+		assert_ok!(DoubleMap::approve_member(Origin::signed(1), 0, 5));
+	})
+}
+
+#[test]
+fn only_admin_or_oracle_set_scores() {
+	ExtBuilder::build().execute_with(|| {
+		setup_group();
+		add_to_group(&[2]);
+
+		// Members can no longer report their own scores
+		assert_noop!(
+			DoubleMap::set_score(Origin::signed(2), 0, 2, 100),
+			Error::<TestRuntime>::NotGroupAdmin
+		);
+		assert_noop!(
+			DoubleMap::set_score(Origin::none(), 0, 2, 100),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			DoubleMap::set_score(Origin::signed(1), 0, 3, 100),
+			Error::<TestRuntime>::NotInGroup
+		);
+
+		assert_ok!(DoubleMap::set_score(Origin::signed(1), 0, 2, 5));
+		assert_eq!(DoubleMap::member_score(0, 2), 5);
+
+		// The oracle is not the admin, but may set scores too
+		assert_ok!(DoubleMap::set_score(Origin::signed(9), 0, 2, 7));
+		assert_eq!(DoubleMap::member_score(0, 2), 7);
+
+		let expected_event = TestEvent::double_map(RawEvent::ScoreSet(2, 0, 7));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
 	})
 }
 
 #[test]
 fn remove_member_works() {
 	ExtBuilder::build().execute_with(|| {
-		// action: user 1 joins
-		assert_ok!(DoubleMap::join_all_members(Origin::signed(1)));
-		// action: user 1 joins group 3 with score 5
-		assert_ok!(DoubleMap::join_a_group(Origin::signed(1), 3, 5));
-		// action: remove user 1
-		assert_ok!(DoubleMap::remove_member(Origin::signed(1)));
+		setup_group();
+		add_to_group(&[2]);
+		// action: remove user 2
+		assert_ok!(DoubleMap::remove_member(Origin::signed(2)));
 
 		// check: correct event emitted
-		let expected_event = TestEvent::double_map(RawEvent::RemoveMember(1));
+		let expected_event = TestEvent::double_map(RawEvent::RemoveMember(2));
 		assert!(System::events().iter().any(|a| a.event == expected_event));
 
-		// check: user 1 should no longer belongs to group 3
-		assert!(!<GroupMembership<TestRuntime>>::contains_key(1));
-		assert!(!<MemberScore<TestRuntime>>::contains_key(3, 1));
+		// check: user 2 should no longer belongs to group 0
+		assert!(!<GroupMembership<TestRuntime>>::contains_key(2));
+		assert!(!<MemberScore<TestRuntime>>::contains_key(0, 2));
+		assert_eq!(DoubleMap::group(0).unwrap().member_count, 0);
+
+		assert_noop!(
+			DoubleMap::remove_member(Origin::signed(2)),
+			Error::<TestRuntime>::NotInGroup
+		);
 	})
 }
 
 #[test]
-fn remove_group_score_works() {
+fn kick_member_works() {
 	ExtBuilder::build().execute_with(|| {
-		assert_ok!(DoubleMap::join_all_members(Origin::signed(1)));
-		assert_ok!(DoubleMap::join_all_members(Origin::signed(2)));
-		assert_ok!(DoubleMap::join_all_members(Origin::signed(3)));
-		assert_ok!(DoubleMap::join_a_group(Origin::signed(1), 3, 5));
-		assert_ok!(DoubleMap::join_a_group(Origin::signed(2), 3, 5));
-		assert_ok!(DoubleMap::join_a_group(Origin::signed(3), 3, 5));
+		setup_group();
+		add_to_group(&[2, 3]);
 
 		assert_noop!(
-			DoubleMap::remove_group_score(Origin::signed(4), 3),
-			"member isn't in the group, can't remove it"
+			DoubleMap::kick_member(Origin::signed(3), 0, 2),
+			Error::<TestRuntime>::NotGroupAdmin
 		);
+		assert_ok!(DoubleMap::kick_member(Origin::signed(1), 0, 2));
 
+		assert!(!<MemberScore<TestRuntime>>::contains_key(0, 2));
+		assert_eq!(DoubleMap::group_membership(2), None);
+		assert_eq!(DoubleMap::group(0).unwrap().member_count, 1);
+	})
+}
+
+#[test]
+fn remove_group_score_works() {
+	ExtBuilder::build().execute_with(|| {
+		setup_group();
+		add_to_group(&[2, 3]);
+		assert_ok!(DoubleMap::join_a_group(Origin::signed(4), 0));
+
+		// Members can no longer wipe the group
+		assert_noop!(
+			DoubleMap::remove_group_score(Origin::signed(2), 0),
+			Error::<TestRuntime>::NotGroupAdmin
+		);
 		assert_noop!(
 			DoubleMap::remove_group_score(Origin::signed(1), 2),
-			"member isn't in the group, can't remove it"
+			Error::<TestRuntime>::UnknownGroup
 		);
 
-		assert_ok!(DoubleMap::remove_group_score(Origin::signed(1), 3));
+		assert_ok!(DoubleMap::remove_group_score(Origin::signed(1), 0));
 
 		// correct event emitted
-		let expected_event = TestEvent::double_map(RawEvent::RemoveGroup(3));
+		let expected_event = TestEvent::double_map(RawEvent::RemoveGroup(0));
 		assert!(System::events().iter().any(|a| a.event == expected_event));
 
-		// check: user 1, 2, 3 should no longer in the group
-		assert!(!<MemberScore<TestRuntime>>::contains_key(3, 1));
-		assert!(!<MemberScore<TestRuntime>>::contains_key(3, 2));
-		assert!(!<MemberScore<TestRuntime>>::contains_key(3, 3));
+		// check: user 2, 3 should no longer in the group, and 4's request is gone
+		assert!(!<MemberScore<TestRuntime>>::contains_key(0, 2));
+		assert!(!<MemberScore<TestRuntime>>::contains_key(0, 3));
+		assert_eq!(DoubleMap::group_membership(2), None);
+		assert_eq!(DoubleMap::group_membership(3), None);
+		assert!(!DoubleMap::join_requested(0, 4));
+		assert_eq!(DoubleMap::pending_request(4), None);
+		assert_eq!(DoubleMap::group(0), None);
+	})
+}
+
+#[test]
+fn join_requests_are_limited() {
+	ExtBuilder::build().execute_with(|| {
+		setup_group();
+		assert_ok!(DoubleMap::join_a_group(Origin::signed(2), 0));
+		assert_ok!(DoubleMap::join_a_group(Origin::signed(3), 0));
+		assert_eq!(DoubleMap::group(0).unwrap().request_count, 2);
+		assert_noop!(
+			DoubleMap::join_a_group(Origin::signed(4), 0),
+			Error::<TestRuntime>::TooManyRequests
+		);
+
+		// Approving a request makes room for another
+		assert_ok!(DoubleMap::approve_member(Origin::signed(1), 0, 2));
+		assert_eq!(DoubleMap::group(0).unwrap().request_count, 1);
+		assert_ok!(DoubleMap::join_a_group(Origin::signed(4), 0));
+	})
+}
+
+#[test]
+fn asking_to_join_withdraws_earlier_requests() {
+	ExtBuilder::build().execute_with(|| {
+		setup_group();
+		assert_ok!(DoubleMap::create_group(Origin::signed(2), 3));
+
+		assert_ok!(DoubleMap::join_a_group(Origin::signed(3), 0));
+		assert_eq!(DoubleMap::pending_request(3), Some(0));
+		assert_ok!(DoubleMap::join_a_group(Origin::signed(3), 1));
+		assert_eq!(DoubleMap::pending_request(3), Some(1));
+		assert!(!DoubleMap::join_requested(0, 3));
+		assert_eq!(DoubleMap::group(0).unwrap().request_count, 0);
+
+		// Once the request is approved, no request is left behind
+		assert_ok!(DoubleMap::approve_member(Origin::signed(2), 1, 3));
+		assert_eq!(DoubleMap::pending_request(3), None);
+		assert_eq!(DoubleMap::group(1).unwrap().request_count, 0);
+		assert_noop!(
+			DoubleMap::approve_member(Origin::signed(1), 0, 3),
+			Error::<TestRuntime>::NoJoinRequest
+		);
+	})
+}
+
+#[test]
+fn members_are_migrated_from_the_old_vec() {
+	ExtBuilder::build().execute_with(|| {
+		// Before V2, `AllMembers` was a `Vec` stored under the item's own key
+		unhashed::put(&<AllMembers<TestRuntime>>::final_prefix(), &vec![1u64, 2]);
+		assert_eq!(DoubleMap::storage_version(), Releases::V1);
+
+		DoubleMap::on_runtime_upgrade();
+		assert_eq!(DoubleMap::storage_version(), Releases::V2);
+		assert!(DoubleMap::all_members(1));
+		assert!(DoubleMap::all_members(2));
+		assert!(!DoubleMap::all_members(3));
+		assert_eq!(unhashed::get_raw(&<AllMembers<TestRuntime>>::final_prefix()), None);
+
+		// The migration only runs once
+		assert_ok!(DoubleMap::join_all_members(Origin::signed(3)));
+		DoubleMap::on_runtime_upgrade();
+		assert!(DoubleMap::all_members(3));
+	})
+}
+
+#[test]
+fn old_groups_are_cleared() {
+	ExtBuilder::build().execute_with(|| {
+		unhashed::put(&<AllMembers<TestRuntime>>::final_prefix(), &vec![1u64, 2, 3]);
+		// Before V2, members put themselves into groups with scores of their choosing. Both maps
+		// kept their layout, so the old entries can be written with the new types.
+		<MemberScore<TestRuntime>>::insert(3, 1, 50);
+		<GroupMembership<TestRuntime>>::insert(1, 3);
+		<MemberScore<TestRuntime>>::insert(5, 2, 7);
+		<GroupMembership<TestRuntime>>::insert(2, 5);
+		// Disbanding an old group left its members' `GroupMembership` behind
+		<GroupMembership<TestRuntime>>::insert(3, 9);
+
+		DoubleMap::on_runtime_upgrade();
+		assert_eq!(DoubleMap::member_score(3, 1), 0);
+		assert!(!<MemberScore<TestRuntime>>::contains_key(5, 2));
+		assert_eq!(DoubleMap::group_membership(1), None);
+		assert_eq!(DoubleMap::group_membership(3), None);
+
+		// New groups start after the highest old index, and every member may join them
+		assert_eq!(DoubleMap::next_group_index(), 10);
+		assert_ok!(DoubleMap::create_group(Origin::signed(1), 3));
+		assert!(DoubleMap::group(10).is_some());
+		assert_eq!(DoubleMap::leaderboard(10), vec![]);
+		for member in &[2, 3] {
+			assert_ok!(DoubleMap::join_a_group(Origin::signed(*member), 10));
+			assert_ok!(DoubleMap::approve_member(Origin::signed(1), 10, *member));
+		}
+		assert_eq!(DoubleMap::leaderboard(10), vec![(2, 0), (3, 0)]);
+	})
+}
+
+#[test]
+fn leaderboard_is_sorted() {
+	ExtBuilder::build().execute_with(|| {
+		setup_group();
+		add_to_group(&[2, 3, 4]);
+		assert_ok!(DoubleMap::set_score(Origin::signed(1), 0, 2, 10));
+		assert_ok!(DoubleMap::set_score(Origin::signed(1), 0, 3, 30));
+		assert_ok!(DoubleMap::set_score(Origin::signed(1), 0, 4, 10));
+
+		// Highest score first, ties broken by account
+		assert_eq!(DoubleMap::leaderboard(0), vec![(3, 30), (2, 10), (4, 10)]);
+		assert_eq!(DoubleMap::leaderboard(1), vec![]);
 	})
 }
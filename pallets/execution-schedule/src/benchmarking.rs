@@ -0,0 +1,51 @@
+//! Benchmarks for the execution schedule pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+use sp_std::vec;
+
+/// Puts `who` on the council with a full signal quota for the current era
+fn join_council<T: Trait>(who: &T::AccountId) {
+	<Council<T>>::put(vec![who.clone()]);
+	<SignalBank<T>>::insert(Era::get(), who, T::SignalQuota::get());
+}
+
+benchmarks! {
+	_ { }
+
+	schedule_task {
+		let caller: T::AccountId = account("caller", 0, 0);
+		join_council::<T>(&caller);
+		let id: TaskId = b"task".to_vec();
+	}: _(RawOrigin::Signed(caller), id.clone())
+	verify {
+		assert!(Module::<T>::pending_tasks(&id).is_some());
+	}
+
+	signal_priority {
+		let caller: T::AccountId = account("caller", 0, 0);
+		join_council::<T>(&caller);
+		let id: TaskId = b"task".to_vec();
+		Module::<T>::schedule_task(RawOrigin::Signed(caller.clone()).into(), id.clone())?;
+		let signal = T::SignalQuota::get();
+	}: _(RawOrigin::Signed(caller), id.clone(), signal)
+	verify {
+		assert_eq!(Module::<T>::pending_tasks(&id).map(|task| task.score), Some(signal));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(test_benchmark_schedule_task::<TestRuntime>());
+			assert_ok!(test_benchmark_signal_priority::<TestRuntime>());
+		});
+	}
+}
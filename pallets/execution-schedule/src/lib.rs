@@ -14,8 +14,13 @@ use frame_system::{self as system, ensure_signed};
 use sp_runtime::{traits::Zero, RuntimeDebug};
 use sp_std::prelude::*;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 pub type TaskId = Vec<u8>;
 pub type PriorityScore = u32;
@@ -43,6 +48,9 @@ pub trait Trait: system::Trait {
 
 	/// The maximum number of tasks that can be approved in an `ExecutionFrequency` period
 	type TaskLimit: Get<PriorityScore>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_event!(
@@ -125,7 +133,7 @@ decl_module! {
 		///
 		/// - the task initially has no priority
 		/// - only council members can schedule tasks
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::schedule_task()]
 		fn schedule_task(origin, data: Vec<u8>) -> DispatchResult {
 			let proposer = ensure_signed(origin)?;
 			ensure!(Self::is_on_council(&proposer), "only members of the council can schedule tasks");
@@ -154,7 +162,7 @@ decl_module! {
 		///
 		/// - members of the council have limited voting power to increase the priority
 		/// of tasks
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::signal_priority()]
 		fn signal_priority(origin, id: TaskId, signal: PriorityScore) -> DispatchResult {
 			let voter = ensure_signed(origin)?;
 			ensure!(Self::is_on_council(&voter), "The voting member must be on the council");
@@ -161,6 +161,7 @@ impl Trait for TestRuntime {
 	type SignalQuota = SignalQuota;
 	type ExecutionFrequency = ExecutionFrequency;
 	type TaskLimit = TaskLimit;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
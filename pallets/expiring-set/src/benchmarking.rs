@@ -0,0 +1,58 @@
+//! Benchmarks for the expiring set pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+/// An account that is not a member yet, with enough funds for several membership fees
+fn funded_account<T: Trait>() -> T::AccountId {
+	// Every benchmark adds a member, so the member count picks a fresh account each time
+	let who: T::AccountId = account("member", MemberCount::get(), 0);
+	let balance = T::MembershipFee::get().max(T::Currency::minimum_balance()) * 10u32.into();
+	T::Currency::make_free_balance_be(&who, balance);
+	who
+}
+
+benchmarks! {
+	_ { }
+
+	join {
+		let caller = funded_account::<T>();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Module::<T>::is_active(&caller));
+	}
+
+	renew {
+		let caller = funded_account::<T>();
+		Module::<T>::join(RawOrigin::Signed(caller.clone()).into())?;
+		let expiry = Module::<T>::expiry_of(&caller);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Module::<T>::expiry_of(&caller) > expiry);
+	}
+
+	leave {
+		let caller = funded_account::<T>();
+		Module::<T>::join(RawOrigin::Signed(caller.clone()).into())?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Module::<T>::expiry_of(&caller), None);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_join::<TestRuntime>());
+			assert_ok!(test_benchmark_renew::<TestRuntime>());
+			assert_ok!(test_benchmark_leave::<TestRuntime>());
+		});
+	}
+}
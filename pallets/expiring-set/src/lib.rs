@@ -0,0 +1,318 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! A pallet that implements a set of accounts whose membership expires.
+//!
+//! Accounts join by paying the `MembershipFee`, which buys `MembershipPeriod` blocks of
+//! membership. Paying the fee again with `renew` extends the membership by another period.
+//! Members who do not renew in time are swept out of the set in `on_initialize`, at most
+//! `ExpiryBatchSize` at a time, so a large number of simultaneous expiries cannot overload a block.
+
+use account_set::AccountSet;
+use frame_support::storage::{IterableStorageMap, StoragePrefixedMap};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, ExistenceRequirement, Get, OnUnbalanced, WithdrawReason},
+	weights::Weight,
+};
+use frame_system::{self as system, ensure_signed};
+use parity_scale_codec::Decode;
+use sp_runtime::traits::{One, Saturating};
+use sp_std::collections::btree_set::BTreeSet;
+use sp_std::prelude::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency in which membership fees are paid
+	type Currency: Currency<Self::AccountId>;
+
+	/// The fee for each period of membership, charged when joining and when renewing
+	type MembershipFee: Get<BalanceOf<Self>>;
+
+	/// The number of blocks each payment of the fee buys
+	type MembershipPeriod: Get<Self::BlockNumber>;
+
+	/// A maximum number of members. When membership reaches this number, no new members may join.
+	type MaxMembers: Get<u32>;
+
+	/// The maximum number of expiry queue entries `on_initialize` processes in a single block
+	type ExpiryBatchSize: Get<u32>;
+
+	/// Handler for the membership fees
+	type FeeDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ExpiringSet {
+		/// The set of all members, along with the block at which each membership expires
+		Members get(fn expiry_of):
+			map hasher(blake2_128_concat) T::AccountId => Option<T::BlockNumber>;
+
+		/// The total number of members stored in the map, including expired members who have not
+		/// been swept yet
+		MemberCount get(fn member_count): u32;
+
+		/// The members whose membership expires at each block
+		ExpiryQueue get(fn expiring_at):
+			map hasher(twox_64_concat) T::BlockNumber => Vec<T::AccountId>;
+
+		/// The earliest block whose expiries have not all been swept yet. `None` until the first
+		/// sweep.
+		SweepCursor get(fn sweep_cursor): Option<T::BlockNumber>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AccountId = <T as system::Trait>::AccountId,
+		BlockNumber = <T as system::Trait>::BlockNumber,
+	{
+		/// An account joined the set (member, expiry block)
+		MemberJoined(AccountId, BlockNumber),
+		/// A member renewed their membership (member, new expiry block)
+		MembershipRenewed(AccountId, BlockNumber),
+		/// A member left the set
+		MemberLeft(AccountId),
+		/// A membership expired and the member was removed from the set
+		MemberExpired(AccountId),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Cannot join as a member because you are already a member
+		AlreadyMember,
+		/// Cannot renew or give up membership because you are not currently a member
+		NotMember,
+		/// Cannot add another member because the limit is already reached
+		MembershipLimitReached,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		type Error = Error<T>;
+
+		/// Removes expired members, visiting at most `ExpiryBatchSize` queue entries
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let processed = Self::sweep(n);
+			// Reading and advancing the cursor, then for each entry the expiry queue and member
+			// count are read, and the queue, member and count are written
+			let db = T::DbWeight::get();
+			db.reads_writes(1, 1)
+				.saturating_add(db.reads_writes(2, 3).saturating_mul(processed as Weight))
+		}
+
+		/// Joins the set for one membership period, paying the membership fee
+		#[weight = T::WeightInfo::join()]
+		fn join(origin) -> DispatchResult {
+			let new_member = ensure_signed(origin)?;
+
+			let member_count = MemberCount::get();
+			ensure!(member_count < T::MaxMembers::get(), Error::<T>::MembershipLimitReached);
+			ensure!(!Members::<T>::contains_key(&new_member), Error::<T>::AlreadyMember);
+
+			// Paying is the last check that can fail, so nothing is written before it
+			Self::charge_fee(&new_member)?;
+
+			let now = system::Module::<T>::block_number();
+			let expiry = now.saturating_add(Self::period());
+			Members::<T>::insert(&new_member, expiry);
+			ExpiryQueue::<T>::mutate(expiry, |queue| queue.push(new_member.clone()));
+			MemberCount::put(member_count + 1); // overflow check not necessary because of maximum
+
+			Self::deposit_event(RawEvent::MemberJoined(new_member, expiry));
+			Ok(())
+		}
+
+		/// Extends the caller's membership by one membership period, paying the membership fee
+		///
+		/// A member whose membership has expired, but who has not been swept yet, may still renew.
+		/// Their new period starts at the current block.
+		#[weight = T::WeightInfo::renew()]
+		fn renew(origin) -> DispatchResult {
+			let member = ensure_signed(origin)?;
+			let old_expiry = Self::expiry_of(&member).ok_or(Error::<T>::NotMember)?;
+
+			Self::charge_fee(&member)?;
+
+			let now = system::Module::<T>::block_number();
+			let new_expiry = old_expiry.max(now).saturating_add(Self::period());
+			Members::<T>::insert(&member, new_expiry);
+			Self::dequeue(old_expiry, &member);
+			ExpiryQueue::<T>::mutate(new_expiry, |queue| queue.push(member.clone()));
+
+			Self::deposit_event(RawEvent::MembershipRenewed(member, new_expiry));
+			Ok(())
+		}
+
+		/// Leaves the set. The fee for the remaining membership period is not refunded.
+		#[weight = T::WeightInfo::leave()]
+		fn leave(origin) -> DispatchResult {
+			let old_member = ensure_signed(origin)?;
+			let expiry = Self::expiry_of(&old_member).ok_or(Error::<T>::NotMember)?;
+
+			Members::<T>::remove(&old_member);
+			Self::dequeue(expiry, &old_member);
+			MemberCount::mutate(|v| *v -= 1);
+
+			Self::deposit_event(RawEvent::MemberLeft(old_member));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Whether the account is a member whose membership has not expired
+	pub fn is_active(who: &T::AccountId) -> bool {
+		let now = system::Module::<T>::block_number();
+		Self::expiry_of(who).map_or(false, |expiry| expiry > now)
+	}
+
+	/// The membership period. Memberships are swept after the block in which they start, so a
+	/// period is at least one block long.
+	fn period() -> T::BlockNumber {
+		T::MembershipPeriod::get().max(One::one())
+	}
+
+	/// Withdraws the membership fee from the account and hands it to the `FeeDestination`
+	fn charge_fee(who: &T::AccountId) -> DispatchResult {
+		let fee = T::Currency::withdraw(
+			who,
+			T::MembershipFee::get(),
+			WithdrawReason::Fee.into(),
+			ExistenceRequirement::KeepAlive,
+		)?;
+		T::FeeDestination::on_unbalanced(fee);
+		Ok(())
+	}
+
+	/// Removes an account from the expiry queue of the given block
+	fn dequeue(expiry: T::BlockNumber, who: &T::AccountId) {
+		ExpiryQueue::<T>::mutate_exists(expiry, |maybe_queue| {
+			if let Some(queue) = maybe_queue {
+				queue.retain(|queued| queued != who);
+				if queue.is_empty() {
+					*maybe_queue = None;
+				}
+			}
+		});
+	}
+
+	/// Removes the members whose membership expired at or before block `n`
+	///
+	/// Each member removed, and each empty queue visited, counts towards `ExpiryBatchSize`.
+	/// Whatever does not fit in this block is left for the next one. Returns the number of entries
+	/// processed.
+	fn sweep(n: T::BlockNumber) -> u32 {
+		let mut budget = T::ExpiryBatchSize::get();
+		let mut block = Self::sweep_cursor().unwrap_or(n);
+		let mut processed = 0;
+
+		while block <= n && budget > 0 {
+			let mut expiring = ExpiryQueue::<T>::get(block);
+			let batch = expiring.len().min(budget as usize);
+			let rest = expiring.split_off(batch);
+
+			// Visiting an empty queue still costs a storage read
+			let cost = (batch as u32).max(1);
+			budget -= cost;
+			processed += cost;
+
+			for who in expiring {
+				// Renewing or leaving removes a member from this queue, so everyone left here
+				// expires now
+				Members::<T>::remove(&who);
+				MemberCount::mutate(|v| *v -= 1);
+				Self::deposit_event(RawEvent::MemberExpired(who));
+			}
+
+			if rest.is_empty() {
+				ExpiryQueue::<T>::remove(block);
+				block = block.saturating_add(One::one());
+			} else {
+				ExpiryQueue::<T>::insert(block, rest);
+				break;
+			}
+		}
+
+		SweepCursor::<T>::put(block);
+		processed
+	}
+}
+
+impl<T: Trait> AccountSet for Module<T> {
+	type AccountId = T::AccountId;
+
+	/// Only members whose membership has not expired are included
+	fn accounts() -> BTreeSet<T::AccountId> {
+		let now = system::Module::<T>::block_number();
+		<Members<T> as IterableStorageMap<T::AccountId, T::BlockNumber>>::iter()
+			.filter(|(_, expiry)| *expiry > now)
+			.map(|(acct, _)| acct)
+			.collect::<BTreeSet<_>>()
+	}
+
+	/// A single storage read, O(1). Expired members are not included.
+	fn contains(who: &T::AccountId) -> bool {
+		Self::is_active(who)
+	}
+
+	/// Reads the separately stored count, O(1). Expired members who have not been swept yet are
+	/// still counted.
+	fn count() -> u32 {
+		MemberCount::get()
+	}
+
+	/// Pages through the members in the order of their storage keys, which is the order of their
+	/// hashes. Expired members are skipped, but they count towards the limit, so a page never reads
+	/// more than `limit` members. A page may therefore be shorter than `limit`, or even empty,
+	/// while members remain. That only happens while expired members wait to be swept.
+	fn accounts_paged(start_after: Option<&T::AccountId>, limit: u32) -> Vec<T::AccountId> {
+		let now = system::Module::<T>::block_number();
+		let prefix = Members::<T>::final_prefix();
+		let mut key = match start_after {
+			Some(who) => Members::<T>::hashed_key_for(who),
+			None => prefix.to_vec(),
+		};
+
+		let mut accounts = Vec::new();
+		for _ in 0..limit {
+			let next = match sp_io::storage::next_key(&key) {
+				Some(next) if next.starts_with(&prefix) => next,
+				_ => break,
+			};
+
+			// Each key is the prefix, the 16 byte blake2_128 hash, and then the account itself
+			if let Some(mut encoded) = next.get(prefix.len() + 16..) {
+				if let Ok(who) = T::AccountId::decode(&mut encoded) {
+					if Self::expiry_of(&who).map_or(false, |expiry| expiry > now) {
+						accounts.push(who);
+					}
+				}
+			}
+			key = next;
+		}
+		accounts
+	}
+}
@@ -0,0 +1,308 @@
+use crate::*;
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types,
+	traits::OnInitialize,
+};
+use frame_system as system;
+use sp_core::H256;
+use sp_io::TestExternalities;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MembershipFee: u64 = 10;
+	pub const MembershipPeriod: u64 = 10;
+	pub const MaxMembers: u32 = 16;
+	pub const ExpiryBatchSize: u32 = 2;
+}
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<TestRuntime>;
+}
+
+mod expiring_set {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for TestRuntime {
+		expiring_set<T>,
+		system<T>,
+		balances<T>,
+	}
+}
+
+impl Trait for TestRuntime {
+	type Event = TestEvent;
+	type Currency = balances::Module<Self>;
+	type MembershipFee = MembershipFee;
+	type MembershipPeriod = MembershipPeriod;
+	type MaxMembers = MaxMembers;
+	type ExpiryBatchSize = ExpiryBatchSize;
+	type FeeDestination = ();
+	type WeightInfo = ();
+}
+
+pub type System = system::Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
+pub type ExpiringSet = Module<TestRuntime>;
+
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	pub fn build() -> TestExternalities {
+		let mut storage = system::GenesisConfig::default()
+			.build_storage::<TestRuntime>()
+			.unwrap();
+		balances::GenesisConfig::<TestRuntime> {
+			balances: (0..20).map(|i| (i, 100)).collect(),
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		let mut ext = TestExternalities::from(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+/// Runs `on_initialize` for every block up to and including `n`
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		ExpiringSet::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn join_charges_fee() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(ExpiringSet::join(Origin::signed(1)));
+
+		assert_eq!(Balances::free_balance(&1), 90);
+		assert_eq!(Balances::total_issuance(), 1990);
+		assert_eq!(ExpiringSet::expiry_of(&1), Some(11));
+		assert_eq!(ExpiringSet::expiring_at(11), vec![1]);
+		assert!(ExpiringSet::contains(&1));
+
+		let expected_event = TestEvent::expiring_set(RawEvent::MemberJoined(1, 11));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_noop!(ExpiringSet::join(Origin::signed(1)), Error::<TestRuntime>::AlreadyMember);
+	})
+}
+
+#[test]
+fn cant_join_without_fee() {
+	ExtBuilder::build().execute_with(|| {
+		// 50 has no funds to pay the fee
+		assert_noop!(
+			ExpiringSet::join(Origin::signed(50)),
+			balances::Error::<TestRuntime, _>::InsufficientBalance
+		);
+	})
+}
+
+#[test]
+fn cant_exceed_max_members() {
+	ExtBuilder::build().execute_with(|| {
+		for i in 0..16 {
+			assert_ok!(ExpiringSet::join(Origin::signed(i)));
+		}
+
+		assert_noop!(
+			ExpiringSet::join(Origin::signed(16)),
+			Error::<TestRuntime>::MembershipLimitReached
+		);
+	})
+}
+
+#[test]
+fn memberships_expire() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(ExpiringSet::join(Origin::signed(1)));
+
+		run_to_block(10);
+		assert!(ExpiringSet::contains(&1));
+
+		run_to_block(11);
+		assert!(!ExpiringSet::contains(&1));
+		assert_eq!(ExpiringSet::expiry_of(&1), None);
+		assert_eq!(ExpiringSet::count(), 0);
+
+		let expected_event = TestEvent::expiring_set(RawEvent::MemberExpired(1));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_noop!(ExpiringSet::renew(Origin::signed(1)), Error::<TestRuntime>::NotMember);
+	})
+}
+
+#[test]
+fn sweeps_are_bounded() {
+	ExtBuilder::build().execute_with(|| {
+		for i in 1..=3 {
+			assert_ok!(ExpiringSet::join(Origin::signed(i)));
+		}
+
+		// Only two of the three expiring members are removed in the first block
+		run_to_block(11);
+		assert_eq!(ExpiringSet::count(), 1);
+		assert_eq!(ExpiringSet::expiring_at(11), vec![3]);
+		// Unswept members no longer count as members
+		assert!(!ExpiringSet::contains(&3));
+
+		run_to_block(12);
+		assert_eq!(ExpiringSet::count(), 0);
+		assert!(!ExpiryQueue::<TestRuntime>::contains_key(11));
+		assert_eq!(ExpiringSet::sweep_cursor(), Some(13));
+	})
+}
+
+#[test]
+fn renewal_extends_membership() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(ExpiringSet::join(Origin::signed(1)));
+
+		run_to_block(5);
+		assert_ok!(ExpiringSet::renew(Origin::signed(1)));
+		assert_eq!(Balances::free_balance(&1), 80);
+		assert_eq!(ExpiringSet::expiry_of(&1), Some(21));
+		assert!(!ExpiryQueue::<TestRuntime>::contains_key(11));
+		assert_eq!(ExpiringSet::expiring_at(21), vec![1]);
+
+		let expected_event = TestEvent::expiring_set(RawEvent::MembershipRenewed(1, 21));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		run_to_block(20);
+		assert!(ExpiringSet::contains(&1));
+		run_to_block(21);
+		assert!(!ExpiringSet::contains(&1));
+	})
+}
+
+#[test]
+fn unswept_members_may_renew() {
+	ExtBuilder::build().execute_with(|| {
+		for i in 1..=3 {
+			assert_ok!(ExpiringSet::join(Origin::signed(i)));
+		}
+		run_to_block(11);
+
+		// 3 expired but was not swept yet, so the new period starts now
+		assert_ok!(ExpiringSet::renew(Origin::signed(3)));
+		assert_eq!(ExpiringSet::expiry_of(&3), Some(21));
+		assert!(ExpiringSet::contains(&3));
+
+		run_to_block(12);
+		assert!(ExpiringSet::contains(&3));
+		assert_eq!(ExpiringSet::count(), 1);
+	})
+}
+
+#[test]
+fn leave_works() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(ExpiringSet::join(Origin::signed(1)));
+		assert_ok!(ExpiringSet::leave(Origin::signed(1)));
+
+		assert!(!ExpiringSet::contains(&1));
+		assert_eq!(ExpiringSet::count(), 0);
+		assert!(!ExpiryQueue::<TestRuntime>::contains_key(11));
+
+		let expected_event = TestEvent::expiring_set(RawEvent::MemberLeft(1));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_noop!(ExpiringSet::leave(Origin::signed(1)), Error::<TestRuntime>::NotMember);
+	})
+}
+
+#[test]
+fn paging_skips_expired_members() {
+	ExtBuilder::build().execute_with(|| {
+		for i in 1..=3 {
+			assert_ok!(ExpiringSet::join(Origin::signed(i)));
+		}
+		run_to_block(5);
+		for i in 4..=6 {
+			assert_ok!(ExpiringSet::join(Origin::signed(i)));
+		}
+
+		// 1, 2 and 3 expire at block 11, but only 1 and 2 are swept
+		run_to_block(11);
+
+		let mut seen = Vec::new();
+		let mut cursor = None;
+		loop {
+			let page = ExpiringSet::accounts_paged(cursor.as_ref(), 2);
+			if page.is_empty() {
+				break;
+			}
+			cursor = page.last().cloned();
+			seen.extend(page);
+		}
+		seen.sort();
+		assert_eq!(seen, vec![4, 5, 6]);
+		assert_eq!(ExpiringSet::accounts().into_iter().collect::<Vec<_>>(), vec![4, 5, 6]);
+	})
+}
+
+#[test]
+fn paging_counts_expired_members_towards_the_limit() {
+	ExtBuilder::build().execute_with(|| {
+		for i in &[1, 3, 5] {
+			assert_ok!(ExpiringSet::join(Origin::signed(*i)));
+		}
+		// 1, 3 and 5 expire at block 11, but are not swept
+		System::set_block_number(11);
+		assert_ok!(ExpiringSet::join(Origin::signed(4)));
+
+		// The members are stored in the order of their hashes: 5, 3, 1, then 4
+		assert_eq!(ExpiringSet::accounts_paged(None, 3), Vec::<u64>::new());
+		assert_eq!(ExpiringSet::accounts_paged(None, 4), vec![4]);
+		assert_eq!(ExpiringSet::accounts_paged(Some(&1), 1), vec![4]);
+	})
+}
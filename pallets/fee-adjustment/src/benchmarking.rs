@@ -0,0 +1,30 @@
+//! Benchmarks for the fee-adjustment pallet
+
+use super::*;
+use frame_benchmarking::benchmarks;
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	set_adjustment_variable {
+		let variable = Multiplier::saturating_from_rational(1, 1_000);
+	}: _(RawOrigin::Root, variable)
+	verify {
+		assert_eq!(Module::<T>::adjustment_variable(), variable);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_set_adjustment_variable::<TestRuntime>());
+		});
+	}
+}
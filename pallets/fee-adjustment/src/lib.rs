@@ -0,0 +1,150 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Adjusts the transaction fee multiplier to how full blocks are
+//!
+//! After every block the multiplier moves towards making blocks exactly as full as
+//! `TargetBlockFullness`. When the last block was fuller than the target, the multiplier goes up,
+//! and when it was emptier, the multiplier goes down. How quickly it moves is governed by the
+//! adjustment variable, which is kept in storage so it can be tuned by governance. The multiplier
+//! never leaves the bounds set by `MinimumMultiplier` and `MaximumMultiplier`.
+//!
+//! Only the weight of normal transactions counts towards fullness, measured against the share of
+//! the block that normal transactions may fill. Operational transactions have their own space in
+//! the block, so they do not raise fees for everyone else.
+//!
+//! To use it, set `TargetedFeeAdjustment<Runtime>` as the transaction payment pallet's
+//! `FeeMultiplierUpdate`.
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::Get,
+	weights::{DispatchClass, Weight},
+};
+use frame_system::{self as system, ensure_root};
+use sp_runtime::{
+	traits::{Convert, Saturating},
+	FixedPointNumber, Perquintill,
+};
+use sp_std::marker::PhantomData;
+pub use transaction_payment::Multiplier;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// How full blocks should be on average
+	type TargetBlockFullness: Get<Perquintill>;
+
+	/// The lowest the multiplier may go. A multiplier of -1 makes the weight fee zero, so this
+	/// should not be lower than that.
+	type MinimumMultiplier: Get<Multiplier>;
+
+	/// The highest the multiplier may go
+	type MaximumMultiplier: Get<Multiplier>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as FeeAdjustment {
+		/// How strongly the multiplier reacts to a block that misses the target. The default
+		/// of 0.00004 is the value used by Substrate's node runtime.
+		AdjustmentVariable get(fn adjustment_variable): Multiplier =
+			Multiplier::saturating_from_rational(4, 100_000);
+	}
+}
+
+decl_event!(
+	pub enum Event {
+		/// The adjustment variable was changed by governance
+		AdjustmentVariableSet(Multiplier),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The adjustment variable must not be negative
+		NegativeAdjustmentVariable,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		type Error = Error<T>;
+
+		/// Sets how strongly the multiplier reacts to block fullness. This call requires root
+		/// origin, so it must come from a governance mechanism such as the Sudo pallet.
+		#[weight = T::WeightInfo::set_adjustment_variable()]
+		fn set_adjustment_variable(origin, variable: Multiplier) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!variable.is_negative(), Error::<T>::NegativeAdjustmentVariable);
+
+			AdjustmentVariable::put(variable);
+
+			Self::deposit_event(Event::AdjustmentVariableSet(variable));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The multiplier that follows `previous` after a block with `block_weight` of normal
+	/// transactions
+	///
+	/// With `v` the adjustment variable and `s` the difference between the block's fullness and
+	/// the target, the multiplier changes by `v * s + v^2 * s^2 / 2`, which approximates the
+	/// exponential update `e^(v * s)` used by Substrate's node runtime.
+	pub fn next_multiplier(previous: Multiplier, block_weight: Weight) -> Multiplier {
+		// The part of the block that normal transactions may fill
+		let max_weight = <T as system::Trait>::AvailableBlockRatio::get()
+			* <T as system::Trait>::MaximumBlockWeight::get();
+		let block_weight = block_weight.min(max_weight) as u128;
+		let target_weight = (T::TargetBlockFullness::get() * max_weight) as u128;
+
+		// Whether the block was fuller than the target
+		let positive = block_weight >= target_weight;
+		let diff_abs = block_weight.max(target_weight) - block_weight.min(target_weight);
+		let diff = Multiplier::saturating_from_rational(diff_abs, max_weight.max(1));
+
+		let v = Self::adjustment_variable();
+		let first_term = v.saturating_mul(diff);
+		let second_term = v
+			.saturating_mul(v)
+			.saturating_mul(diff)
+			.saturating_mul(diff)
+			.saturating_mul(Multiplier::saturating_from_rational(1, 2));
+
+		let next = if positive {
+			previous.saturating_add(first_term.saturating_add(second_term))
+		} else {
+			// The first term is always larger than the second, as long as `v * s` is below 2
+			previous.saturating_sub(first_term.saturating_sub(second_term))
+		};
+
+		next.max(T::MinimumMultiplier::get())
+			.min(T::MaximumMultiplier::get())
+	}
+}
+
+/// Updates the fee multiplier according to the weight of the block that just finished
+pub struct TargetedFeeAdjustment<T>(PhantomData<T>);
+
+impl<T: Trait> Convert<Multiplier, Multiplier> for TargetedFeeAdjustment<T> {
+	fn convert(previous: Multiplier) -> Multiplier {
+		let block_weight =
+			<system::Module<T>>::all_extrinsics_weight().get(DispatchClass::Normal);
+		Module::<T>::next_multiplier(previous, block_weight)
+	}
+}
@@ -0,0 +1,267 @@
+use crate::*;
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types,
+	weights::DispatchClass,
+};
+use sp_core::H256;
+use sp_io::TestExternalities;
+use sp_runtime::{
+	testing::Header,
+	traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+use system::{EventRecord, Phase, RawOrigin};
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1_000_000;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_integer(-1);
+	pub MaximumMultiplier: Multiplier = Multiplier::saturating_from_integer(10);
+}
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+mod fee_adjustment {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for TestRuntime {
+		fee_adjustment,
+		system<T>,
+	}
+}
+
+impl Trait for TestRuntime {
+	type Event = TestEvent;
+	type TargetBlockFullness = TargetBlockFullness;
+	type MinimumMultiplier = MinimumMultiplier;
+	type MaximumMultiplier = MaximumMultiplier;
+	type WeightInfo = ();
+}
+
+pub type System = system::Module<TestRuntime>;
+pub type FeeAdjustment = Module<TestRuntime>;
+
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	pub fn build() -> TestExternalities {
+		let storage = system::GenesisConfig::default()
+			.build_storage::<TestRuntime>()
+			.unwrap();
+		let mut ext = TestExternalities::from(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+/// The weight normal transactions may fill in a block
+fn normal_weight() -> Weight {
+	AvailableBlockRatio::get() * MaximumBlockWeight::get()
+}
+
+/// A block whose normal transactions fill `percent` of their share
+fn block(percent: u64) -> Weight {
+	normal_weight() * percent / 100
+}
+
+/// Runs `rounds` blocks whose weight is given by `demand` for the current multiplier, and
+/// returns the multiplier after the last one
+fn simulate(rounds: u32, demand: impl Fn(Multiplier) -> Weight) -> Multiplier {
+	let mut multiplier = Multiplier::saturating_from_integer(0);
+	for _ in 0..rounds {
+		multiplier = FeeAdjustment::next_multiplier(multiplier, demand(multiplier));
+	}
+	multiplier
+}
+
+#[test]
+fn multiplier_holds_at_target() {
+	ExtBuilder::build().execute_with(|| {
+		let start = Multiplier::saturating_from_rational(1, 2);
+		assert_eq!(FeeAdjustment::next_multiplier(start, block(25)), start);
+	});
+}
+
+#[test]
+fn multiplier_follows_fullness() {
+	ExtBuilder::build().execute_with(|| {
+		let start = Multiplier::saturating_from_integer(0);
+		let busy = FeeAdjustment::next_multiplier(start, block(100));
+		let quiet = FeeAdjustment::next_multiplier(start, block(0));
+
+		assert!(busy > start);
+		assert!(quiet < start);
+		// A fuller block raises the multiplier more
+		assert!(FeeAdjustment::next_multiplier(start, block(50)) < busy);
+	});
+}
+
+#[test]
+fn multiplier_respects_bounds() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(FeeAdjustment::set_adjustment_variable(
+			RawOrigin::Root.into(),
+			Multiplier::saturating_from_integer(1)
+		));
+
+		assert_eq!(simulate(100, |_| block(100)), MaximumMultiplier::get());
+		assert_eq!(simulate(100, |_| block(0)), MinimumMultiplier::get());
+	});
+}
+
+#[test]
+fn convert_uses_system_block_weight() {
+	ExtBuilder::build().execute_with(|| {
+		// Nothing was executed, so the block is empty and the multiplier drops
+		let next =
+			TargetedFeeAdjustment::<TestRuntime>::convert(Multiplier::saturating_from_integer(0));
+		assert_eq!(
+			next,
+			FeeAdjustment::next_multiplier(Multiplier::saturating_from_integer(0), 0)
+		);
+		assert!(next < Multiplier::saturating_from_integer(0));
+	});
+}
+
+#[test]
+fn convert_only_counts_normal_weight() {
+	ExtBuilder::build().execute_with(|| {
+		// Operational transactions alone fill the whole block
+		System::register_extra_weight_unchecked(
+			MaximumBlockWeight::get(),
+			DispatchClass::Operational,
+		);
+
+		// But no normal transactions were included, so the multiplier still drops
+		let start = Multiplier::saturating_from_integer(0);
+		let next = TargetedFeeAdjustment::<TestRuntime>::convert(start);
+		assert_eq!(next, FeeAdjustment::next_multiplier(start, 0));
+		assert!(next < start);
+
+		// Normal transactions filling their share of the block raise it
+		System::register_extra_weight_unchecked(normal_weight(), DispatchClass::Normal);
+		let next = TargetedFeeAdjustment::<TestRuntime>::convert(start);
+		assert_eq!(next, FeeAdjustment::next_multiplier(start, block(100)));
+		assert!(next > start);
+	});
+}
+
+#[test]
+fn multiplier_converges_with_demand() {
+	ExtBuilder::build().execute_with(|| {
+		// A large adjustment variable, so the simulation settles in a few hundred blocks
+		assert_ok!(FeeAdjustment::set_adjustment_variable(
+			RawOrigin::Root.into(),
+			Multiplier::saturating_from_rational(1, 10)
+		));
+
+		// Users fill 75% of a block when there is no extra fee, and 25% less for each unit
+		// the multiplier rises. Blocks are 25% full, which is the target, at a multiplier of 2.
+		let demand = |multiplier: Multiplier| {
+			let fullness = Multiplier::saturating_from_rational(3, 4)
+				.saturating_sub(
+					Multiplier::saturating_from_rational(1, 4).saturating_mul(multiplier),
+				)
+				.max(Multiplier::saturating_from_integer(0))
+				.min(Multiplier::saturating_from_integer(1));
+			fullness.saturating_mul_int(normal_weight())
+		};
+
+		let multiplier = simulate(1_000, demand);
+		let expected = Multiplier::saturating_from_integer(2);
+		let tolerance = Multiplier::saturating_from_rational(1, 1_000);
+		assert!(multiplier > expected.saturating_sub(tolerance));
+		assert!(multiplier < expected.saturating_add(tolerance));
+
+		// Blocks are now as full as the target, give or take a rounding error
+		let weight = demand(multiplier);
+		assert!(weight.max(block(25)) - weight.min(block(25)) < block(1));
+
+		// And the multiplier stays there
+		let next = FeeAdjustment::next_multiplier(multiplier, weight);
+		assert!(next > expected.saturating_sub(tolerance));
+		assert!(next < expected.saturating_add(tolerance));
+	});
+}
+
+#[test]
+fn set_adjustment_variable_works() {
+	ExtBuilder::build().execute_with(|| {
+		let variable = Multiplier::saturating_from_rational(1, 1_000);
+		assert_ok!(FeeAdjustment::set_adjustment_variable(
+			RawOrigin::Root.into(),
+			variable
+		));
+		assert_eq!(FeeAdjustment::adjustment_variable(), variable);
+
+		let expected_event = TestEvent::fee_adjustment(Event::AdjustmentVariableSet(variable));
+		assert_eq!(
+			System::events(),
+			vec![EventRecord {
+				phase: Phase::Initialization,
+				event: expected_event,
+				topics: vec![],
+			}]
+		);
+	});
+}
+
+#[test]
+fn set_adjustment_variable_requires_root() {
+	ExtBuilder::build().execute_with(|| {
+		let variable = Multiplier::saturating_from_rational(1, 1_000);
+		assert_noop!(
+			FeeAdjustment::set_adjustment_variable(Origin::signed(1), variable),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn negative_adjustment_variable_is_rejected() {
+	ExtBuilder::build().execute_with(|| {
+		assert_noop!(
+			FeeAdjustment::set_adjustment_variable(
+				RawOrigin::Root.into(),
+				Multiplier::saturating_from_integer(-1)
+			),
+			Error::<TestRuntime>::NegativeAdjustmentVariable
+		);
+	});
+}
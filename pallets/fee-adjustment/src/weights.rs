@@ -0,0 +1,24 @@
+//! Weights for the fee-adjustment pallet, one for each benchmark in `benchmarking.rs`
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the fee-adjustment pallet
+pub trait WeightInfo {
+	fn set_adjustment_variable() -> Weight;
+}
+
+/// Weights for the fee-adjustment pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn set_adjustment_variable() -> Weight {
+		(12_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn set_adjustment_variable() -> Weight {
+		(12_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
@@ -0,0 +1,15 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+
+pub use fee_curve::Coefficient;
+use parity_scale_codec::Codec;
+use sp_std::vec::Vec;
+
+// Here we declare the runtime API. It is implemented in the `impl` block in the runtime
+// amalgamator file (the `runtime/src/lib.rs`)
+sp_api::decl_runtime_apis! {
+	pub trait FeeCurveApi<Balance> where Balance: Codec {
+		/// The coefficients of the polynomial that currently converts weight to fee
+		fn query_fee_curve() -> Vec<Coefficient<Balance>>;
+	}
+}
@@ -0,0 +1,38 @@
+//! Benchmarks for the fee-curve pallet
+
+use super::*;
+use frame_benchmarking::benchmarks;
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	set_curve {
+		let c in 1 .. T::MaxCoefficients::get();
+		let coefficients: Vec<_> = (0..c)
+			.map(|degree| Coefficient {
+				coeff_integer: 1u32.into(),
+				coeff_frac: Perbill::zero(),
+				negative: false,
+				degree: degree as u8,
+			})
+			.collect();
+	}: _(RawOrigin::Root, coefficients.clone())
+	verify {
+		assert_eq!(Module::<T>::coefficients(), coefficients);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_set_curve::<TestRuntime>());
+		});
+	}
+}
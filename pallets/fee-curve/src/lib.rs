@@ -0,0 +1,218 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Keeps the polynomial that converts weight to fee in storage
+//!
+//! The transaction payment pallet turns the weight of each transaction into a fee with a
+//! `WeightToFeePolynomial`. Usually that polynomial is fixed when the runtime is compiled. This
+//! pallet keeps its coefficients in storage instead, so governance can change the curve with a
+//! root call. Until it does, the curve is `DefaultCurve`.
+//!
+//! Curves that would charge less than nothing for some weight are rejected. The transaction
+//! payment pallet saturates such fees to zero, so those transactions would be free.
+//!
+//! To use it, set `StoredWeightToFee<Runtime>` as the transaction payment pallet's `WeightToFee`.
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::Get,
+	weights::{WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial},
+	Parameter,
+};
+use frame_system::{self as system, ensure_root};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{AtLeast32Bit, Member, SaturatedConversion},
+	PerThing, Perbill, RuntimeDebug,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The type that fees are computed in
+	type Balance: Parameter + Member + AtLeast32Bit + Default + Copy;
+
+	/// The curve that is used until governance sets another one
+	type DefaultCurve: WeightToFeePolynomial<Balance = Self::Balance>;
+
+	/// The most coefficients a curve may have
+	type MaxCoefficients: Get<u32>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+/// One term of the curve: `(coeff_integer + coeff_frac) * weight^degree`, which is subtracted
+/// from the fee rather than added to it when `negative` is set
+///
+/// It has the same fields as frame-support's `WeightToFeeCoefficient`, along with the traits
+/// needed to keep it in storage.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct Coefficient<Balance> {
+	/// The integral part of the coefficient
+	pub coeff_integer: Balance,
+	/// The fractional part of the coefficient
+	pub coeff_frac: Perbill,
+	/// Whether the term is subtracted
+	pub negative: bool,
+	/// The power of the weight that the coefficient multiplies
+	pub degree: u8,
+}
+
+impl<Balance: AtLeast32Bit> Coefficient<Balance> {
+	/// The size of the coefficient in billionths
+	fn billionths(&self) -> u128 {
+		self.coeff_integer
+			.clone()
+			.saturated_into::<u128>()
+			.saturating_mul(1_000_000_000)
+			.saturating_add(self.coeff_frac.deconstruct().into())
+	}
+}
+
+impl<Balance> From<WeightToFeeCoefficient<Balance>> for Coefficient<Balance> {
+	fn from(c: WeightToFeeCoefficient<Balance>) -> Self {
+		Coefficient {
+			coeff_integer: c.coeff_integer,
+			coeff_frac: c.coeff_frac,
+			negative: c.negative,
+			degree: c.degree,
+		}
+	}
+}
+
+impl<Balance> From<Coefficient<Balance>> for WeightToFeeCoefficient<Balance> {
+	fn from(c: Coefficient<Balance>) -> Self {
+		WeightToFeeCoefficient {
+			coeff_integer: c.coeff_integer,
+			coeff_frac: c.coeff_frac,
+			negative: c.negative,
+			degree: c.degree,
+		}
+	}
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as FeeCurve {
+		/// The coefficients of the curve, with the positive ones first
+		Coefficients get(fn coefficients): Vec<Coefficient<T::Balance>> =
+			Module::<T>::default_curve();
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		Balance = <T as Trait>::Balance,
+	{
+		/// Governance replaced the curve
+		CurveSet(Vec<Coefficient<Balance>>),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The curve has more coefficients than `MaxCoefficients`
+		TooManyCoefficients,
+		/// The curve may go below zero for some weight
+		NegativeFees,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		type Error = Error<T>;
+
+		/// Replaces the curve that converts weight to fee. This call requires root origin, so it
+		/// must come from a governance mechanism such as the Sudo pallet.
+		#[weight = T::WeightInfo::set_curve(coefficients.len() as u32)]
+		fn set_curve(origin, coefficients: Vec<Coefficient<T::Balance>>) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				coefficients.len() as u32 <= T::MaxCoefficients::get(),
+				Error::<T>::TooManyCoefficients
+			);
+			ensure!(Self::is_non_negative(&coefficients), Error::<T>::NegativeFees);
+
+			let coefficients = Self::positive_first(coefficients);
+			<Coefficients<T>>::put(&coefficients);
+
+			Self::deposit_event(RawEvent::CurveSet(coefficients));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Whether the curve is at least zero for every weight
+	///
+	/// Weights are whole numbers, so once the weight is at least one, every power of it is at
+	/// least as large as the powers below. Let `D` be the highest degree of a negative term. When
+	/// the positive coefficients of degree `D` or more add up to at least the negative
+	/// coefficients, the positive terms outweigh the negative ones for every weight from one up.
+	/// At a weight of zero only the constant term is left, so it must not be negative.
+	///
+	/// The check is conservative, so it rejects some curves that never go below zero.
+	pub fn is_non_negative(coefficients: &[Coefficient<T::Balance>]) -> bool {
+		let negatives = coefficients.iter().filter(|c| c.negative);
+		let highest = match negatives.clone().map(|c| c.degree).max() {
+			Some(degree) => degree,
+			None => return true,
+		};
+		if negatives.clone().any(|c| c.degree == 0) {
+			return false;
+		}
+
+		let owed = negatives.fold(0u128, |sum, c| sum.saturating_add(c.billionths()));
+		let covered = coefficients
+			.iter()
+			.filter(|c| !c.negative && c.degree >= highest)
+			.fold(0u128, |sum, c| sum.saturating_add(c.billionths()));
+		covered >= owed
+	}
+
+	/// Moves the negative coefficients to the end. The fee is evaluated term by term and
+	/// saturates at zero, so a negative term that came first could be lost.
+	fn positive_first(
+		mut coefficients: Vec<Coefficient<T::Balance>>,
+	) -> Vec<Coefficient<T::Balance>> {
+		coefficients.sort_by_key(|c| c.negative);
+		coefficients
+	}
+
+	/// The coefficients of `DefaultCurve`
+	fn default_curve() -> Vec<Coefficient<T::Balance>> {
+		Self::positive_first(
+			T::DefaultCurve::polynomial()
+				.into_iter()
+				.map(Into::into)
+				.collect(),
+		)
+	}
+}
+
+/// Converts weight to fee with the curve in storage
+pub struct StoredWeightToFee<T>(PhantomData<T>);
+
+impl<T: Trait> WeightToFeePolynomial for StoredWeightToFee<T> {
+	type Balance = <T::DefaultCurve as WeightToFeePolynomial>::Balance;
+
+	fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+		Module::<T>::coefficients()
+			.into_iter()
+			.map(Into::into)
+			.collect()
+	}
+}
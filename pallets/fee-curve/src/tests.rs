@@ -0,0 +1,203 @@
+use crate::*;
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types,
+	weights::{IdentityFee, Weight},
+};
+use sp_core::H256;
+use sp_io::TestExternalities;
+use sp_runtime::{
+	testing::Header,
+	traits::{BadOrigin, BlakeTwo256, IdentityLookup},
+};
+use system::{EventRecord, Phase, RawOrigin};
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const MaxCoefficients: u32 = 4;
+}
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+mod fee_curve {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for TestRuntime {
+		fee_curve<T>,
+		system<T>,
+	}
+}
+
+impl Trait for TestRuntime {
+	type Event = TestEvent;
+	type Balance = u64;
+	type DefaultCurve = IdentityFee<u64>;
+	type MaxCoefficients = MaxCoefficients;
+	type WeightInfo = ();
+}
+
+pub type System = system::Module<TestRuntime>;
+pub type FeeCurve = Module<TestRuntime>;
+pub type WeightToFee = StoredWeightToFee<TestRuntime>;
+
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	pub fn build() -> TestExternalities {
+		let storage = system::GenesisConfig::default()
+			.build_storage::<TestRuntime>()
+			.unwrap();
+		let mut ext = TestExternalities::from(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+/// The term `(integer + percent / 100) * weight^degree`, or its negation
+fn term(integer: u64, percent: u32, negative: bool, degree: u8) -> Coefficient<u64> {
+	Coefficient {
+		coeff_integer: integer,
+		coeff_frac: Perbill::from_percent(percent),
+		negative,
+		degree,
+	}
+}
+
+/// fee = 3 w^2 - 2.4 w, with the negative term first
+fn quadratic() -> Vec<Coefficient<u64>> {
+	vec![term(2, 40, true, 1), term(3, 0, false, 2)]
+}
+
+#[test]
+fn default_curve_is_used() {
+	ExtBuilder::build().execute_with(|| {
+		assert_eq!(FeeCurve::coefficients(), vec![term(1, 0, false, 1)]);
+		assert_eq!(WeightToFee::calc(&10), 10);
+	});
+}
+
+#[test]
+fn set_curve_works() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(FeeCurve::set_curve(RawOrigin::Root.into(), quadratic()));
+
+		// The negative term is moved last
+		let stored = vec![term(3, 0, false, 2), term(2, 40, true, 1)];
+		assert_eq!(FeeCurve::coefficients(), stored);
+		assert_eq!(WeightToFee::calc(&10), 300 - 24);
+		// Subtracting first would saturate at zero and charge 3 here
+		assert_eq!(WeightToFee::calc(&1), 1);
+
+		let expected_event = TestEvent::fee_curve(RawEvent::CurveSet(stored));
+		assert_eq!(
+			System::events(),
+			vec![EventRecord {
+				phase: Phase::Initialization,
+				event: expected_event,
+				topics: vec![],
+			}]
+		);
+	});
+}
+
+#[test]
+fn set_curve_requires_root() {
+	ExtBuilder::build().execute_with(|| {
+		assert_noop!(
+			FeeCurve::set_curve(Origin::signed(1), quadratic()),
+			BadOrigin
+		);
+	});
+}
+
+#[test]
+fn too_many_coefficients_are_rejected() {
+	ExtBuilder::build().execute_with(|| {
+		let curve = (0..5).map(|degree| term(1, 0, false, degree)).collect();
+		assert_noop!(
+			FeeCurve::set_curve(RawOrigin::Root.into(), curve),
+			Error::<TestRuntime>::TooManyCoefficients
+		);
+	});
+}
+
+#[test]
+fn negative_curves_are_rejected() {
+	ExtBuilder::build().execute_with(|| {
+		let negative_curves = vec![
+			// Below zero at a weight of zero
+			vec![term(1, 0, true, 0), term(5, 0, false, 1)],
+			// Below zero for weights from 1 to 9
+			vec![term(1, 0, false, 2), term(10, 0, true, 1)],
+			// Eventually below zero, as the negative term has the highest degree
+			vec![term(1, 0, true, 2), term(100, 0, false, 1)],
+			// Only just below zero at a weight of one
+			vec![term(1, 0, false, 3), term(1, 1, true, 1)],
+		];
+		for curve in negative_curves {
+			assert_noop!(
+				FeeCurve::set_curve(RawOrigin::Root.into(), curve),
+				Error::<TestRuntime>::NegativeFees
+			);
+		}
+	});
+}
+
+#[test]
+fn non_negative_curves_are_accepted() {
+	ExtBuilder::build().execute_with(|| {
+		assert!(FeeCurve::is_non_negative(&quadratic()));
+		// Exactly zero at a weight of one
+		assert!(FeeCurve::is_non_negative(&[
+			term(1, 0, false, 2),
+			term(1, 0, true, 1)
+		]));
+		// A free curve
+		assert!(FeeCurve::is_non_negative(&[]));
+		// A positive constant does not help at larger weights, but a positive cubic does
+		assert!(FeeCurve::is_non_negative(&[
+			term(5, 0, false, 0),
+			term(1, 0, true, 2),
+			term(1, 0, false, 3)
+		]));
+		assert!(!FeeCurve::is_non_negative(&[
+			term(5, 0, false, 0),
+			term(1, 0, true, 2)
+		]));
+	});
+}
@@ -0,0 +1,28 @@
+//! Weights for the fee-curve pallet, one for each benchmark in `benchmarking.rs`
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the fee-curve pallet
+pub trait WeightInfo {
+	fn set_curve(c: u32) -> Weight;
+}
+
+/// Weights for the fee-curve pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn set_curve(c: u32) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((500_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn set_curve(c: u32) -> Weight {
+		(15_000_000 as Weight)
+			.saturating_add((500_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
@@ -0,0 +1,49 @@
+//! Benchmarks for the fixed point pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	update_permill {
+		let caller: T::AccountId = account("caller", 0, 0);
+		PermillAccumulator::put(Permill::one());
+	}: _(RawOrigin::Signed(caller), Permill::from_percent(50))
+	verify {
+		assert_eq!(Module::<T>::permill_value(), Permill::from_percent(50));
+	}
+
+	update_fixed {
+		let caller: T::AccountId = account("caller", 0, 0);
+		FixedAccumulator::put(U16F16::from_num(1));
+	}: _(RawOrigin::Signed(caller), U16F16::from_bits(3 << 15))
+	verify {
+		assert_eq!(Module::<T>::fixed_value(), U16F16::from_bits(3 << 15));
+	}
+
+	update_manual {
+		let caller: T::AccountId = account("caller", 0, 0);
+		ManualAccumulator::put(1 << 16);
+	}: _(RawOrigin::Signed(caller), 3 << 15)
+	verify {
+		assert_eq!(Module::<T>::manual_value(), 3 << 15);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_update_permill::<TestRuntime>());
+			assert_ok!(test_benchmark_update_fixed::<TestRuntime>());
+			assert_ok!(test_benchmark_update_manual::<TestRuntime>());
+		});
+	}
+}
@@ -20,11 +20,19 @@ use frame_system::{self as system, ensure_signed};
 use sp_arithmetic::{traits::Saturating, Permill};
 use substrate_fixed::types::U16F16;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -64,7 +72,7 @@ decl_module! {
 
 		/// Update the Permill accumulator implementation's value by multiplying it
 		/// by the new factor given in the extrinsic
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::update_permill()]
 		fn update_permill(origin, new_factor: Permill) -> DispatchResult {
 			ensure_signed(origin)?;
 
@@ -84,7 +92,7 @@ decl_module! {
 
 		/// Update the Substrate-fixed accumulator implementation's value by multiplying it
 		/// by the new factor given in the extrinsic
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::update_fixed()]
 		fn update_fixed(origin, new_factor: U16F16) -> DispatchResult {
 			ensure_signed(origin)?;
 
@@ -104,7 +112,7 @@ decl_module! {
 
 		/// Update the manually-implemented accumulator's value by multiplying it
 		/// by the new factor given in the extrinsic
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::update_manual()]
 		fn update_manual(origin, new_factor: u32) -> DispatchResult {
 			ensure_signed(origin)?;
 
@@ -5,11 +5,19 @@
 use frame_support::{decl_event, decl_module, dispatch::DispatchResult};
 use frame_system::{self as system, ensure_signed};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_module! {
@@ -17,7 +25,7 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// A simple call that does little more than emit an event
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::do_something()]
 		fn do_something(origin, input: u32) -> DispatchResult {
 			let user = ensure_signed(origin)?;
 
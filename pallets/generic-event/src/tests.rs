@@ -61,6 +61,7 @@ impl_outer_event! {
 
 impl Trait for TestRuntime {
 	type Event = TestEvent;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
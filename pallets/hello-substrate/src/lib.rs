@@ -7,16 +7,24 @@ use frame_support::{debug, decl_module, dispatch::DispatchResult};
 use frame_system::{self as system, ensure_signed};
 use sp_runtime::print;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
 
-pub trait Trait: system::Trait {}
+pub use weights::WeightInfo;
+
+pub trait Trait: system::Trait {
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 
 		/// A function that says hello to the user by printing messages to the node log
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::say_hello()]
 		pub fn say_hello(origin) -> DispatchResult {
 			// Ensure that the caller is a regular keypair account
 			let caller = ensure_signed(origin)?;
@@ -50,7 +50,9 @@ impl system::Trait for TestRuntime {
 	type OnKilledAccount = ();
 }
 
-impl Trait for TestRuntime {}
+impl Trait for TestRuntime {
+	type WeightInfo = ();
+}
 
 pub type System = system::Module<TestRuntime>;
 pub type HelloSubstrate = Module<TestRuntime>;
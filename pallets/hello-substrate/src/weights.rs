@@ -0,0 +1,25 @@
+//! Weights for the hello substrate pallet, one for each benchmark in `benchmarking.rs`
+
+use frame_support::weights::Weight;
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the hello substrate pallet
+pub trait WeightInfo {
+	fn say_hello() -> Weight;
+}
+
+/// Weights for the hello substrate pallet. Its calls do not touch storage, so the runtime's
+/// database weights do not matter.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn say_hello() -> Weight {
+		12_000_000 as Weight
+	}
+}
+
+// For tests
+impl WeightInfo for () {
+	fn say_hello() -> Weight {
+		12_000_000 as Weight
+	}
+}
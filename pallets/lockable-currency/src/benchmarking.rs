@@ -0,0 +1,112 @@
+//! Benchmarks for the lockable currency pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+use sp_std::vec;
+
+/// A schedule that starts now and unlocks its funds over a thousand blocks
+fn schedule<T: Trait>() -> VestingScheduleOf<T> {
+	let locked = T::MinVestedTransfer::get().max(1_000u32.into());
+	let now = system::Module::<T>::block_number();
+	VestingSchedule {
+		locked,
+		per_block: locked / 1_000u32.into(),
+		starting_block: now,
+		cliff: now,
+	}
+}
+
+/// Funds `who` and gives it `count` vesting schedules, then moves on a block so that each of
+/// them has something to unlock
+fn add_schedules<T: Trait>(who: &T::AccountId, count: u32) {
+	let schedule = schedule::<T>();
+	T::Currency::make_free_balance_be(who, schedule.locked * (count + 1).into());
+	Module::<T>::update_lock(who, vec![schedule; count as usize]);
+
+	let now = system::Module::<T>::block_number();
+	system::Module::<T>::set_block_number(now + One::one());
+}
+
+/// An account with funds to lock
+fn funded<T: Trait>(name: &'static str) -> T::AccountId {
+	let who: T::AccountId = account(name, 0, 0);
+	T::Currency::make_free_balance_be(&who, 1_000_000u32.into());
+	who
+}
+
+benchmarks! {
+	_ { }
+
+	lock_capital {
+		let caller = funded::<T>("caller");
+	}: _(RawOrigin::Signed(caller), 1_000u32.into())
+
+	extend_lock {
+		let caller = funded::<T>("caller");
+		Module::<T>::lock_capital(RawOrigin::Signed(caller.clone()).into(), 1_000u32.into())?;
+	}: _(RawOrigin::Signed(caller), 2_000u32.into())
+
+	unlock_all {
+		let caller = funded::<T>("caller");
+		Module::<T>::lock_capital(RawOrigin::Signed(caller.clone()).into(), 1_000u32.into())?;
+	}: _(RawOrigin::Signed(caller))
+
+	vest {
+		let s in 1 .. T::MaxVestingSchedules::get();
+		let caller: T::AccountId = account("caller", 0, 0);
+		add_schedules::<T>(&caller, s);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Module::<T>::vesting(&caller).len() as u32, s);
+	}
+
+	vest_other {
+		let s in 1 .. T::MaxVestingSchedules::get();
+		let caller: T::AccountId = account("caller", 0, 0);
+		let target: T::AccountId = account("target", 0, 0);
+		add_schedules::<T>(&target, s);
+	}: _(RawOrigin::Signed(caller), target.clone())
+	verify {
+		assert_eq!(Module::<T>::vesting(&target).len() as u32, s);
+	}
+
+	vested_transfer {
+		let s in 0 .. T::MaxVestingSchedules::get() - 1;
+		let caller = funded::<T>("caller");
+		let target: T::AccountId = account("target", 0, 0);
+		add_schedules::<T>(&target, s);
+	}: _(RawOrigin::Signed(caller), target.clone(), schedule::<T>())
+	verify {
+		assert_eq!(Module::<T>::vesting(&target).len() as u32, s + 1);
+	}
+
+	merge_schedules {
+		let s in 2 .. T::MaxVestingSchedules::get();
+		let caller: T::AccountId = account("caller", 0, 0);
+		add_schedules::<T>(&caller, s);
+	}: _(RawOrigin::Signed(caller.clone()), 0, 1)
+	verify {
+		assert_eq!(Module::<T>::vesting(&caller).len() as u32, s - 1);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_lock_capital::<TestRuntime>());
+			assert_ok!(test_benchmark_extend_lock::<TestRuntime>());
+			assert_ok!(test_benchmark_unlock_all::<TestRuntime>());
+			assert_ok!(test_benchmark_vest::<TestRuntime>());
+			assert_ok!(test_benchmark_vest_other::<TestRuntime>());
+			assert_ok!(test_benchmark_vested_transfer::<TestRuntime>());
+			assert_ok!(test_benchmark_merge_schedules::<TestRuntime>());
+		});
+	}
+}
@@ -1,16 +1,42 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 //! A pallet to demonstrate the `LockableCurrency` trait
 //! borrows collateral locking logic from pallet_staking
+//!
+//! In addition to a manually managed lock, the pallet supports vesting. A vesting schedule locks
+//! some funds, and unlocks them linearly, block by block, once a cliff has passed. The amount a
+//! schedule keeps locked shrinks as blocks pass, and calling `vest` reduces the lock accordingly.
 
 use frame_support::{
-	decl_event, decl_module,
+	decl_error, decl_event, decl_module, decl_storage,
 	dispatch::DispatchResult,
-	traits::{Currency, LockIdentifier, LockableCurrency, WithdrawReason, WithdrawReasons},
+	ensure,
+	traits::{
+		Currency, ExistenceRequirement::AllowDeath, Get, LockIdentifier, LockableCurrency,
+		WithdrawReason, WithdrawReasons,
+	},
 };
 use frame_system::{self as system, ensure_signed};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{Convert, One, Saturating, Zero},
+	RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 const EXAMPLE_ID: LockIdentifier = *b"example ";
+const VESTING_ID: LockIdentifier = *b"vesting ";
 
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type VestingScheduleOf<T> = VestingSchedule<BalanceOf<T>, <T as system::Trait>::BlockNumber>;
 
 pub trait Trait: system::Trait {
 	/// The lockable currency type
@@ -18,6 +44,43 @@ pub trait Trait: system::Trait {
 
 	/// The overarching event type
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Convert a number of blocks into a balance, so it can be multiplied by a per-block amount
+	type BlockNumberToBalance: Convert<Self::BlockNumber, BalanceOf<Self>>;
+
+	/// The smallest amount that may be transferred into a new vesting schedule
+	type MinVestedTransfer: Get<BalanceOf<Self>>;
+
+	/// The maximum number of vesting schedules a single account may have
+	type MaxVestingSchedules: Get<u32>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+/// Funds that unlock linearly over time
+///
+/// Starting at `starting_block`, `per_block` of the `locked` funds unlock every block. Nothing may
+/// be withdrawn before the `cliff` block, but once it has passed, everything that unlocked in the
+/// meantime becomes available at once.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct VestingSchedule<Balance, BlockNumber> {
+	/// The amount locked when the schedule was created
+	pub locked: Balance,
+	/// The amount that unlocks each block
+	pub per_block: Balance,
+	/// The block at which funds begin to unlock
+	pub starting_block: BlockNumber,
+	/// The block before which no funds may be withdrawn
+	pub cliff: BlockNumber,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as LockableCurrency {
+		/// The vesting schedules of each account
+		Vesting get(fn vesting):
+			map hasher(blake2_128_concat) T::AccountId => Vec<VestingScheduleOf<T>>;
+	}
 }
 
 decl_event!(
@@ -29,15 +92,38 @@ decl_event!(
 		Locked(AccountId, Balance),
 		ExtendedLock(AccountId, Balance),
 		Unlocked(AccountId),
+		/// A new vesting schedule was funded (source, target, amount)
+		VestedTransfer(AccountId, AccountId, Balance),
+		/// An account's vesting lock was updated. The balance is the amount still locked.
+		VestingUpdated(AccountId, Balance),
+		/// All of an account's vesting schedules have finished
+		VestingCompleted(AccountId),
 	}
 );
 
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The account has no vesting schedules
+		NotVesting,
+		/// The amount is below the minimum for a vested transfer
+		AmountLow,
+		/// A schedule must unlock a non-zero amount each block
+		InvalidSchedule,
+		/// The account already has the maximum number of vesting schedules
+		AtMaxVestingSchedules,
+		/// No vesting schedule exists at the given index
+		ScheduleIndexOutOfBounds,
+	}
+}
+
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
+		type Error = Error<T>;
+
 		/// Locks the specified amount of tokens from the caller
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::lock_capital()]
 		fn lock_capital(origin, amount: BalanceOf<T>) -> DispatchResult {
 			let user = ensure_signed(origin)?;
 
@@ -53,7 +139,7 @@ decl_module! {
 		}
 
 		/// Extends the lock period
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::extend_lock()]
 		fn extend_lock(origin, amount: BalanceOf<T>) -> DispatchResult {
 			let user = ensure_signed(origin)?;
 
@@ -69,7 +155,7 @@ decl_module! {
 		}
 
 		/// Releases all locked tokens
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::unlock_all()]
 		fn unlock_all(origin) -> DispatchResult {
 			let user = ensure_signed(origin)?;
 
@@ -79,6 +165,174 @@ decl_module! {
 			Ok(())
 		}
 
-		// use dilution and imbalances types
+		/// Releases whatever the caller's vesting schedules have unlocked so far
+		#[weight = T::WeightInfo::vest(T::MaxVestingSchedules::get())]
+		fn vest(origin) -> DispatchResult {
+			let user = ensure_signed(origin)?;
+			ensure!(Vesting::<T>::contains_key(&user), Error::<T>::NotVesting);
+
+			Self::update_lock(&user, Vesting::<T>::get(&user));
+			Ok(())
+		}
+
+		/// Releases whatever another account's vesting schedules have unlocked so far
+		#[weight = T::WeightInfo::vest_other(T::MaxVestingSchedules::get())]
+		fn vest_other(origin, target: T::AccountId) -> DispatchResult {
+			ensure_signed(origin)?;
+			ensure!(Vesting::<T>::contains_key(&target), Error::<T>::NotVesting);
+
+			Self::update_lock(&target, Vesting::<T>::get(&target));
+			Ok(())
+		}
+
+		/// Transfers funds to another account, and locks them there according to a new vesting
+		/// schedule
+		#[weight = T::WeightInfo::vested_transfer(T::MaxVestingSchedules::get())]
+		fn vested_transfer(
+			origin,
+			target: T::AccountId,
+			schedule: VestingScheduleOf<T>,
+		) -> DispatchResult {
+			let source = ensure_signed(origin)?;
+			ensure!(schedule.locked >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+			ensure!(!schedule.per_block.is_zero(), Error::<T>::InvalidSchedule);
+
+			// Finished schedules do not count towards the maximum
+			let now = system::Module::<T>::block_number();
+			let mut schedules = Vesting::<T>::get(&target);
+			schedules.retain(|s| !Self::locked_at(s, now).is_zero());
+			ensure!(
+				(schedules.len() as u32) < T::MaxVestingSchedules::get(),
+				Error::<T>::AtMaxVestingSchedules
+			);
+
+			T::Currency::transfer(&source, &target, schedule.locked, AllowDeath)?;
+
+			schedules.push(schedule);
+			Self::update_lock(&target, schedules);
+
+			Self::deposit_event(RawEvent::VestedTransfer(source, target, schedule.locked));
+			Ok(())
+		}
+
+		/// Merges two of the caller's vesting schedules into one
+		///
+		/// The merged schedule keeps everything that is still locked under either schedule, and
+		/// unlocks it linearly until the later of the two would have finished. It waits for the
+		/// later of the two cliffs, so no funds unlock earlier than the original schedules
+		/// would have allowed.
+		#[weight = T::WeightInfo::merge_schedules(T::MaxVestingSchedules::get())]
+		fn merge_schedules(origin, index1: u32, index2: u32) -> DispatchResult {
+			let user = ensure_signed(origin)?;
+			if index1 == index2 {
+				return Ok(());
+			}
+
+			let mut schedules = Vesting::<T>::get(&user);
+			let first = index1.min(index2) as usize;
+			let second = index1.max(index2) as usize;
+			ensure!(second < schedules.len(), Error::<T>::ScheduleIndexOutOfBounds);
+
+			// Remove the later index first, so the earlier index stays valid
+			let schedule2 = schedules.remove(second);
+			let schedule1 = schedules.remove(first);
+
+			let now = system::Module::<T>::block_number();
+			if let Some(merged) = Self::merge(&schedule1, &schedule2, now) {
+				schedules.push(merged);
+			}
+			Self::update_lock(&user, schedules);
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The amount a schedule keeps locked at block `n`
+	pub fn locked_at(schedule: &VestingScheduleOf<T>, n: T::BlockNumber) -> BalanceOf<T> {
+		if n < schedule.cliff {
+			return schedule.locked;
+		}
+
+		let elapsed = T::BlockNumberToBalance::convert(n.saturating_sub(schedule.starting_block));
+		let unlocked = elapsed.saturating_mul(schedule.per_block);
+		schedule.locked.saturating_sub(unlocked)
+	}
+
+	/// The block, expressed as a balance, at which a schedule unlocks its last funds
+	fn ending_block(schedule: &VestingScheduleOf<T>) -> BalanceOf<T> {
+		let start = T::BlockNumberToBalance::convert(schedule.starting_block);
+		let mut duration = schedule.locked / schedule.per_block;
+		if !(schedule.locked % schedule.per_block).is_zero() {
+			duration = duration.saturating_add(One::one());
+		}
+		start.saturating_add(duration)
+	}
+
+	/// Combine two schedules into one that keeps at least as much locked at every block
+	///
+	/// Returns `None` if both schedules have finished.
+	fn merge(
+		schedule1: &VestingScheduleOf<T>,
+		schedule2: &VestingScheduleOf<T>,
+		now: T::BlockNumber,
+	) -> Option<VestingScheduleOf<T>> {
+		let locked1 = Self::locked_at(schedule1, now);
+		let locked2 = Self::locked_at(schedule2, now);
+
+		match (locked1.is_zero(), locked2.is_zero()) {
+			(true, true) => return None,
+			(true, false) => return Some(*schedule2),
+			(false, true) => return Some(*schedule1),
+			(false, false) => (),
+		}
+
+		let locked = locked1.saturating_add(locked2);
+		let ending_block = Self::ending_block(schedule1).max(Self::ending_block(schedule2));
+		let starting_block = now
+			.max(schedule1.starting_block)
+			.max(schedule2.starting_block);
+		let duration =
+			ending_block.saturating_sub(T::BlockNumberToBalance::convert(starting_block));
+
+		// Rounding down means the merged schedule may finish a little late, but never early
+		let per_block = if duration.is_zero() {
+			locked
+		} else {
+			(locked / duration).max(One::one())
+		};
+
+		Some(VestingSchedule {
+			locked,
+			per_block,
+			starting_block,
+			cliff: schedule1.cliff.max(schedule2.cliff),
+		})
+	}
+
+	/// Drop finished schedules, lock whatever the rest keep locked, and store them
+	fn update_lock(who: &T::AccountId, mut schedules: Vec<VestingScheduleOf<T>>) {
+		let now = system::Module::<T>::block_number();
+		schedules.retain(|s| !Self::locked_at(s, now).is_zero());
+
+		if schedules.is_empty() {
+			T::Currency::remove_lock(VESTING_ID, who);
+			Vesting::<T>::remove(who);
+			Self::deposit_event(RawEvent::VestingCompleted(who.clone()));
+		} else {
+			let locked = schedules
+				.iter()
+				.fold(Zero::zero(), |total: BalanceOf<T>, s| {
+					total.saturating_add(Self::locked_at(s, now))
+				});
+			T::Currency::set_lock(
+				VESTING_ID,
+				who,
+				locked,
+				WithdrawReasons::except(WithdrawReason::TransactionPayment),
+			);
+			Vesting::<T>::insert(who, schedules);
+			Self::deposit_event(RawEvent::VestingUpdated(who.clone(), locked));
+		}
 	}
 }
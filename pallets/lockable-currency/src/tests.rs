@@ -0,0 +1,267 @@
+use crate::*;
+use balances;
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types,
+};
+use frame_system::{self as system};
+use sp_core::H256;
+use sp_io;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MinVestedTransfer: u64 = 100;
+	pub const MaxVestingSchedules: u32 = 3;
+}
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<TestRuntime>;
+}
+
+mod lockable_currency {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for TestRuntime {
+		system<T>,
+		lockable_currency<T>,
+		balances<T>,
+	}
+}
+
+impl Trait for TestRuntime {
+	type Event = TestEvent;
+	type Currency = balances::Module<Self>;
+	type BlockNumberToBalance = ConvertInto;
+	type MinVestedTransfer = MinVestedTransfer;
+	type MaxVestingSchedules = MaxVestingSchedules;
+	type WeightInfo = ();
+}
+
+pub type System = system::Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
+pub type LockableCurrency = Module<TestRuntime>;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default()
+		.build_storage::<TestRuntime>()
+		.unwrap();
+	balances::GenesisConfig::<TestRuntime> {
+		balances: vec![(1, 10000), (2, 10000)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// The amount an account's vesting lock currently holds, if it has one
+fn vesting_lock(who: u64) -> Option<u64> {
+	Balances::locks(&who)
+		.iter()
+		.find(|lock| lock.id == VESTING_ID)
+		.map(|lock| lock.amount)
+}
+
+/// A schedule that unlocks 10 per block from block 1, once block 11 is reached
+fn schedule(locked: u64) -> VestingSchedule<u64, u64> {
+	VestingSchedule {
+		locked,
+		per_block: 10,
+		starting_block: 1,
+		cliff: 11,
+	}
+}
+
+#[test]
+fn vested_transfer_locks_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(1000)));
+
+		assert_eq!(Balances::free_balance(&1), 9000);
+		assert_eq!(Balances::free_balance(&2), 11000);
+		assert_eq!(vesting_lock(2), Some(1000));
+		assert_eq!(LockableCurrency::vesting(&2), vec![schedule(1000)]);
+
+		let expected_event = TestEvent::lockable_currency(RawEvent::VestedTransfer(1, 2, 1000));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn vested_transfer_rejects_bad_schedules() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(99)),
+			Error::<TestRuntime>::AmountLow
+		);
+
+		let mut frozen = schedule(1000);
+		frozen.per_block = 0;
+		assert_noop!(
+			LockableCurrency::vested_transfer(Origin::signed(1), 2, frozen),
+			Error::<TestRuntime>::InvalidSchedule
+		);
+	})
+}
+
+#[test]
+fn nothing_vests_before_the_cliff() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(1000)));
+
+		System::set_block_number(10);
+		assert_ok!(LockableCurrency::vest(Origin::signed(2)));
+		assert_eq!(vesting_lock(2), Some(1000));
+
+		// At the cliff, everything that unlocked since the starting block is released at once
+		System::set_block_number(11);
+		assert_ok!(LockableCurrency::vest(Origin::signed(2)));
+		assert_eq!(vesting_lock(2), Some(900));
+
+		let expected_event = TestEvent::lockable_currency(RawEvent::VestingUpdated(2, 900));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn vesting_completes() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(1000)));
+
+		// Anyone may vest on behalf of the owner
+		System::set_block_number(101);
+		assert_ok!(LockableCurrency::vest_other(Origin::signed(1), 2));
+
+		assert_eq!(vesting_lock(2), None);
+		assert!(!Vesting::<TestRuntime>::contains_key(&2));
+		let expected_event = TestEvent::lockable_currency(RawEvent::VestingCompleted(2));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_noop!(
+			LockableCurrency::vest(Origin::signed(2)),
+			Error::<TestRuntime>::NotVesting
+		);
+	})
+}
+
+#[test]
+fn multiple_schedules_lock_their_sum() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(1000)));
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(500)));
+		assert_eq!(vesting_lock(2), Some(1500));
+
+		System::set_block_number(51);
+		assert_ok!(LockableCurrency::vest(Origin::signed(2)));
+		assert_eq!(vesting_lock(2), Some(500));
+		assert_eq!(LockableCurrency::vesting(&2), vec![schedule(1000)]);
+	})
+}
+
+#[test]
+fn schedules_are_limited() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(1000)));
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(1000)));
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(100)));
+		assert_noop!(
+			LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(100)),
+			Error::<TestRuntime>::AtMaxVestingSchedules
+		);
+
+		// Once a schedule finishes, it no longer counts towards the maximum
+		System::set_block_number(11);
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(100)));
+		assert_eq!(LockableCurrency::vesting(&2).len(), 3);
+	})
+}
+
+#[test]
+fn merging_keeps_locked_funds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(1000)));
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(500)));
+
+		// 200 of each schedule has unlocked, and the longer one ends at block 101
+		System::set_block_number(21);
+		assert_ok!(LockableCurrency::merge_schedules(Origin::signed(2), 0, 1));
+
+		assert_eq!(
+			LockableCurrency::vesting(&2),
+			vec![VestingSchedule {
+				locked: 1100,
+				per_block: 13,
+				starting_block: 21,
+				cliff: 11,
+			}]
+		);
+		assert_eq!(vesting_lock(2), Some(1100));
+
+		assert_noop!(
+			LockableCurrency::merge_schedules(Origin::signed(2), 0, 1),
+			Error::<TestRuntime>::ScheduleIndexOutOfBounds
+		);
+	})
+}
+
+#[test]
+fn merging_drops_finished_schedules() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(1000)));
+		assert_ok!(LockableCurrency::vested_transfer(Origin::signed(1), 2, schedule(100)));
+
+		System::set_block_number(21);
+		assert_ok!(LockableCurrency::merge_schedules(Origin::signed(2), 1, 0));
+
+		assert_eq!(LockableCurrency::vesting(&2), vec![schedule(1000)]);
+		assert_eq!(vesting_lock(2), Some(800));
+	})
+}
@@ -0,0 +1,105 @@
+//! Weights for the lockable currency pallet, one for each benchmark in `benchmarking.rs`
+//!
+//! The vesting calls are weighed by the number of schedules `s` the account has. Runtimes charge
+//! for the maximum, because the caller does not say how many there are.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the lockable currency pallet
+pub trait WeightInfo {
+	fn lock_capital() -> Weight;
+	fn extend_lock() -> Weight;
+	fn unlock_all() -> Weight;
+	fn vest(s: u32) -> Weight;
+	fn vest_other(s: u32) -> Weight;
+	fn vested_transfer(s: u32) -> Weight;
+	fn merge_schedules(s: u32) -> Weight;
+}
+
+/// Weights for the lockable currency pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn lock_capital() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn extend_lock() -> Weight {
+		(32_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn unlock_all() -> Weight {
+		(28_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn vest(s: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn vest_other(s: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn vested_transfer(s: u32) -> Weight {
+		(90_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(4))
+	}
+	fn merge_schedules(s: u32) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn lock_capital() -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn extend_lock() -> Weight {
+		(32_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn unlock_all() -> Weight {
+		(28_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn vest(s: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn vest_other(s: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn vested_transfer(s: u32) -> Weight {
+		(90_000_000 as Weight)
+			.saturating_add((2_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(4))
+	}
+	fn merge_schedules(s: u32) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+}
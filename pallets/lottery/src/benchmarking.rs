@@ -0,0 +1,37 @@
+//! Benchmarks for the lottery pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	buy_tickets {
+		let t in 1 .. T::MaxTicketsPerAccount::get();
+		let buyer: T::AccountId = account("buyer", t, 0);
+		let round = Module::<T>::current_round();
+		// Start from an empty round, so the tickets are not sold out
+		TicketCount::put(0);
+		TicketsBought::<T>::remove(round, &buyer);
+		let price = T::TicketPrice::get().max(T::Currency::minimum_balance());
+		T::Currency::make_free_balance_be(&buyer, price * (t + 1).into());
+	}: _(RawOrigin::Signed(buyer.clone()), t)
+	verify {
+		assert_eq!(Module::<T>::tickets_bought(round, &buyer), t);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_buy_tickets::<TestRuntime>());
+		});
+	}
+}
@@ -0,0 +1,333 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! A lottery that draws its winner with a configurable randomness source
+//!
+//! Each round sells tickets for `SalesPeriod` blocks. The ticket price is paid into a pot account
+//! that belongs to the pallet. Once sales have been closed for `DrawDelay` blocks, and the
+//! randomness source has produced two new outputs since then, the winning ticket is drawn in
+//! `on_initialize`. The house takes its `HouseFee` from the pot, and the winner receives the rest.
+//! A new round starts in the same block.
+//!
+//! Drawing only after sales have closed means that nobody can buy tickets once the randomness
+//! that decides the winner is known. A source such as a commit-reveal beacon may keep the same
+//! output for a long time, and its first new output may come from a round that was under way
+//! while tickets were sold. The second new output comes from a round that started after sales
+//! closed, so that is the one the draw waits for.
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	storage::{StorageDoubleMap, StorageValue},
+	traits::{Currency, ExistenceRequirement, Get, OnUnbalanced, Randomness, WithdrawReason},
+	weights::Weight,
+};
+use frame_system::{self as system, ensure_signed};
+use parity_scale_codec::{Decode, Encode};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{AccountIdConversion, One, Saturating},
+	ModuleId, Perbill,
+};
+use sp_std::prelude::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+
+/// Hardcoded pallet ID; used to create the pot account
+const PALLET_ID: ModuleId = ModuleId(*b"lottery!");
+
+pub type RoundIndex = u32;
+pub type TicketIndex = u32;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency in which tickets are bought and prizes are paid
+	type Currency: Currency<Self::AccountId>;
+
+	/// The source of randomness used to draw the winning ticket
+	type Randomness: Randomness<H256>;
+
+	/// The price of a single ticket
+	type TicketPrice: Get<BalanceOf<Self>>;
+
+	/// The number of blocks at the start of each round during which tickets are sold
+	type SalesPeriod: Get<Self::BlockNumber>;
+
+	/// The least number of blocks between the end of ticket sales and the draw. The draw also
+	/// waits for the randomness source to produce two new outputs after sales closed.
+	type DrawDelay: Get<Self::BlockNumber>;
+
+	/// The maximum number of tickets a single account may buy in a round
+	type MaxTicketsPerAccount: Get<u32>;
+
+	/// The maximum number of tickets sold in a round
+	type MaxTicketsPerRound: Get<u32>;
+
+	/// The share of the pot taken by the house when a winner is drawn
+	type HouseFee: Get<Perbill>;
+
+	/// Handler for the house fee
+	type HouseFeeDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Lottery {
+		/// The index of the current round
+		CurrentRound get(fn current_round): RoundIndex;
+
+		/// The block at which the current round started
+		RoundStart get(fn round_start): T::BlockNumber;
+
+		/// The number of tickets sold in the current round
+		TicketCount get(fn ticket_count): u32;
+
+		/// The owner of each ticket sold in a round
+		Tickets get(fn ticket_owner):
+			double_map hasher(twox_64_concat) RoundIndex,
+			hasher(twox_64_concat) TicketIndex => Option<T::AccountId>;
+
+		/// The number of tickets each account bought in a round
+		TicketsBought get(fn tickets_bought):
+			double_map hasher(twox_64_concat) RoundIndex,
+			hasher(blake2_128_concat) T::AccountId => u32;
+
+		/// The output of the randomness source when it was last checked, or `None` while tickets
+		/// are on sale
+		LastRandom get(fn last_random): Option<H256>;
+
+		/// The number of new outputs the randomness source has produced since sales closed
+		FreshOutputs get(fn fresh_outputs): u32;
+	}
+	add_extra_genesis {
+		build(|_config| {
+			// Create the pot account, and ensure it has the minimum required deposit
+			let _ = T::Currency::make_free_balance_be(
+				&<Module<T>>::account_id(),
+				T::Currency::minimum_balance(),
+			);
+		});
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AccountId = <T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// An account bought tickets (buyer, round, number of tickets)
+		TicketsBought(AccountId, RoundIndex, u32),
+		/// The winner of a round was paid (round, winner, prize)
+		LotteryWon(RoundIndex, AccountId, Balance),
+		/// The house took its fee from the pot (round, fee)
+		HouseFeeTaken(RoundIndex, Balance),
+		/// A round ended without any tickets sold, so the pot carries over
+		NoTicketsSold(RoundIndex),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Tickets are only sold at the start of each round
+		SalesClosed,
+		/// At least one ticket must be bought
+		ZeroTickets,
+		/// The account would exceed the maximum number of tickets per account
+		TicketLimitReached,
+		/// The round does not have that many tickets left
+		SoldOut,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		type Error = Error<T>;
+
+		/// Watches the randomness source once ticket sales are over, and draws the winner when
+		/// the draw delay is over and the source has produced two new outputs
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let db = T::DbWeight::get();
+			if n < Self::sales_end() {
+				// Reading the round start to find the end of sales
+				return db.reads(1);
+			}
+
+			let round = Self::current_round();
+			let random = T::Randomness::random(&(PALLET_ID.0, round).encode());
+			match Self::last_random() {
+				// The output when sales closed is already known to the buyers
+				None => LastRandom::put(random),
+				Some(last) if last != random => {
+					LastRandom::put(random);
+					FreshOutputs::mutate(|fresh| *fresh = fresh.saturating_add(1));
+				}
+				Some(_) => {}
+			}
+
+			// A round without tickets has nothing to draw, so it ends without waiting
+			let ready = Self::fresh_outputs() >= 2 || Self::ticket_count() == 0;
+			// Reading the round, the randomness and the round's progress, and updating the
+			// randomness seen
+			let watch = db.reads_writes(6, 2);
+			if n >= Self::draw_block() && ready {
+				let sold = Self::draw(round, random, n);
+				// The winner and the pot are read and written, the round is reset, then each
+				// ticket is removed, along with at most one buyer's count per ticket
+				watch
+					.saturating_add(db.reads_writes(2, 6))
+					.saturating_add(db.writes(2).saturating_mul(sold as Weight))
+			} else {
+				watch
+			}
+		}
+
+		/// Buys tickets in the current round, paying the ticket price for each into the pot
+		#[weight = T::WeightInfo::buy_tickets(T::MaxTicketsPerAccount::get())]
+		fn buy_tickets(origin, count: u32) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+			ensure!(count > 0, Error::<T>::ZeroTickets);
+
+			let now = system::Module::<T>::block_number();
+			ensure!(now < Self::sales_end(), Error::<T>::SalesClosed);
+
+			let round = Self::current_round();
+			let bought = Self::tickets_bought(round, &buyer).saturating_add(count);
+			ensure!(bought <= T::MaxTicketsPerAccount::get(), Error::<T>::TicketLimitReached);
+			let first = Self::ticket_count();
+			let sold = first.saturating_add(count);
+			ensure!(sold <= T::MaxTicketsPerRound::get(), Error::<T>::SoldOut);
+
+			// Paying is the last check that can fail, so nothing is written before it
+			let cost = T::TicketPrice::get().saturating_mul(count.into());
+			T::Currency::transfer(
+				&buyer,
+				&Self::account_id(),
+				cost,
+				ExistenceRequirement::KeepAlive,
+			)?;
+
+			for index in first..sold {
+				Tickets::<T>::insert(round, index, &buyer);
+			}
+			TicketCount::put(sold);
+			TicketsBought::<T>::insert(round, &buyer, bought);
+
+			Self::deposit_event(RawEvent::TicketsBought(buyer, round, count));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The account ID of the lottery pot
+	///
+	/// This actually does computation. If you need to keep using it, then make sure you cache the
+	/// value and only call this once.
+	pub fn account_id() -> T::AccountId {
+		PALLET_ID.into_account()
+	}
+
+	/// The amount in the pot that will be paid out in the next draw
+	pub fn pot() -> BalanceOf<T> {
+		T::Currency::free_balance(&Self::account_id())
+			// The minimum balance keeps the pot account alive
+			.saturating_sub(T::Currency::minimum_balance())
+	}
+
+	/// The first block after the current round's ticket sales
+	fn sales_end() -> T::BlockNumber {
+		Self::round_start().saturating_add(T::SalesPeriod::get().max(One::one()))
+	}
+
+	/// The block in which the current round's winner is drawn
+	fn draw_block() -> T::BlockNumber {
+		Self::sales_end().saturating_add(T::DrawDelay::get().max(One::one()))
+	}
+
+	/// Picks one of `sold` tickets, each with the same chance
+	///
+	/// Taking a random number modulo `sold` would favour the lower tickets, unless `sold` divides
+	/// the number of possible values. So each 64 bit chunk of `random` is only used if it falls
+	/// below the largest multiple of `sold` that fits, and the next chunk is tried otherwise.
+	fn pick_ticket(random: H256, sold: u32) -> TicketIndex {
+		let sold = u64::from(sold);
+		let zone = u64::max_value() - u64::max_value() % sold;
+		let values = random
+			.as_bytes()
+			.chunks(8)
+			.map(|mut chunk| u64::decode(&mut chunk).unwrap_or_default())
+			.collect::<Vec<_>>();
+		let value = values
+			.iter()
+			.find(|value| **value < zone)
+			// Every chunk is rejected with a chance below 2^-128, so any of them will do then
+			.unwrap_or(&values[0]);
+		(value % sold) as TicketIndex
+	}
+
+	/// Draws the winning ticket of `round` with `random`, pays out the pot, and starts the next
+	/// round at block `n`. Returns the number of tickets sold in the round.
+	fn draw(round: RoundIndex, random: H256, n: T::BlockNumber) -> u32 {
+		let sold = TicketCount::take();
+		LastRandom::kill();
+		FreshOutputs::kill();
+
+		if sold == 0 {
+			Self::deposit_event(RawEvent::NoTicketsSold(round));
+		} else {
+			let winning = Self::pick_ticket(random, sold);
+
+			if let Some(winner) = Self::ticket_owner(round, winning) {
+				let pot_account = Self::account_id();
+				let pot = Self::pot();
+				let fee = T::HouseFee::get() * pot;
+
+				// These can only fail if the pot's balance is locked. In that case the funds
+				// stay in the pot for the next round.
+				if let Ok(imbalance) = T::Currency::withdraw(
+					&pot_account,
+					fee,
+					WithdrawReason::Transfer.into(),
+					ExistenceRequirement::KeepAlive,
+				) {
+					T::HouseFeeDestination::on_unbalanced(imbalance);
+					Self::deposit_event(RawEvent::HouseFeeTaken(round, fee));
+				}
+
+				let prize = Self::pot();
+				if T::Currency::transfer(
+					&pot_account,
+					&winner,
+					prize,
+					ExistenceRequirement::KeepAlive,
+				)
+				.is_ok()
+				{
+					Self::deposit_event(RawEvent::LotteryWon(round, winner, prize));
+				}
+			}
+		}
+
+		Tickets::<T>::remove_prefix(round);
+		TicketsBought::<T>::remove_prefix(round);
+		CurrentRound::put(round.wrapping_add(1));
+		RoundStart::<T>::put(n);
+		sold
+	}
+}
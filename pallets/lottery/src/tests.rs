@@ -0,0 +1,328 @@
+use crate::*;
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types,
+	traits::OnInitialize,
+};
+use frame_system as system;
+use sp_io::TestExternalities;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use std::cell::RefCell;
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 1;
+	pub const TicketPrice: u64 = 10;
+	pub const SalesPeriod: u64 = 5;
+	pub const DrawDelay: u64 = 2;
+	pub const MaxTicketsPerAccount: u32 = 3;
+	pub const MaxTicketsPerRound: u32 = 5;
+	pub const HouseFee: Perbill = Perbill::from_percent(10);
+}
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<TestRuntime>;
+}
+
+thread_local! {
+	static RANDOM_OUTPUT: RefCell<H256> = RefCell::new(H256::zero());
+	static OUTPUTS: RefCell<u64> = RefCell::new(0);
+}
+
+/// Returns a preset output, which only changes when a test asks for a new one, like a beacon
+/// that only updates when a round ends with reveals
+pub struct TestRandomness;
+impl Randomness<H256> for TestRandomness {
+	fn random(_subject: &[u8]) -> H256 {
+		RANDOM_OUTPUT.with(|r| *r.borrow())
+	}
+}
+
+/// Produces a new random output, which picks the ticket `index` modulo the number of tickets sold
+fn set_random_ticket(index: u64) {
+	let count = OUTPUTS.with(|o| {
+		*o.borrow_mut() += 1;
+		*o.borrow()
+	});
+	let mut output = H256::zero();
+	output.as_mut()[..8].copy_from_slice(&index.encode());
+	// Makes every output different, even for the same ticket
+	output.as_mut()[24..].copy_from_slice(&count.encode());
+	RANDOM_OUTPUT.with(|r| *r.borrow_mut() = output);
+}
+
+mod lottery {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for TestRuntime {
+		lottery<T>,
+		system<T>,
+		balances<T>,
+	}
+}
+
+impl Trait for TestRuntime {
+	type Event = TestEvent;
+	type Currency = balances::Module<Self>;
+	type Randomness = TestRandomness;
+	type TicketPrice = TicketPrice;
+	type SalesPeriod = SalesPeriod;
+	type DrawDelay = DrawDelay;
+	type MaxTicketsPerAccount = MaxTicketsPerAccount;
+	type MaxTicketsPerRound = MaxTicketsPerRound;
+	type HouseFee = HouseFee;
+	type HouseFeeDestination = ();
+	type WeightInfo = ();
+}
+
+pub type System = system::Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
+pub type Lottery = Module<TestRuntime>;
+
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	pub fn build() -> TestExternalities {
+		let mut storage = system::GenesisConfig::default()
+			.build_storage::<TestRuntime>()
+			.unwrap();
+		balances::GenesisConfig::<TestRuntime> {
+			balances: (1..10).map(|i| (i, 100)).collect(),
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		GenesisConfig::default()
+			.assimilate_storage::<TestRuntime>(&mut storage)
+			.unwrap();
+		let mut ext = TestExternalities::from(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+/// Runs `on_initialize` for every block up to and including `n`
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Lottery::on_initialize(System::block_number());
+	}
+}
+
+/// Runs to the first round's draw at block 7. The randomness source produces a new output in
+/// each block after sales close, the last of which picks the ticket `index`.
+fn draw_first_round(index: u64) {
+	run_to_block(5);
+	set_random_ticket(0);
+	run_to_block(6);
+	set_random_ticket(index);
+	run_to_block(7);
+}
+
+#[test]
+fn buying_tickets_fills_pot() {
+	ExtBuilder::build().execute_with(|| {
+		assert_eq!(Balances::free_balance(&Lottery::account_id()), 1);
+		assert_eq!(Lottery::pot(), 0);
+
+		assert_ok!(Lottery::buy_tickets(Origin::signed(1), 2));
+		assert_ok!(Lottery::buy_tickets(Origin::signed(2), 1));
+
+		assert_eq!(Balances::free_balance(&1), 80);
+		assert_eq!(Lottery::pot(), 30);
+		assert_eq!(Lottery::ticket_count(), 3);
+		assert_eq!(Lottery::tickets_bought(0, &1), 2);
+		assert_eq!(Lottery::ticket_owner(0, 1), Some(1));
+		assert_eq!(Lottery::ticket_owner(0, 2), Some(2));
+
+		let expected_event = TestEvent::lottery(RawEvent::TicketsBought(1, 0, 2));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn ticket_limits_are_enforced() {
+	ExtBuilder::build().execute_with(|| {
+		assert_noop!(Lottery::buy_tickets(Origin::signed(1), 0), Error::<TestRuntime>::ZeroTickets);
+
+		assert_ok!(Lottery::buy_tickets(Origin::signed(1), 2));
+		assert_noop!(
+			Lottery::buy_tickets(Origin::signed(1), 2),
+			Error::<TestRuntime>::TicketLimitReached
+		);
+		assert_ok!(Lottery::buy_tickets(Origin::signed(1), 1));
+
+		assert_ok!(Lottery::buy_tickets(Origin::signed(2), 1));
+		assert_noop!(Lottery::buy_tickets(Origin::signed(3), 2), Error::<TestRuntime>::SoldOut);
+		assert_ok!(Lottery::buy_tickets(Origin::signed(3), 1));
+	})
+}
+
+#[test]
+fn tickets_must_be_paid_for() {
+	ExtBuilder::build().execute_with(|| {
+		// 50 has no funds
+		assert_noop!(
+			Lottery::buy_tickets(Origin::signed(50), 1),
+			balances::Error::<TestRuntime, _>::InsufficientBalance
+		);
+	})
+}
+
+#[test]
+fn sales_close_before_draw() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(Lottery::buy_tickets(Origin::signed(1), 1));
+
+		// Sales cover blocks 0 to 4
+		run_to_block(5);
+		assert_noop!(Lottery::buy_tickets(Origin::signed(2), 1), Error::<TestRuntime>::SalesClosed);
+
+		// The draw happens at block 7, and not before
+		set_random_ticket(0);
+		run_to_block(6);
+		assert_eq!(Lottery::current_round(), 0);
+		set_random_ticket(0);
+		run_to_block(7);
+
+		// The next round is open for sales straight away
+		assert_eq!(Lottery::current_round(), 1);
+		assert_eq!(Lottery::round_start(), 7);
+		assert_ok!(Lottery::buy_tickets(Origin::signed(2), 1));
+	})
+}
+
+#[test]
+fn winner_is_paid() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(Lottery::buy_tickets(Origin::signed(1), 2));
+		assert_ok!(Lottery::buy_tickets(Origin::signed(2), 2));
+
+		// Ticket 2 belongs to 2
+		draw_first_round(6);
+
+		// 2 paid 20 and receives 90% of the 40 in the pot. The house fee is burned.
+		assert_eq!(Balances::free_balance(&2), 116);
+		assert_eq!(Balances::free_balance(&1), 80);
+		assert_eq!(Lottery::pot(), 0);
+		assert_eq!(Balances::total_issuance(), 897);
+
+		let expected_event = TestEvent::lottery(RawEvent::LotteryWon(0, 2, 36));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		let expected_event = TestEvent::lottery(RawEvent::HouseFeeTaken(0, 4));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		// The round's tickets are cleared
+		assert_eq!(Lottery::ticket_count(), 0);
+		assert_eq!(Lottery::ticket_owner(0, 0), None);
+		assert_eq!(Lottery::tickets_bought(0, &1), 0);
+	})
+}
+
+#[test]
+fn empty_round_carries_over() {
+	ExtBuilder::build().execute_with(|| {
+		// Funds sent to the pot directly are added to the prize
+		assert_ok!(Balances::transfer(Origin::signed(3), Lottery::account_id(), 50));
+		run_to_block(7);
+
+		assert_eq!(Lottery::current_round(), 1);
+		assert_eq!(Lottery::pot(), 50);
+		let expected_event = TestEvent::lottery(RawEvent::NoTicketsSold(0));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_ok!(Lottery::buy_tickets(Origin::signed(1), 1));
+		run_to_block(12);
+		set_random_ticket(0);
+		run_to_block(13);
+		set_random_ticket(0);
+		run_to_block(14);
+		assert_eq!(Balances::free_balance(&1), 144);
+	})
+}
+
+#[test]
+fn draw_waits_for_new_randomness() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(Lottery::buy_tickets(Origin::signed(1), 2));
+		assert_ok!(Lottery::buy_tickets(Origin::signed(2), 2));
+		run_to_block(5);
+
+		// The beacon round that was under way when sales closed produces a new output, but
+		// its commitments were made while tickets were on sale
+		set_random_ticket(0);
+		run_to_block(8);
+		assert_eq!(Lottery::fresh_outputs(), 1);
+
+		// The next beacon round has no reveals, so the output stays the same, and the draw
+		// keeps waiting long after the draw delay
+		run_to_block(30);
+		assert_eq!(Lottery::current_round(), 0);
+		assert_eq!(Lottery::ticket_count(), 4);
+		assert_noop!(Lottery::buy_tickets(Origin::signed(3), 1), Error::<TestRuntime>::SalesClosed);
+
+		// A round that started after sales closed produces the output that decides the winner
+		set_random_ticket(6);
+		run_to_block(31);
+		assert_eq!(Lottery::current_round(), 1);
+		assert_eq!(Lottery::round_start(), 31);
+		assert_eq!(Lottery::last_random(), None);
+		assert_eq!(Lottery::fresh_outputs(), 0);
+		let expected_event = TestEvent::lottery(RawEvent::LotteryWon(0, 2, 36));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn tickets_are_picked_without_bias() {
+	let mut random = H256::zero();
+	random.as_mut()[..8].copy_from_slice(&u64::max_value().encode());
+	random.as_mut()[8..16].copy_from_slice(&4u64.encode());
+
+	// Taking u64::MAX modulo three would favour the first ticket, so the next chunk is used
+	assert_eq!(Lottery::pick_ticket(random, 3), 1);
+	assert_eq!(Lottery::pick_ticket(H256::zero(), 3), 0);
+}
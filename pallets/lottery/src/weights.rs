@@ -0,0 +1,32 @@
+//! Weights for the lottery pallet, one for each benchmark in `benchmarking.rs`
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the lottery pallet
+pub trait WeightInfo {
+	fn buy_tickets(t: u32) -> Weight;
+}
+
+/// Weights for the lottery pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn buy_tickets(t: u32) -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add((4_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(T::DbWeight::get().reads(6))
+			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().writes(t as Weight))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn buy_tickets(t: u32) -> Weight {
+		(70_000_000 as Weight)
+			.saturating_add((4_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add(RocksDbWeight::get().reads(6))
+			.saturating_add(RocksDbWeight::get().writes(4))
+			.saturating_add(RocksDbWeight::get().writes(t as Weight))
+	}
+}
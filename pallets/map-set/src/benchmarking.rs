@@ -0,0 +1,70 @@
+//! Benchmarks for the map set pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+/// An account that is not a member yet, with enough funds for the admission deposit
+fn new_account<T: Trait>() -> T::AccountId {
+	// Every benchmark adds a member, so the member count picks a fresh account each time
+	let who: T::AccountId = account("member", MemberCount::get(), 0);
+	let deposit = T::AdmissionDeposit::get().max(T::Currency::minimum_balance());
+	T::Currency::make_free_balance_be(&who, deposit * 10u32.into());
+	who
+}
+
+/// Adds a new member who reserved the admission deposit
+fn new_member<T: Trait>() -> Result<T::AccountId, &'static str> {
+	let who = new_account::<T>();
+	Module::<T>::add_member(RawOrigin::Signed(who.clone()).into())?;
+	Ok(who)
+}
+
+benchmarks! {
+	_ { }
+
+	add_member {
+		let caller = new_account::<T>();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Module::<T>::members(&caller));
+	}
+
+	admit_member {
+		let member = new_account::<T>();
+	}: _(T::AddOrigin::successful_origin(), member.clone())
+	verify {
+		assert!(Module::<T>::members(&member));
+	}
+
+	remove_member {
+		let caller = new_member::<T>()?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!Module::<T>::members(&caller));
+	}
+
+	expel_member {
+		let member = new_member::<T>()?;
+	}: _(T::RemoveOrigin::successful_origin(), member.clone())
+	verify {
+		assert!(!Module::<T>::members(&member));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_add_member::<TestRuntime>());
+			assert_ok!(test_benchmark_admit_member::<TestRuntime>());
+			assert_ok!(test_benchmark_remove_member::<TestRuntime>());
+			assert_ok!(test_benchmark_expel_member::<TestRuntime>());
+		});
+	}
+}
@@ -2,22 +2,63 @@
 
 //! A pallet that implements a storage set on top of a storage map and demonstrates performance
 //! tradeoffs when using vec sets.
-
-use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure};
+//!
+//! Accounts may join the set themselves by reserving an `AdmissionDeposit`, or be admitted by the
+//! `AddOrigin` without a deposit. Members who leave get their deposit back. Members expelled by
+//! the `RemoveOrigin` have their deposit slashed.
+
+use account_set::{AccountSet, OnMembersChanged};
+use frame_support::storage::{IterableStorageMap, StoragePrefixedMap};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, EnsureOrigin, Get, Imbalance, OnUnbalanced, ReservableCurrency},
+};
 use frame_system::{self as system, ensure_signed};
-use frame_support::storage::IterableStorageMap;
-use sp_std::prelude::*;
+use parity_scale_codec::Decode;
+use sp_runtime::traits::Zero;
 use sp_std::collections::btree_set::BTreeSet;
-use account_set::AccountSet;
+use sp_std::prelude::*;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
 
-/// A maximum number of members. When membership reaches this number, no new members may join.
-pub const MAX_MEMBERS: u32 = 16;
+pub use weights::WeightInfo;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency in which admission deposits are reserved
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The origin that may admit members without a deposit
+	type AddOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The origin that may expel members
+	type RemoveOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The deposit reserved from accounts that join by themselves. Zero means joining is free.
+	type AdmissionDeposit: Get<BalanceOf<Self>>;
+
+	/// A maximum number of members. When membership reaches this number, no new members may join.
+	type MaxMembers: Get<u32>;
+
+	/// Handler for the deposits slashed from expelled members
+	type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// Notified whenever members join or leave
+	type MembershipChanged: OnMembersChanged<Self::AccountId>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -30,6 +71,8 @@ decl_storage! {
 		// The total number of members stored in the map.
 		// Because the map does not store its size internally, we must store it separately
 		MemberCount: u32;
+		// The deposit each member reserved when joining
+		Deposits get(fn deposit_of): map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
 	}
 }
 
@@ -37,11 +80,14 @@ decl_event!(
 	pub enum Event<T>
 	where
 		AccountId = <T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
 	{
 		/// Added a member
 		MemberAdded(AccountId),
 		/// Removed a member
 		MemberRemoved(AccountId),
+		/// Expelled a member and slashed what was left of their deposit (member, amount slashed)
+		MemberExpelled(AccountId, Balance),
 	}
 );
 
@@ -62,39 +108,83 @@ decl_module! {
 
 		type Error = Error<T>;
 
-		/// Adds a member to the membership set
-		#[weight = 10_000]
+		/// Adds the caller to the membership set, reserving the admission deposit
+		#[weight = T::WeightInfo::add_member()]
 		fn add_member(origin) -> DispatchResult {
 			let new_member = ensure_signed(origin)?;
+			Self::insert_member(new_member, T::AdmissionDeposit::get())
+		}
 
-			let member_count = MemberCount::get();
-			ensure!(member_count < MAX_MEMBERS, Error::<T>::MembershipLimitReached);
-
-			// We don't want to add duplicate members, so we check whether the potential new
-			// member is already present in the list. Because the membership is stored as a hash
-			// map this check is constant time O(1)
-			ensure!(!Members::<T>::contains_key(&new_member), Error::<T>::AlreadyMember);
-
-			// Insert the new member and emit the event
-			Members::<T>::insert(&new_member, true);
-			MemberCount::put(member_count + 1); // overflow check not necessary because of maximum
-			Self::deposit_event(RawEvent::MemberAdded(new_member));
-			Ok(())
+		/// Admits an account to the membership set without a deposit
+		#[weight = T::WeightInfo::admit_member()]
+		fn admit_member(origin, new_member: T::AccountId) -> DispatchResult {
+			T::AddOrigin::ensure_origin(origin)?;
+			Self::insert_member(new_member, Zero::zero())
 		}
 
-		/// Removes a member.
-		#[weight = 10_000]
+		/// Removes the caller from the membership set, returning their deposit
+		#[weight = T::WeightInfo::remove_member()]
 		fn remove_member(origin) -> DispatchResult {
 			let old_member = ensure_signed(origin)?;
 
-			ensure!(Members::<T>::contains_key(&old_member), Error::<T>::NotMember);
+			Self::take_member(&old_member)?;
+			T::Currency::unreserve(&old_member, Deposits::<T>::take(&old_member));
 
-			Members::<T>::remove(&old_member);
-			MemberCount::mutate(|v| *v -= 1);
 			Self::deposit_event(RawEvent::MemberRemoved(old_member));
 			Ok(())
 		}
 
+		/// Expels a member from the membership set, slashing their deposit
+		#[weight = T::WeightInfo::expel_member()]
+		fn expel_member(origin, old_member: T::AccountId) -> DispatchResult {
+			T::RemoveOrigin::ensure_origin(origin)?;
+
+			Self::take_member(&old_member)?;
+			let deposit = Deposits::<T>::take(&old_member);
+			// Another pallet may already have slashed part of the reserved deposit
+			let (imbalance, _) = T::Currency::slash_reserved(&old_member, deposit);
+			let slashed = imbalance.peek();
+			T::Slashed::on_unbalanced(imbalance);
+
+			Self::deposit_event(RawEvent::MemberExpelled(old_member, slashed));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Inserts a new member into the map after reserving their deposit
+	fn insert_member(new_member: T::AccountId, deposit: BalanceOf<T>) -> DispatchResult {
+		let member_count = MemberCount::get();
+		ensure!(member_count < T::MaxMembers::get(), Error::<T>::MembershipLimitReached);
+
+		// We don't want to add duplicate members, so we check whether the potential new
+		// member is already present in the list. Because the membership is stored as a hash
+		// map this check is constant time O(1)
+		ensure!(!Members::<T>::contains_key(&new_member), Error::<T>::AlreadyMember);
+
+		// Reserving is the last check that can fail, so nothing is written before it
+		T::Currency::reserve(&new_member, deposit)?;
+		if !deposit.is_zero() {
+			Deposits::<T>::insert(&new_member, deposit);
+		}
+
+		// Insert the new member and emit the event
+		Members::<T>::insert(&new_member, true);
+		MemberCount::put(member_count + 1); // overflow check not necessary because of maximum
+		T::MembershipChanged::on_members_changed(&[new_member.clone()], &[]);
+		Self::deposit_event(RawEvent::MemberAdded(new_member));
+		Ok(())
+	}
+
+	/// Removes a member from the map
+	fn take_member(old_member: &T::AccountId) -> DispatchResult {
+		ensure!(Members::<T>::contains_key(old_member), Error::<T>::NotMember);
+
+		Members::<T>::remove(old_member);
+		MemberCount::mutate(|v| *v -= 1);
+		T::MembershipChanged::on_members_changed(&[], &[old_member.clone()]);
+		Ok(())
 	}
 }
 
@@ -107,4 +197,41 @@ impl<T: Trait> AccountSet for Module<T> {
 			.map(|(acct, _)| acct)
 			.collect::<BTreeSet<_>>()
 	}
+
+	/// A single storage read, O(1)
+	fn contains(who: &T::AccountId) -> bool {
+		Members::<T>::contains_key(who)
+	}
+
+	/// Reads the separately stored count, O(1)
+	fn count() -> u32 {
+		MemberCount::get()
+	}
+
+	/// Pages through the members in the order of their storage keys, which is the order of their
+	/// hashes. Each page costs one storage read per member, however many pages came before it.
+	fn accounts_paged(start_after: Option<&T::AccountId>, limit: u32) -> Vec<T::AccountId> {
+		let prefix = Members::<T>::final_prefix();
+		let mut key = match start_after {
+			Some(who) => Members::<T>::hashed_key_for(who),
+			None => prefix.to_vec(),
+		};
+
+		let mut accounts = Vec::new();
+		while (accounts.len() as u32) < limit {
+			let next = match sp_io::storage::next_key(&key) {
+				Some(next) if next.starts_with(&prefix) => next,
+				_ => break,
+			};
+
+			// Each key is the prefix, the 16 byte blake2_128 hash, and then the account itself
+			if let Some(mut encoded) = next.get(prefix.len() + 16..) {
+				if let Ok(who) = T::AccountId::decode(&mut encoded) {
+					accounts.push(who);
+				}
+			}
+			key = next;
+		}
+		accounts
+	}
 }
@@ -0,0 +1,74 @@
+//! Benchmarks for the offchain demo pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+use sp_std::vec;
+
+/// Fills the numbers vec so that new submissions replace an existing entry
+fn fill_numbers() {
+	Numbers::put(vec![1u64; NUM_VEC_LEN]);
+}
+
+benchmarks! {
+	_ { }
+
+	insert_new_task {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let url = b"https://api.github.com/orgs/substrate-developer-hub".to_vec();
+		let user_agent = b"jimmychu0807".to_vec();
+	}: _(RawOrigin::Signed(caller), 1, url, user_agent)
+	verify {
+		assert!(Module::<T>::queue_available());
+	}
+
+	empty_tasks {
+		let caller: T::AccountId = account("caller", 0, 0);
+		QueueAvailable::put(true);
+	}: _(RawOrigin::Signed(caller))
+	verify {
+		assert!(!Module::<T>::queue_available());
+	}
+
+	submit_agent_signed {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let agent = b"jimmychu0807".to_vec();
+	}: _(RawOrigin::Signed(caller), agent.clone())
+	verify {
+		assert_eq!(Module::<T>::user_agent_on_chain(), agent);
+	}
+
+	submit_number_signed {
+		let caller: T::AccountId = account("caller", 0, 0);
+		fill_numbers();
+	}: _(RawOrigin::Signed(caller), 42)
+	verify {
+		assert!(Module::<T>::numbers().contains(&42));
+	}
+
+	submit_number_unsigned {
+		fill_numbers();
+	}: _(RawOrigin::None, 42)
+	verify {
+		assert!(Module::<T>::numbers().contains(&42));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		let (mut t, _, _) = ExtBuilder::build();
+		t.execute_with(|| {
+			assert_ok!(test_benchmark_insert_new_task::<TestRuntime>());
+			assert_ok!(test_benchmark_empty_tasks::<TestRuntime>());
+			assert_ok!(test_benchmark_submit_agent_signed::<TestRuntime>());
+			assert_ok!(test_benchmark_submit_number_signed::<TestRuntime>());
+			assert_ok!(test_benchmark_submit_number_unsigned::<TestRuntime>());
+		});
+	}
+}
@@ -2,8 +2,13 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 use core::{convert::TryInto, fmt};
 use frame_support::{
@@ -140,6 +145,9 @@ pub trait Trait: system::Trait + CreateSignedTransaction<Call<Self>> {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 	/// The type to sign and send transactions.
 	type UnsignedPriority: Get<TransactionPriority>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 // Custom data type
@@ -204,7 +212,7 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// Adds a new task to the TaskQueue
-		#[weight = 0]
+		#[weight = T::WeightInfo::insert_new_task()]
 		pub fn insert_new_task(origin, task_number: u32, http_remote_reqst: Vec<u8>, http_header_usr: Vec<u8>) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 			let task_queue = TaskQueue {
@@ -216,27 +224,27 @@ decl_module! {
 			Ok(())
 		}
 
-		#[weight = 0]
+		#[weight = T::WeightInfo::empty_tasks()]
 		pub fn empty_tasks(origin) -> DispatchResult {
 			QueueAvailable::put(false);
 			Ok(())
 		}
 
-		#[weight = 0]
+		#[weight = T::WeightInfo::submit_agent_signed()]
 		pub fn submit_agent_signed(origin, agent: Vec<u8>) -> DispatchResult {
 			debug::info!("submit_agent_signed: {:?}", agent);
 			let who = ensure_signed(origin)?;
 			Self::update_agent(Some(who), agent)
 		}
 
-		#[weight = 0]
+		#[weight = T::WeightInfo::submit_number_signed()]
 		pub fn submit_number_signed(origin, number: u64) -> DispatchResult {
 			debug::info!("submit_number_signed: {:?}", number);
 			let who = ensure_signed(origin)?;
 			Self::append_or_replace_number(Some(who), number)
 		}
 
-		#[weight = 0]
+		#[weight = T::WeightInfo::submit_number_unsigned()]
 		pub fn submit_number_unsigned(origin, number: u64) -> DispatchResult {
 			debug::info!("submit_number_unsigned: {:?}", number);
 			let _ = ensure_none(origin)?;
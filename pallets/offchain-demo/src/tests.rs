@@ -82,6 +82,7 @@ impl Trait for TestRuntime {
 	type Call = Call<TestRuntime>;
 	type Event = TestEvent;
 	type UnsignedPriority = UnsignedPriority;
+	type WeightInfo = ();
 }
 
 impl<LocalCall> system::offchain::CreateSignedTransaction<LocalCall> for TestRuntime
@@ -0,0 +1,63 @@
+//! Weights for the offchain demo pallet, one for each benchmark in `benchmarking.rs`
+//!
+//! These only cover the calls that the offchain worker submits back to the chain. The offchain
+//! worker itself runs outside of block execution and is not charged any weight.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the offchain demo pallet
+pub trait WeightInfo {
+	fn insert_new_task() -> Weight;
+	fn empty_tasks() -> Weight;
+	fn submit_agent_signed() -> Weight;
+	fn submit_number_signed() -> Weight;
+	fn submit_number_unsigned() -> Weight;
+}
+
+/// Weights for the offchain demo pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn insert_new_task() -> Weight {
+		(15_000_000 as Weight).saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn empty_tasks() -> Weight {
+		(5_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn submit_agent_signed() -> Weight {
+		(10_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn submit_number_signed() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn submit_number_unsigned() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn insert_new_task() -> Weight {
+		(15_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn empty_tasks() -> Weight {
+		(5_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn submit_agent_signed() -> Weight {
+		(10_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn submit_number_signed() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn submit_number_unsigned() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
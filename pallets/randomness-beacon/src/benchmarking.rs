@@ -0,0 +1,57 @@
+//! Benchmarks for the randomness beacon pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+/// Starts a fresh round at the current block with `p` other participants already committed
+fn start_round<T: Trait>(p: u32) -> T::AccountId {
+	RoundStart::<T>::put(system::Module::<T>::block_number());
+	let participants: Vec<T::AccountId> = (0..p).map(|i| account("participant", i, 0)).collect();
+	Participants::<T>::put(participants);
+
+	let caller: T::AccountId = account("caller", 0, 0);
+	Commitments::<T>::remove(&caller);
+	let bond = T::CommitBond::get().max(T::Currency::minimum_balance());
+	T::Currency::make_free_balance_be(&caller, bond * 10u32.into());
+	caller
+}
+
+benchmarks! {
+	_ { }
+
+	commit {
+		let p in 0 .. T::MaxParticipants::get().saturating_sub(1);
+		let caller = start_round::<T>(p);
+		let hash = Module::<T>::commitment(&caller, &H256::repeat_byte(1));
+	}: _(RawOrigin::Signed(caller.clone()), hash)
+	verify {
+		assert!(Module::<T>::commitment_of(&caller).is_some());
+	}
+
+	reveal {
+		let caller = start_round::<T>(0);
+		let value = H256::repeat_byte(1);
+		let hash = Module::<T>::commitment(&caller, &value);
+		Module::<T>::commit(RawOrigin::Signed(caller.clone()).into(), hash)?;
+		system::Module::<T>::set_block_number(Module::<T>::commit_end());
+	}: _(RawOrigin::Signed(caller.clone()), value)
+	verify {
+		assert_eq!(Module::<T>::commitment_of(&caller).map(|c| c.revealed), Some(true));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_commit::<TestRuntime>());
+			assert_ok!(test_benchmark_reveal::<TestRuntime>());
+		});
+	}
+}
@@ -0,0 +1,291 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! A randomness beacon built from bonded commit-reveal rounds
+//!
+//! Each round has a commit phase of `CommitPeriod` blocks followed by a reveal phase of
+//! `RevealPeriod` blocks. During the commit phase, participants reserve the `CommitBond` and
+//! submit the hash of a secret value. During the reveal phase they submit the value itself. When
+//! the round ends, the revealed values are combined into the beacon's output, revealers get their
+//! bond back, and the bonds of participants who did not reveal are slashed.
+//!
+//! The output is exposed through the `Randomness<H256>` trait, so this pallet can be plugged into
+//! any pallet that takes a `Randomness` source, including in runtimes without BABE.
+
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, Get, OnUnbalanced, Randomness, ReservableCurrency},
+	weights::Weight,
+};
+use frame_system::{self as system, ensure_signed};
+use parity_scale_codec::{Decode, Encode};
+use sp_core::H256;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	traits::{One, Saturating},
+	RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
+
+pub type RoundIndex = u32;
+
+pub trait Trait: system::Trait {
+	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency in which commitment bonds are reserved
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The bond reserved with each commitment
+	type CommitBond: Get<BalanceOf<Self>>;
+
+	/// The number of blocks at the start of each round during which commitments are accepted
+	type CommitPeriod: Get<Self::BlockNumber>;
+
+	/// The number of blocks after the commit phase during which values may be revealed
+	type RevealPeriod: Get<Self::BlockNumber>;
+
+	/// The maximum number of commitments in a single round
+	type MaxParticipants: Get<u32>;
+
+	/// Handler for the bonds slashed from participants who did not reveal
+	type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+/// A participant's commitment in the current round
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Commitment<Balance> {
+	/// The hash of the participant's account and secret value
+	pub hash: H256,
+	/// The bond reserved when committing
+	pub bond: Balance,
+	/// Whether the value has been revealed
+	pub revealed: bool,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as RandomnessBeacon {
+		/// The index of the current round
+		CurrentRound get(fn current_round): RoundIndex;
+
+		/// The block at which the current round started
+		RoundStart get(fn round_start): T::BlockNumber;
+
+		/// The accounts that committed in the current round, in the order they committed
+		Participants get(fn participants): Vec<T::AccountId>;
+
+		/// The commitment of each participant in the current round
+		Commitments get(fn commitment_of):
+			map hasher(blake2_128_concat) T::AccountId => Option<Commitment<BalanceOf<T>>>;
+
+		/// The combination of the values revealed so far in the current round
+		Accumulator get(fn accumulator): H256;
+
+		/// The output of the last round in which at least one value was revealed, or `None` until
+		/// such a round has ended
+		Output get(fn output): Option<H256>;
+	}
+}
+
+decl_event!(
+	pub enum Event<T>
+	where
+		AccountId = <T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
+	{
+		/// A participant committed to a value (participant, round)
+		Committed(AccountId, RoundIndex),
+		/// A participant revealed their value (participant, round)
+		Revealed(AccountId, RoundIndex),
+		/// A participant did not reveal and their bond was slashed
+		BondSlashed(AccountId, Balance),
+		/// A round ended and produced a new output
+		OutputUpdated(RoundIndex, H256),
+		/// A round ended without any reveals, so the output did not change
+		NoReveals(RoundIndex),
+	}
+);
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// Commitments are only accepted during the commit phase
+		NotCommitPhase,
+		/// Values may only be revealed during the reveal phase
+		NotRevealPhase,
+		/// The account already committed in this round
+		AlreadyCommitted,
+		/// The round already has the maximum number of participants
+		TooManyParticipants,
+		/// The account did not commit in this round
+		NoCommitment,
+		/// The account already revealed its value in this round
+		AlreadyRevealed,
+		/// The revealed value does not match the commitment
+		InvalidReveal,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		type Error = Error<T>;
+
+		/// Ends the current round once its reveal phase is over
+		fn on_initialize(n: T::BlockNumber) -> Weight {
+			let db = T::DbWeight::get();
+			if n >= Self::reveal_end() {
+				let participants = Self::close_round(n);
+				// The round's items are read and written, then for each participant their
+				// commitment is taken and their bond unreserved or slashed
+				db.reads_writes(5, 5)
+					.saturating_add(db.reads_writes(2, 2).saturating_mul(participants as Weight))
+			} else {
+				// Reading the round start to find the end of the reveal phase
+				db.reads(1)
+			}
+		}
+
+		/// Commits to a secret value, reserving the commitment bond
+		///
+		/// The hash is the `commitment` of the caller's account and the value.
+		#[weight = T::WeightInfo::commit(T::MaxParticipants::get())]
+		fn commit(origin, hash: H256) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let now = system::Module::<T>::block_number();
+			ensure!(now < Self::commit_end(), Error::<T>::NotCommitPhase);
+			ensure!(!Commitments::<T>::contains_key(&who), Error::<T>::AlreadyCommitted);
+			let mut participants = Self::participants();
+			ensure!(
+				(participants.len() as u32) < T::MaxParticipants::get(),
+				Error::<T>::TooManyParticipants
+			);
+
+			// Reserving is the last check that can fail, so nothing is written before it
+			let bond = T::CommitBond::get();
+			T::Currency::reserve(&who, bond)?;
+
+			participants.push(who.clone());
+			Participants::<T>::put(participants);
+			Commitments::<T>::insert(&who, Commitment { hash, bond, revealed: false });
+
+			Self::deposit_event(RawEvent::Committed(who, Self::current_round()));
+			Ok(())
+		}
+
+		/// Reveals the value the caller committed to
+		///
+		/// The bond stays reserved until the round ends.
+		#[weight = T::WeightInfo::reveal()]
+		fn reveal(origin, value: H256) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let now = system::Module::<T>::block_number();
+			ensure!(
+				now >= Self::commit_end() && now < Self::reveal_end(),
+				Error::<T>::NotRevealPhase
+			);
+			let mut commitment = Self::commitment_of(&who).ok_or(Error::<T>::NoCommitment)?;
+			ensure!(!commitment.revealed, Error::<T>::AlreadyRevealed);
+			let hash = Self::commitment(&who, &value);
+			ensure!(commitment.hash == hash, Error::<T>::InvalidReveal);
+
+			commitment.revealed = true;
+			Commitments::<T>::insert(&who, commitment);
+			// Combining with xor makes the output independent of the order of the reveals
+			Accumulator::mutate(|acc| *acc ^= H256(blake2_256(&value.encode())));
+
+			Self::deposit_event(RawEvent::Revealed(who, Self::current_round()));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The hash an account must commit to in order to later reveal `value`
+	///
+	/// The account is part of the hash so that nobody can copy another participant's commitment
+	/// and then reveal the value once it is public.
+	pub fn commitment(who: &T::AccountId, value: &H256) -> H256 {
+		H256(blake2_256(&(who, value).encode()))
+	}
+
+	/// The first block after the current round's commit phase
+	fn commit_end() -> T::BlockNumber {
+		Self::round_start().saturating_add(T::CommitPeriod::get().max(One::one()))
+	}
+
+	/// The first block after the current round's reveal phase
+	fn reveal_end() -> T::BlockNumber {
+		Self::commit_end().saturating_add(T::RevealPeriod::get().max(One::one()))
+	}
+
+	/// Settles the bonds of the current round, publishes its output and starts the next round at
+	/// block `n`. Returns the number of participants settled.
+	fn close_round(n: T::BlockNumber) -> u32 {
+		let round = Self::current_round();
+		let participants = Participants::<T>::take();
+		let mut revealed = 0;
+
+		for who in participants.iter() {
+			if let Some(commitment) = Commitments::<T>::take(who) {
+				if commitment.revealed {
+					revealed += 1;
+					T::Currency::unreserve(who, commitment.bond);
+				} else {
+					let (imbalance, _) = T::Currency::slash_reserved(who, commitment.bond);
+					T::Slashed::on_unbalanced(imbalance);
+					Self::deposit_event(RawEvent::BondSlashed(who.clone(), commitment.bond));
+				}
+			}
+		}
+
+		let accumulator = Accumulator::take();
+		if revealed > 0 {
+			// Mixing in the previous output chains the rounds together
+			let previous = Self::output().unwrap_or_default();
+			let output = H256(blake2_256(&(previous, accumulator).encode()));
+			Output::put(output);
+			Self::deposit_event(RawEvent::OutputUpdated(round, output));
+		} else {
+			Self::deposit_event(RawEvent::NoReveals(round));
+		}
+
+		CurrentRound::put(round.wrapping_add(1));
+		RoundStart::<T>::put(n);
+		participants.len() as u32
+	}
+}
+
+impl<T: Trait> Randomness<H256> for Module<T> {
+	/// Hashes the subject together with the output of the last completed round
+	///
+	/// The output only changes once per round, so every call with the same subject during a round
+	/// returns the same value.
+	///
+	/// Until a round with at least one reveal has ended there is no output to draw from, and the
+	/// all-zero hash is returned for every subject. Consumers can check for it, or for
+	/// `Module::output` being `None`, to tell that the beacon is not ready yet.
+	fn random(subject: &[u8]) -> H256 {
+		match Self::output() {
+			Some(output) => H256(blake2_256(&(output, subject).encode())),
+			None => H256::zero(),
+		}
+	}
+}
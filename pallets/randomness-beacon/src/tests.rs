@@ -0,0 +1,300 @@
+use crate::*;
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types,
+	traits::OnInitialize,
+};
+use frame_system as system;
+use sp_io::TestExternalities;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 1;
+	pub const CommitBond: u64 = 10;
+	pub const CommitPeriod: u64 = 3;
+	pub const RevealPeriod: u64 = 2;
+	pub const MaxParticipants: u32 = 3;
+}
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = TestEvent;
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<TestRuntime>;
+}
+
+mod randomness_beacon {
+	pub use crate::Event;
+}
+
+impl_outer_event! {
+	pub enum TestEvent for TestRuntime {
+		randomness_beacon<T>,
+		system<T>,
+		balances<T>,
+	}
+}
+
+impl Trait for TestRuntime {
+	type Event = TestEvent;
+	type Currency = balances::Module<Self>;
+	type CommitBond = CommitBond;
+	type CommitPeriod = CommitPeriod;
+	type RevealPeriod = RevealPeriod;
+	type MaxParticipants = MaxParticipants;
+	type Slashed = ();
+	type WeightInfo = ();
+}
+
+pub type System = system::Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
+pub type Beacon = Module<TestRuntime>;
+
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	pub fn build() -> TestExternalities {
+		let mut storage = system::GenesisConfig::default()
+			.build_storage::<TestRuntime>()
+			.unwrap();
+		balances::GenesisConfig::<TestRuntime> {
+			balances: (0..10).map(|i| (i, 100)).collect(),
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		let mut ext = TestExternalities::from(storage);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+/// Runs `on_initialize` for every block up to and including `n`
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		Beacon::on_initialize(System::block_number());
+	}
+}
+
+/// A distinct secret value for each account
+fn secret(who: u64) -> H256 {
+	H256::repeat_byte(who as u8)
+}
+
+/// Commits `secret(who)` on behalf of `who`
+fn commit(who: u64) -> DispatchResult {
+	Beacon::commit(Origin::signed(who), Beacon::commitment(&who, &secret(who)))
+}
+
+#[test]
+fn commit_reserves_bond() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(commit(1));
+
+		assert_eq!(Balances::reserved_balance(&1), 10);
+		assert_eq!(Beacon::participants(), vec![1]);
+		assert!(!Beacon::commitment_of(&1).unwrap().revealed);
+
+		let expected_event = TestEvent::randomness_beacon(RawEvent::Committed(1, 0));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_noop!(commit(1), Error::<TestRuntime>::AlreadyCommitted);
+	})
+}
+
+#[test]
+fn commit_requires_bond() {
+	ExtBuilder::build().execute_with(|| {
+		// 50 has no funds to reserve
+		assert_noop!(commit(50), balances::Error::<TestRuntime, _>::InsufficientBalance);
+	})
+}
+
+#[test]
+fn participants_are_limited() {
+	ExtBuilder::build().execute_with(|| {
+		for i in 1..=3 {
+			assert_ok!(commit(i));
+		}
+		assert_noop!(commit(4), Error::<TestRuntime>::TooManyParticipants);
+	})
+}
+
+#[test]
+fn phases_are_enforced() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(commit(1));
+		assert_noop!(
+			Beacon::reveal(Origin::signed(1), secret(1)),
+			Error::<TestRuntime>::NotRevealPhase
+		);
+
+		// The commit phase covers blocks 0 to 2
+		run_to_block(3);
+		assert_noop!(commit(2), Error::<TestRuntime>::NotCommitPhase);
+		assert_ok!(Beacon::reveal(Origin::signed(1), secret(1)));
+		assert_noop!(
+			Beacon::reveal(Origin::signed(1), secret(1)),
+			Error::<TestRuntime>::AlreadyRevealed
+		);
+		assert_noop!(
+			Beacon::reveal(Origin::signed(2), secret(2)),
+			Error::<TestRuntime>::NoCommitment
+		);
+
+		// The next round starts at block 5
+		run_to_block(5);
+		assert_eq!(Beacon::current_round(), 1);
+		assert_eq!(Beacon::round_start(), 5);
+		assert_ok!(commit(2));
+	})
+}
+
+#[test]
+fn reveal_must_match_commitment() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(commit(1));
+		// 2 copies 1's commitment
+		assert_ok!(Beacon::commit(Origin::signed(2), Beacon::commitment(&1, &secret(1))));
+		run_to_block(3);
+
+		assert_noop!(
+			Beacon::reveal(Origin::signed(1), secret(2)),
+			Error::<TestRuntime>::InvalidReveal
+		);
+		assert_ok!(Beacon::reveal(Origin::signed(1), secret(1)));
+		// Knowing 1's value doesn't help 2, because the commitment includes the account
+		assert_noop!(
+			Beacon::reveal(Origin::signed(2), secret(1)),
+			Error::<TestRuntime>::InvalidReveal
+		);
+	})
+}
+
+#[test]
+fn round_produces_output() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(commit(1));
+		assert_ok!(commit(2));
+		run_to_block(3);
+		assert_ok!(Beacon::reveal(Origin::signed(2), secret(2)));
+		assert_ok!(Beacon::reveal(Origin::signed(1), secret(1)));
+
+		// Nothing is drawn before the first round ends
+		assert_eq!(Beacon::output(), None);
+		assert_eq!(Beacon::random(b"subject"), H256::zero());
+		run_to_block(5);
+
+		let accumulator = H256(blake2_256(&secret(1).encode()))
+			^ H256(blake2_256(&secret(2).encode()));
+		let output = H256(blake2_256(&(H256::zero(), accumulator).encode()));
+		assert_eq!(Beacon::output(), Some(output));
+		assert_eq!(Beacon::accumulator(), H256::zero());
+		assert_ne!(Beacon::random(b"subject"), H256::zero());
+		assert_ne!(Beacon::random(b"subject"), Beacon::random(b"other subject"));
+
+		// Bonds of revealers are returned
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&2), 100);
+		assert_eq!(Beacon::commitment_of(&1), None);
+		assert!(Beacon::participants().is_empty());
+
+		let expected_event = TestEvent::randomness_beacon(RawEvent::OutputUpdated(0, output));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn non_revealers_are_slashed() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(commit(1));
+		assert_ok!(commit(2));
+		run_to_block(3);
+		assert_ok!(Beacon::reveal(Origin::signed(1), secret(1)));
+		run_to_block(5);
+
+		assert_eq!(Balances::free_balance(&1), 100);
+		assert_eq!(Balances::reserved_balance(&2), 0);
+		assert_eq!(Balances::free_balance(&2), 90);
+		assert_eq!(Balances::total_issuance(), 990);
+
+		let expected_event = TestEvent::randomness_beacon(RawEvent::BondSlashed(2, 10));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn output_is_kept_without_reveals() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(commit(1));
+		run_to_block(3);
+		assert_ok!(Beacon::reveal(Origin::signed(1), secret(1)));
+		run_to_block(5);
+		let output = Beacon::output();
+		assert!(output.is_some());
+
+		// Nobody reveals in the second round
+		assert_ok!(commit(1));
+		run_to_block(10);
+		assert_eq!(Beacon::current_round(), 2);
+		assert_eq!(Beacon::output(), output);
+		assert_eq!(Balances::free_balance(&1), 90);
+
+		let expected_event = TestEvent::randomness_beacon(RawEvent::NoReveals(1));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn no_output_without_reveals() {
+	ExtBuilder::build().execute_with(|| {
+		// The first round ends without any reveals
+		assert_ok!(commit(1));
+		run_to_block(5);
+
+		assert_eq!(Beacon::current_round(), 1);
+		assert_eq!(Beacon::output(), None);
+		assert_eq!(Beacon::random(b"subject"), H256::zero());
+	})
+}
@@ -0,0 +1,44 @@
+//! Weights for the randomness beacon pallet, one for each benchmark in `benchmarking.rs`
+//!
+//! Committing decodes the whole participants vec, so its weight is linear in the number of
+//! participants `p`. The pallet charges for `MaxParticipants`.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the randomness beacon pallet
+pub trait WeightInfo {
+	fn commit(p: u32) -> Weight;
+	fn reveal() -> Weight;
+}
+
+/// Weights for the randomness beacon pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn commit(p: u32) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((200_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(T::DbWeight::get().reads(5))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn reveal() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn commit(p: u32) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((200_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add(RocksDbWeight::get().reads(5))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn reveal() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+}
@@ -0,0 +1,40 @@
+//! Benchmarks for the randomness pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	call_collective_flip {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let nonce = Nonce::get();
+	}: _(RawOrigin::Signed(caller))
+	verify {
+		assert_eq!(Nonce::get(), nonce.wrapping_add(1));
+	}
+
+	call_babe_vrf {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let nonce = Nonce::get();
+	}: _(RawOrigin::Signed(caller))
+	verify {
+		assert_eq!(Nonce::get(), nonce.wrapping_add(1));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_call_collective_flip::<TestRuntime>());
+			assert_ok!(test_benchmark_call_babe_vrf::<TestRuntime>());
+		});
+	}
+}
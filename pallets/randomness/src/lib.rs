@@ -9,8 +9,13 @@ use parity_scale_codec::Encode;
 use sp_core::H256;
 use sp_std::vec::Vec;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 /// The pallet's configuration trait.
 /// This trait includes two randomness sources. In production you will only ever need one. This pallet
@@ -27,6 +32,9 @@ pub trait Trait: system::Trait {
 	/// `RandomnessSource` but because we are using two sources in this pallet, we will name
 	/// them explicitly
 	type BabeRandomnessSource: Randomness<H256>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -41,7 +49,7 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// Grab a random seed and random value from the randomness collective flip pallet
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::call_collective_flip()]
 		fn call_collective_flip(origin) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 
@@ -57,7 +65,7 @@ decl_module! {
 		}
 
 		/// Grab a random seed and random value from the babe pallet
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::call_babe_vrf()]
 		fn call_babe_vrf(origin) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 
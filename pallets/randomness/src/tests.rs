@@ -64,6 +64,7 @@ impl Trait for TestRuntime {
 	type Event = TestEvent;
 	type CollectiveFlipRandomnessSource = CollectiveFlip;
 	type BabeRandomnessSource = Babe;
+	type WeightInfo = ();
 }
 
 parameter_types! {
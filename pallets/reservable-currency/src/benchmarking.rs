@@ -0,0 +1,143 @@
+//! Benchmarks for the reservable currency pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+/// An account with a large free balance and nothing reserved
+fn funded<T: Trait>(name: &'static str) -> T::AccountId {
+	let who: T::AccountId = account(name, 0, 0);
+	T::Currency::unreserve(&who, T::Currency::reserved_balance(&who));
+	T::Currency::make_free_balance_be(&who, 1_000_000u32.into());
+	who
+}
+
+/// Opens an escrow of 1_000 from a funded buyer to a funded seller, returning its id
+fn open<T: Trait>(
+	duration: u32,
+) -> Result<(T::AccountId, T::AccountId, EscrowId), &'static str> {
+	let buyer = funded::<T>("buyer");
+	let seller = funded::<T>("seller");
+	let id = Module::<T>::next_escrow_id();
+	Module::<T>::open_escrow(
+		RawOrigin::Signed(buyer.clone()).into(),
+		seller.clone(),
+		1_000u32.into(),
+		duration.into(),
+	)?;
+	Ok((buyer, seller, id))
+}
+
+/// The status of an escrow, if it has not been settled
+fn status<T: Trait>(id: EscrowId) -> Option<EscrowStatus> {
+	Module::<T>::escrow(id).map(|escrow| escrow.status)
+}
+
+benchmarks! {
+	_ { }
+
+	reserve_funds {
+		let caller = funded::<T>("caller");
+	}: _(RawOrigin::Signed(caller.clone()), 1_000u32.into())
+	verify {
+		assert_eq!(T::Currency::reserved_balance(&caller), 1_000u32.into());
+	}
+
+	unreserve_funds {
+		let caller = funded::<T>("caller");
+		T::Currency::reserve(&caller, 1_000u32.into())?;
+	}: _(RawOrigin::Signed(caller.clone()), 1_000u32.into())
+	verify {
+		assert_eq!(T::Currency::reserved_balance(&caller), 0u32.into());
+	}
+
+	transfer_funds {
+		let caller = funded::<T>("caller");
+		// A new account, so the transfer also creates it
+		let dest: T::AccountId = account("dest", 0, 0);
+		T::Currency::make_free_balance_be(&dest, 0u32.into());
+	}: _(RawOrigin::Signed(caller), dest.clone(), 1_000u32.into())
+	verify {
+		assert_eq!(T::Currency::free_balance(&dest), 1_000u32.into());
+	}
+
+	unreserve_and_transfer {
+		let caller = funded::<T>("caller");
+		let to_punish = funded::<T>("to_punish");
+		T::Currency::reserve(&to_punish, 1_000u32.into())?;
+		let dest: T::AccountId = account("dest", 0, 0);
+		T::Currency::make_free_balance_be(&dest, 0u32.into());
+	}: _(RawOrigin::Signed(caller), to_punish, dest.clone(), 1_000u32.into())
+	verify {
+		assert_eq!(T::Currency::free_balance(&dest), 1_000u32.into());
+	}
+
+	open_escrow {
+		let buyer = funded::<T>("buyer");
+		let seller = funded::<T>("seller");
+		let id = Module::<T>::next_escrow_id();
+	}: _(RawOrigin::Signed(buyer), seller, 1_000u32.into(), 10u32.into())
+	verify {
+		assert_eq!(status::<T>(id), Some(EscrowStatus::Open));
+	}
+
+	release {
+		let (buyer, _, id) = open::<T>(10)?;
+	}: _(RawOrigin::Signed(buyer), id)
+	verify {
+		assert_eq!(status::<T>(id), None);
+	}
+
+	refund {
+		let (_, seller, id) = open::<T>(10)?;
+	}: _(RawOrigin::Signed(seller), id)
+	verify {
+		assert_eq!(status::<T>(id), None);
+	}
+
+	dispute {
+		let (buyer, _, id) = open::<T>(10)?;
+	}: _(RawOrigin::Signed(buyer), id)
+	verify {
+		assert_eq!(status::<T>(id), Some(EscrowStatus::Disputed));
+	}
+
+	resolve {
+		let (buyer, _, id) = open::<T>(10)?;
+		Module::<T>::dispute(RawOrigin::Signed(buyer).into(), id)?;
+	}: _(T::ArbiterOrigin::successful_origin(), id, 500u32.into())
+	verify {
+		assert_eq!(status::<T>(id), None);
+	}
+
+	expire {
+		// An escrow with no duration has expired as soon as it is opened
+		let (buyer, _, id) = open::<T>(0)?;
+	}: _(RawOrigin::Signed(buyer), id)
+	verify {
+		assert_eq!(status::<T>(id), None);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_reserve_funds::<TestRuntime>());
+			assert_ok!(test_benchmark_unreserve_funds::<TestRuntime>());
+			assert_ok!(test_benchmark_transfer_funds::<TestRuntime>());
+			assert_ok!(test_benchmark_unreserve_and_transfer::<TestRuntime>());
+			assert_ok!(test_benchmark_open_escrow::<TestRuntime>());
+			assert_ok!(test_benchmark_release::<TestRuntime>());
+			assert_ok!(test_benchmark_refund::<TestRuntime>());
+			assert_ok!(test_benchmark_dispute::<TestRuntime>());
+			assert_ok!(test_benchmark_resolve::<TestRuntime>());
+			assert_ok!(test_benchmark_expire::<TestRuntime>());
+		});
+	}
+}
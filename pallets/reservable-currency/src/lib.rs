@@ -1,18 +1,49 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 //! A pallet to demonstrate the `ReservableCurrency` trait
 //! borrows collateral locking logic from pallet_treasury
+//!
+//! It also implements a simple escrow. A buyer opens an escrow, which reserves funds for a
+//! seller until an expiry block. The buyer may release the funds to the seller, and the seller may
+//! refund them to the buyer. Either party may instead raise a dispute, which an arbiter settles by
+//! splitting the reserved funds between them. An escrow that expires undisputed is returned to the
+//! buyer. The pallet remembers how much of each buyer's reserve is escrowed, and its other calls
+//! never unreserve those funds. Escrows are removed from storage once they are settled.
 
 use frame_support::{
-	decl_event, decl_module,
+	decl_error, decl_event, decl_module, decl_storage,
 	dispatch::DispatchResult,
-	traits::{Currency, ExistenceRequirement::AllowDeath, ReservableCurrency},
+	ensure,
+	traits::{
+		BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement::AllowDeath,
+		ReservableCurrency,
+	},
 };
 use frame_system::{self as system, ensure_signed};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	RuntimeDebug,
+};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 // balance type using reservable currency type
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type EscrowOf<T> = Escrow<
+	<T as system::Trait>::AccountId,
+	BalanceOf<T>,
+	<T as system::Trait>::BlockNumber,
+>;
+
+/// Escrows are identified by a sequential number
+pub type EscrowId = u64;
 
 pub trait Trait: system::Trait + Sized {
 	// overarching event type
@@ -20,6 +51,49 @@ pub trait Trait: system::Trait + Sized {
 
 	/// Currency type for this pallet.
 	type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
+
+	/// The origin that settles disputed escrows
+	type ArbiterOrigin: EnsureOrigin<Self::Origin>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+/// The lifecycle of an escrow
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum EscrowStatus {
+	/// The funds are reserved, and either party may settle or dispute
+	Open,
+	/// The funds are reserved until the arbiter settles
+	Disputed,
+}
+
+/// Funds reserved by a buyer on behalf of a seller
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Escrow<AccountId, Balance, BlockNumber> {
+	/// The account whose funds are reserved
+	pub buyer: AccountId,
+	/// The account that receives the funds when the buyer releases them
+	pub seller: AccountId,
+	/// The amount reserved
+	pub amount: Balance,
+	/// The block at which an undisputed escrow may be returned to the buyer
+	pub expiry: BlockNumber,
+	/// The current state of the escrow
+	pub status: EscrowStatus,
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as ReservableCurrency {
+		/// The id the next escrow will receive
+		NextEscrowId get(fn next_escrow_id): EscrowId;
+
+		/// The escrows that have not been settled yet
+		Escrows get(fn escrow): map hasher(twox_64_concat) EscrowId => Option<EscrowOf<T>>;
+
+		/// The part of each buyer's reserved balance that is held by unsettled escrows
+		Escrowed get(fn escrowed): map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
+	}
 }
 
 decl_event!(
@@ -33,15 +107,62 @@ decl_event!(
 		UnlockFunds(AccountId, Balance, BlockNumber),
 		// sender, dest, amount, block number
 		TransferFunds(AccountId, AccountId, Balance, BlockNumber),
+		/// An escrow was opened (id, buyer, seller, amount, expiry)
+		EscrowOpened(EscrowId, AccountId, AccountId, Balance, BlockNumber),
+		/// The buyer released an escrow to the seller
+		EscrowReleased(EscrowId),
+		/// The seller refunded an escrow to the buyer
+		EscrowRefunded(EscrowId),
+		/// A party disputed an escrow
+		EscrowDisputed(EscrowId, AccountId),
+		/// The arbiter settled a dispute (id, amount to seller, amount to buyer)
+		DisputeResolved(EscrowId, Balance, Balance),
+		/// An undisputed escrow expired and was returned to the buyer
+		EscrowExpired(EscrowId),
 	}
 );
 
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The buyer can not reserve the amount requested
+		InsufficientBalance,
+		/// The buyer and seller must be different accounts
+		CannotEscrowToSelf,
+		/// No escrow exists with the given id
+		UnknownEscrow,
+		/// Only the buyer may do this
+		NotBuyer,
+		/// Only the seller may do this
+		NotSeller,
+		/// Only the buyer or the seller may do this
+		NotParticipant,
+		/// The escrow is no longer open
+		EscrowClosed,
+		/// The escrow is not disputed
+		NotDisputed,
+		/// The escrow has passed its expiry
+		EscrowExpired,
+		/// The escrow has not reached its expiry
+		NotExpired,
+		/// The seller's share exceeds the escrowed amount
+		ShareTooLarge,
+		/// The funds are held in escrow, and can only be unreserved by settling it
+		FundsInEscrow,
+		/// The buyer's reserved balance no longer covers the escrow
+		EscrowUnderfunded,
+		/// An escrow must hold some funds
+		ZeroAmount,
+	}
+}
+
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
+		type Error = Error<T>;
+
 		/// Reserves the specified amount of funds from the caller
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::reserve_funds()]
 		pub fn reserve_funds(origin, amount: BalanceOf<T>) -> DispatchResult {
 			let locker = ensure_signed(origin)?;
 
@@ -55,9 +176,14 @@ decl_module! {
 		}
 
 		/// Unreserves the specified amount of funds from the caller
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::unreserve_funds()]
 		pub fn unreserve_funds(origin, amount: BalanceOf<T>) -> DispatchResult {
 			let unlocker = ensure_signed(origin)?;
+			ensure!(
+				T::Currency::reserved_balance(&unlocker).saturating_sub(amount)
+					>= Self::escrowed(&unlocker),
+				Error::<T>::FundsInEscrow
+			);
 
 			T::Currency::unreserve(&unlocker, amount);
 			// ReservableCurrency::unreserve does not fail (it will lock up as much as amount)
@@ -69,7 +195,7 @@ decl_module! {
 		}
 
 		/// Transfers funds. Essentially a wrapper around the Currency's own transfer method
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::transfer_funds()]
 		pub fn transfer_funds(origin, dest: T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
 			let sender = ensure_signed(origin)?;
 
@@ -83,7 +209,7 @@ decl_module! {
 
 		/// Atomically unreserves funds and and transfers them.
 		/// might be useful in closed economic systems
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::unreserve_and_transfer()]
 		pub fn unreserve_and_transfer(
 			origin,
 			to_punish: T::AccountId,
@@ -92,6 +218,11 @@ decl_module! {
 		) -> DispatchResult {
 			let _ = ensure_signed(origin)?; // dangerous because can be called with any signature (so dont do this in practice ever!)
 
+			// Funds held in escrow are not collateral, so they are never punished
+			let unescrowed = T::Currency::reserved_balance(&to_punish)
+				.saturating_sub(Self::escrowed(&to_punish));
+			let collateral = collateral.min(unescrowed);
+
 						// If collateral is bigger than to_punish's reserved_balance, store what's left in overdraft.
 			let overdraft = T::Currency::unreserve(&to_punish, collateral);
 
@@ -102,5 +233,165 @@ decl_module! {
 
 			Ok(())
 		}
+
+		/// Reserves funds from the caller on behalf of a seller, until `duration` blocks from now
+		#[weight = T::WeightInfo::open_escrow()]
+		pub fn open_escrow(
+			origin,
+			seller: T::AccountId,
+			amount: BalanceOf<T>,
+			duration: T::BlockNumber,
+		) -> DispatchResult {
+			let buyer = ensure_signed(origin)?;
+			ensure!(buyer != seller, Error::<T>::CannotEscrowToSelf);
+			ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
+
+			T::Currency::reserve(&buyer, amount)
+				.map_err(|_| Error::<T>::InsufficientBalance)?;
+
+			let id = Self::next_escrow_id();
+			let expiry = <system::Module<T>>::block_number().saturating_add(duration);
+			NextEscrowId::put(id.wrapping_add(1));
+			Escrowed::<T>::mutate(&buyer, |escrowed| *escrowed = escrowed.saturating_add(amount));
+			Escrows::<T>::insert(id, Escrow {
+				buyer: buyer.clone(),
+				seller: seller.clone(),
+				amount,
+				expiry,
+				status: EscrowStatus::Open,
+			});
+
+			Self::deposit_event(RawEvent::EscrowOpened(id, buyer, seller, amount, expiry));
+			Ok(())
+		}
+
+		/// Releases the caller's escrowed funds to the seller
+		#[weight = T::WeightInfo::release()]
+		pub fn release(origin, id: EscrowId) -> DispatchResult {
+			let caller = ensure_signed(origin)?;
+			let escrow = Self::escrow(id).ok_or(Error::<T>::UnknownEscrow)?;
+			ensure!(caller == escrow.buyer, Error::<T>::NotBuyer);
+			Self::ensure_funded(&escrow)?;
+
+			T::Currency::repatriate_reserved(
+				&escrow.buyer,
+				&escrow.seller,
+				escrow.amount,
+				BalanceStatus::Free,
+			)?;
+			Self::release_escrowed(&escrow);
+			Escrows::<T>::remove(id);
+
+			Self::deposit_event(RawEvent::EscrowReleased(id));
+			Ok(())
+		}
+
+		/// Returns escrowed funds to the buyer. Only the seller may do this.
+		#[weight = T::WeightInfo::refund()]
+		pub fn refund(origin, id: EscrowId) -> DispatchResult {
+			let caller = ensure_signed(origin)?;
+			let escrow = Self::escrow(id).ok_or(Error::<T>::UnknownEscrow)?;
+			ensure!(caller == escrow.seller, Error::<T>::NotSeller);
+
+			T::Currency::unreserve(&escrow.buyer, escrow.amount);
+			Self::release_escrowed(&escrow);
+			Escrows::<T>::remove(id);
+
+			Self::deposit_event(RawEvent::EscrowRefunded(id));
+			Ok(())
+		}
+
+		/// Hands an open escrow to the arbiter. Either party may do this before the expiry.
+		#[weight = T::WeightInfo::dispute()]
+		pub fn dispute(origin, id: EscrowId) -> DispatchResult {
+			let caller = ensure_signed(origin)?;
+			let mut escrow = Self::escrow(id).ok_or(Error::<T>::UnknownEscrow)?;
+			ensure!(
+				caller == escrow.buyer || caller == escrow.seller,
+				Error::<T>::NotParticipant
+			);
+			ensure!(escrow.status == EscrowStatus::Open, Error::<T>::EscrowClosed);
+			ensure!(
+				<system::Module<T>>::block_number() < escrow.expiry,
+				Error::<T>::EscrowExpired
+			);
+
+			escrow.status = EscrowStatus::Disputed;
+			Escrows::<T>::insert(id, escrow);
+
+			Self::deposit_event(RawEvent::EscrowDisputed(id, caller));
+			Ok(())
+		}
+
+		/// Settles a disputed escrow by paying `seller_share` to the seller and returning the
+		/// rest to the buyer
+		///
+		/// If part of the buyer's reserve was slashed elsewhere, only what is still reserved is
+		/// settled. The seller's share is paid from it first, and the buyer gets what remains.
+		#[weight = T::WeightInfo::resolve()]
+		pub fn resolve(origin, id: EscrowId, seller_share: BalanceOf<T>) -> DispatchResult {
+			T::ArbiterOrigin::ensure_origin(origin)?;
+			let escrow = Self::escrow(id).ok_or(Error::<T>::UnknownEscrow)?;
+			ensure!(escrow.status == EscrowStatus::Disputed, Error::<T>::NotDisputed);
+			ensure!(seller_share <= escrow.amount, Error::<T>::ShareTooLarge);
+
+			let funded = escrow.amount.min(T::Currency::reserved_balance(&escrow.buyer));
+			let seller_share = seller_share.min(funded);
+
+			T::Currency::repatriate_reserved(
+				&escrow.buyer,
+				&escrow.seller,
+				seller_share,
+				BalanceStatus::Free,
+			)?;
+			let buyer_share = funded - seller_share;
+			T::Currency::unreserve(&escrow.buyer, buyer_share);
+			Self::release_escrowed(&escrow);
+			Escrows::<T>::remove(id);
+
+			Self::deposit_event(RawEvent::DisputeResolved(id, seller_share, buyer_share));
+			Ok(())
+		}
+
+		/// Returns an expired, undisputed escrow to the buyer. Anyone may do this.
+		#[weight = T::WeightInfo::expire()]
+		pub fn expire(origin, id: EscrowId) -> DispatchResult {
+			ensure_signed(origin)?;
+			let escrow = Self::escrow(id).ok_or(Error::<T>::UnknownEscrow)?;
+			ensure!(escrow.status == EscrowStatus::Open, Error::<T>::EscrowClosed);
+			ensure!(
+				<system::Module<T>>::block_number() >= escrow.expiry,
+				Error::<T>::NotExpired
+			);
+
+			T::Currency::unreserve(&escrow.buyer, escrow.amount);
+			Self::release_escrowed(&escrow);
+			Escrows::<T>::remove(id);
+
+			Self::deposit_event(RawEvent::EscrowExpired(id));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Ensure the buyer's reserved balance still covers an escrow, so paying it to the seller
+	/// moves the whole amount instead of leaving a shortfall behind
+	fn ensure_funded(escrow: &EscrowOf<T>) -> DispatchResult {
+		ensure!(
+			T::Currency::reserved_balance(&escrow.buyer) >= escrow.amount,
+			Error::<T>::EscrowUnderfunded
+		);
+		Ok(())
+	}
+
+	/// Stop holding a settled escrow's funds in the buyer's escrowed balance
+	fn release_escrowed(escrow: &EscrowOf<T>) {
+		let remaining = Self::escrowed(&escrow.buyer).saturating_sub(escrow.amount);
+		if remaining.is_zero() {
+			Escrowed::<T>::remove(&escrow.buyer);
+		} else {
+			Escrowed::<T>::insert(&escrow.buyer, remaining);
+		}
 	}
 }
@@ -1,7 +1,10 @@
 use crate::*;
 use balances;
-use frame_support::{assert_ok, impl_outer_event, impl_outer_origin, parameter_types};
-use frame_system::{self as system};
+use frame_support::{
+	assert_noop, assert_ok, dispatch::DispatchError, impl_outer_event, impl_outer_origin,
+	parameter_types,
+};
+use frame_system::{self as system, EnsureRoot, RawOrigin};
 use sp_core::H256;
 use sp_io;
 use sp_runtime::{
@@ -76,6 +79,8 @@ impl_outer_event! {
 impl Trait for TestRuntime {
 	type Event = TestEvent;
 	type Currency = balances::Module<Self>;
+	type ArbiterOrigin = EnsureRoot<u64>;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
@@ -180,3 +185,266 @@ fn new_test_ext_unreserve_and_transfer() {
 		assert_eq!(Balances::free_balance(&2), 15000);
 	})
 }
+
+/// Account 1 buys from account 2 for 1000, with an expiry at block 11
+fn open_default_escrow() -> EscrowId {
+	let id = ReservableCurrency::next_escrow_id();
+	assert_ok!(ReservableCurrency::open_escrow(Origin::signed(1), 2, 1000, 10));
+	id
+}
+
+fn escrow_status(id: EscrowId) -> EscrowStatus {
+	ReservableCurrency::escrow(id).unwrap().status
+}
+
+#[test]
+fn open_escrow_reserves_funds() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+
+		assert_eq!(Balances::free_balance(&1), 9000);
+		assert_eq!(Balances::reserved_balance(&1), 1000);
+		assert_eq!(
+			ReservableCurrency::escrow(id),
+			Some(Escrow {
+				buyer: 1,
+				seller: 2,
+				amount: 1000,
+				expiry: 11,
+				status: EscrowStatus::Open,
+			})
+		);
+		assert_eq!(ReservableCurrency::next_escrow_id(), id + 1);
+
+		let expected_event =
+			TestEvent::reservable_currency(RawEvent::EscrowOpened(id, 1, 2, 1000, 11));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn open_escrow_checks_buyer() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ReservableCurrency::open_escrow(Origin::signed(1), 1, 1000, 10),
+			Error::<TestRuntime>::CannotEscrowToSelf
+		);
+		assert_noop!(
+			ReservableCurrency::open_escrow(Origin::signed(1), 2, 10001, 10),
+			Error::<TestRuntime>::InsufficientBalance
+		);
+		assert_noop!(
+			ReservableCurrency::open_escrow(Origin::signed(1), 2, 0, 10),
+			Error::<TestRuntime>::ZeroAmount
+		);
+	})
+}
+
+#[test]
+fn buyer_releases_to_seller() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+
+		assert_noop!(
+			ReservableCurrency::release(Origin::signed(2), id),
+			Error::<TestRuntime>::NotBuyer
+		);
+		assert_ok!(ReservableCurrency::release(Origin::signed(1), id));
+
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 9000);
+		assert_eq!(Balances::free_balance(&2), 12000);
+		// A settled escrow is removed, so it can not be settled again
+		assert_eq!(ReservableCurrency::escrow(id), None);
+		assert_noop!(
+			ReservableCurrency::release(Origin::signed(1), id),
+			Error::<TestRuntime>::UnknownEscrow
+		);
+	})
+}
+
+#[test]
+fn seller_refunds_to_buyer() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+
+		assert_noop!(
+			ReservableCurrency::refund(Origin::signed(1), id),
+			Error::<TestRuntime>::NotSeller
+		);
+		assert_ok!(ReservableCurrency::refund(Origin::signed(2), id));
+
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 10000);
+		assert_eq!(Balances::free_balance(&2), 11000);
+		assert_eq!(ReservableCurrency::escrow(id), None);
+	})
+}
+
+#[test]
+fn arbiter_splits_disputed_escrow() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+
+		// Only a disputed escrow can be resolved, and only by the arbiter
+		assert_noop!(
+			ReservableCurrency::resolve(RawOrigin::Root.into(), id, 300),
+			Error::<TestRuntime>::NotDisputed
+		);
+		assert_noop!(
+			ReservableCurrency::dispute(Origin::signed(3), id),
+			Error::<TestRuntime>::NotParticipant
+		);
+		assert_ok!(ReservableCurrency::dispute(Origin::signed(2), id));
+		assert_eq!(escrow_status(id), EscrowStatus::Disputed);
+		assert_noop!(
+			ReservableCurrency::resolve(Origin::signed(1), id, 300),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			ReservableCurrency::resolve(RawOrigin::Root.into(), id, 1001),
+			Error::<TestRuntime>::ShareTooLarge
+		);
+
+		assert_ok!(ReservableCurrency::resolve(RawOrigin::Root.into(), id, 300));
+
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 9700);
+		assert_eq!(Balances::free_balance(&2), 11300);
+		assert_eq!(ReservableCurrency::escrow(id), None);
+
+		let expected_event =
+			TestEvent::reservable_currency(RawEvent::DisputeResolved(id, 300, 700));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn undisputed_escrow_expires() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+
+		System::set_block_number(10);
+		assert_noop!(
+			ReservableCurrency::expire(Origin::signed(3), id),
+			Error::<TestRuntime>::NotExpired
+		);
+
+		System::set_block_number(11);
+		assert_noop!(
+			ReservableCurrency::dispute(Origin::signed(1), id),
+			Error::<TestRuntime>::EscrowExpired
+		);
+		assert_ok!(ReservableCurrency::expire(Origin::signed(3), id));
+
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 10000);
+		assert_eq!(ReservableCurrency::escrow(id), None);
+	})
+}
+
+#[test]
+fn disputed_escrow_does_not_expire() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+		assert_ok!(ReservableCurrency::dispute(Origin::signed(1), id));
+
+		System::set_block_number(11);
+		assert_noop!(
+			ReservableCurrency::expire(Origin::signed(3), id),
+			Error::<TestRuntime>::EscrowClosed
+		);
+		assert_eq!(Balances::reserved_balance(&1), 1000);
+	})
+}
+
+#[test]
+fn escrowed_funds_can_not_be_unreserved() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+		assert_ok!(ReservableCurrency::reserve_funds(Origin::signed(1), 500));
+
+		// Only the funds reserved outside the escrow may be unreserved
+		assert_noop!(
+			ReservableCurrency::unreserve_funds(Origin::signed(1), 501),
+			Error::<TestRuntime>::FundsInEscrow
+		);
+		assert_ok!(ReservableCurrency::unreserve_funds(Origin::signed(1), 500));
+		assert_eq!(Balances::reserved_balance(&1), 1000);
+
+		// Nor can they be taken as collateral
+		assert_ok!(ReservableCurrency::unreserve_and_transfer(
+			Origin::signed(3),
+			1,
+			3,
+			1000
+		));
+		assert_eq!(Balances::reserved_balance(&1), 1000);
+		assert_eq!(Balances::free_balance(&3), 12000);
+
+		// Settling the escrow pays the seller in full
+		assert_ok!(ReservableCurrency::release(Origin::signed(1), id));
+		assert_eq!(Balances::free_balance(&2), 12000);
+		assert_eq!(ReservableCurrency::escrowed(1), 0);
+	})
+}
+
+#[test]
+fn underfunded_escrow_is_not_released() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+		assert_ok!(ReservableCurrency::dispute(Origin::signed(1), id));
+
+		// Another pallet slashes part of the buyer's reserve
+		<Balances as frame_support::traits::ReservableCurrency<u64>>::slash_reserved(&1, 400);
+
+		assert_noop!(
+			ReservableCurrency::release(Origin::signed(1), id),
+			Error::<TestRuntime>::EscrowUnderfunded
+		);
+		assert_eq!(Balances::free_balance(&2), 11000);
+	})
+}
+
+#[test]
+fn arbiter_settles_what_is_still_reserved() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+		assert_ok!(ReservableCurrency::dispute(Origin::signed(1), id));
+
+		// Another pallet slashes part of the buyer's reserve, leaving 600 of the escrow
+		<Balances as frame_support::traits::ReservableCurrency<u64>>::slash_reserved(&1, 400);
+
+		assert_ok!(ReservableCurrency::resolve(RawOrigin::Root.into(), id, 300));
+
+		// The seller's share is paid in full, and the buyer gets back what is left
+		assert_eq!(Balances::free_balance(&2), 11300);
+		assert_eq!(Balances::free_balance(&1), 9300);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(ReservableCurrency::escrowed(1), 0);
+		assert_eq!(ReservableCurrency::escrow(id), None);
+
+		let expected_event =
+			TestEvent::reservable_currency(RawEvent::DisputeResolved(id, 300, 300));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn seller_share_is_capped_at_what_is_still_reserved() {
+	new_test_ext().execute_with(|| {
+		let id = open_default_escrow();
+		assert_ok!(ReservableCurrency::dispute(Origin::signed(2), id));
+		<Balances as frame_support::traits::ReservableCurrency<u64>>::slash_reserved(&1, 400);
+
+		assert_ok!(ReservableCurrency::resolve(RawOrigin::Root.into(), id, 1000));
+
+		assert_eq!(Balances::free_balance(&2), 11600);
+		assert_eq!(Balances::free_balance(&1), 9000);
+		assert_eq!(Balances::reserved_balance(&1), 0);
+
+		let expected_event =
+			TestEvent::reservable_currency(RawEvent::DisputeResolved(id, 600, 0));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
@@ -0,0 +1,41 @@
+//! Benchmarks for the ringbuffer queue pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	add_to_queue {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller), 42, true)
+
+	add_multiple {
+		// The buffer holds at most `BufferIndex::max_value()` items, after which it wraps
+		let n in 0 .. BufferIndex::max_value() as u32;
+		let caller: T::AccountId = account("caller", 0, 0);
+		let integers: Vec<i32> = (0..n as i32).collect();
+	}: _(RawOrigin::Signed(caller), integers, true)
+
+	pop_from_queue {
+		let caller: T::AccountId = account("caller", 0, 0);
+		Module::<T>::add_to_queue(RawOrigin::Signed(caller.clone()).into(), 42, true)?;
+	}: _(RawOrigin::Signed(caller))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_add_to_queue::<TestRuntime>());
+			assert_ok!(test_benchmark_add_multiple::<TestRuntime>());
+			assert_ok!(test_benchmark_pop_from_queue::<TestRuntime>());
+		});
+	}
+}
@@ -4,7 +4,11 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
-use frame_support::{decl_event, decl_module, decl_storage, dispatch::DispatchResult};
+use frame_support::{
+	decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	weights::{DispatchClass, FunctionOf, Pays},
+};
 use frame_system::{self as system, ensure_signed};
 use sp_std::prelude::*;
 
@@ -12,8 +16,13 @@ mod ringbuffer;
 
 use ringbuffer::{RingBufferTrait, RingBufferTransient};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 type BufferIndex = u8;
 
@@ -26,6 +35,9 @@ pub struct ValueStruct {
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -50,7 +62,7 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// Add an item to the queue
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::add_to_queue()]
 		pub fn add_to_queue(origin, integer: i32, boolean: bool) -> DispatchResult {
 			// only a user can push into the queue
 			let _user = ensure_signed(origin)?;
@@ -62,7 +74,11 @@ decl_module! {
 		}
 
 		/// Add several items to the queue
-		#[weight = 10_000]
+		#[weight = FunctionOf(
+			|(integers, _): (&Vec<i32>, &bool)| T::WeightInfo::add_multiple(integers.len() as u32),
+			DispatchClass::Normal,
+			Pays::Yes,
+		)]
 		pub fn add_multiple(origin, integers: Vec<i32>, boolean: bool) -> DispatchResult {
 			// only a user can push into the queue
 			let _user = ensure_signed(origin)?;
@@ -76,7 +92,7 @@ decl_module! {
 		}
 
 		/// Remove and return an item from the queue
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::pop_from_queue()]
 		pub fn pop_from_queue(origin) -> DispatchResult {
 			// only a user can pop from the queue
 			let _user = ensure_signed(origin)?;
@@ -0,0 +1,100 @@
+//! Benchmarks for the simple crowdfund pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+/// An account with plenty of funds
+fn funded<T: Trait>(name: &'static str) -> T::AccountId {
+	let who: T::AccountId = account(name, 0, 0);
+	T::Currency::make_free_balance_be(&who, 1_000_000u32.into());
+	who
+}
+
+/// The amount each benchmark contributes, which is also the goal of the funds they create
+fn contribution<T: Trait>() -> BalanceOf<T> {
+	T::MinContribution::get().max(1_000u32.into())
+}
+
+/// Creates a fund that ends 10 blocks from now and has one contribution that meets its goal.
+/// Returns the fund's index and end block.
+fn create_funded<T: Trait>() -> Result<(FundIndex, T::BlockNumber), &'static str> {
+	let creator = funded::<T>("creator");
+	let contributor = funded::<T>("contributor");
+	let index = FundCount::get();
+	let end = system::Module::<T>::block_number() + 10u32.into();
+	Module::<T>::create(
+		RawOrigin::Signed(creator.clone()).into(),
+		creator,
+		contribution::<T>(),
+		end,
+	)?;
+	Module::<T>::contribute(RawOrigin::Signed(contributor).into(), index, contribution::<T>())?;
+	Ok((index, end))
+}
+
+benchmarks! {
+	_ { }
+
+	create {
+		let caller = funded::<T>("creator");
+		let index = FundCount::get();
+		let end = system::Module::<T>::block_number() + 10u32.into();
+	}: _(RawOrigin::Signed(caller.clone()), caller, contribution::<T>(), end)
+	verify {
+		assert!(Module::<T>::funds(index).is_some());
+	}
+
+	contribute {
+		let (index, _) = create_funded::<T>()?;
+		let caller = funded::<T>("caller");
+	}: _(RawOrigin::Signed(caller.clone()), index, contribution::<T>())
+	verify {
+		assert_eq!(Module::<T>::contribution_get(index, &caller), contribution::<T>());
+	}
+
+	withdraw {
+		let (index, end) = create_funded::<T>()?;
+		let caller: T::AccountId = account("contributor", 0, 0);
+		system::Module::<T>::set_block_number(end + 1u32.into());
+	}: _(RawOrigin::Signed(caller.clone()), index)
+	verify {
+		assert!(Module::<T>::contribution_get(index, &caller).is_zero());
+	}
+
+	dissolve {
+		let (index, end) = create_funded::<T>()?;
+		let caller = funded::<T>("caller");
+		system::Module::<T>::set_block_number(end + T::RetirementPeriod::get());
+	}: _(RawOrigin::Signed(caller), index)
+	verify {
+		assert!(Module::<T>::funds(index).is_none());
+	}
+
+	dispense {
+		let (index, end) = create_funded::<T>()?;
+		let caller = funded::<T>("caller");
+		system::Module::<T>::set_block_number(end);
+	}: _(RawOrigin::Signed(caller), index)
+	verify {
+		assert!(Module::<T>::funds(index).is_none());
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_create::<Test>());
+			assert_ok!(test_benchmark_contribute::<Test>());
+			assert_ok!(test_benchmark_withdraw::<Test>());
+			assert_ok!(test_benchmark_dissolve::<Test>());
+			assert_ok!(test_benchmark_dispense::<Test>());
+		});
+	}
+}
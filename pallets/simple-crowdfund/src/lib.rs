@@ -22,8 +22,13 @@ use sp_runtime::{
 };
 use sp_std::prelude::*;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 const PALLET_ID: ModuleId = ModuleId(*b"ex/cfund");
 
@@ -45,6 +50,9 @@ pub trait Trait: system::Trait {
 	/// The period of time (in blocks) after an unsuccessful crowdfund ending during which
 	/// contributors are able to withdraw their funds. After this period, their funds are lost.
 	type RetirementPeriod: Get<Self::BlockNumber>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 /// Simple index for identifying a fund.
@@ -126,7 +134,7 @@ decl_module! {
 		type Error = Error<T>;
 
 		/// Create a new fund
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::create()]
 		fn create(
 			origin,
 			beneficiary: AccountIdOf<T>,
@@ -166,7 +174,7 @@ decl_module! {
 		}
 
 		/// Contribute funds to an existing fund
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::contribute()]
 		fn contribute(origin, index: FundIndex, value: BalanceOf<T>) {
 			let who = ensure_signed(origin)?;
 
@@ -195,7 +203,7 @@ decl_module! {
 		}
 
 		/// Withdraw full balance of a contributor to a fund
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::withdraw()]
 		fn withdraw(origin, #[compact] index: FundIndex) {
 			let who = ensure_signed(origin)?;
 
@@ -225,7 +233,7 @@ decl_module! {
 		/// Dissolve an entire crowdfund after its retirement period has expired.
 		/// Anyone can call this function, and they are incentivized to do so because
 		/// they inherit the deposit.
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::dissolve()]
 		fn dissolve(origin, index: FundIndex) {
 			let reporter = ensure_signed(origin)?;
 
@@ -257,7 +265,7 @@ decl_module! {
 		/// Dispense a payment to the beneficiary of a successful crowdfund.
 		/// The beneficiary receives the contributed funds and the caller receives
 		/// the deposit as a reward to incentivize clearing settled crowdfunds out of storage.
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::dispense()]
 		fn dispense(origin, index: FundIndex) {
 			let caller = ensure_signed(origin)?;
 
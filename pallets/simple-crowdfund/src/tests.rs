@@ -87,6 +87,7 @@ impl Trait for Test {
 	type SubmissionDeposit = SubmissionDeposit;
 	type MinContribution = MinContribution;
 	type RetirementPeriod = RetirementPeriod;
+	type WeightInfo = ();
 }
 
 type System = system::Module<Test>;
@@ -96,7 +97,7 @@ use balances::Error as BalancesError;
 
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
-fn new_test_ext() -> sp_io::TestExternalities {
+pub fn new_test_ext() -> sp_io::TestExternalities {
 	let mut t = system::GenesisConfig::default()
 		.build_storage::<Test>()
 		.unwrap();
@@ -0,0 +1,27 @@
+//! Benchmarks for the simple event pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	do_something {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller), 42)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_do_something::<TestRuntime>());
+		});
+	}
+}
@@ -6,11 +6,19 @@
 use frame_support::{decl_event, decl_module, dispatch::DispatchResult};
 use system::ensure_signed;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_module! {
@@ -18,7 +26,7 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// A simple call that does little more than emit an event
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::do_something()]
 		fn do_something(origin, input: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 
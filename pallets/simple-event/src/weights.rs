@@ -0,0 +1,25 @@
+//! Weights for the simple event pallet, one for each benchmark in `benchmarking.rs`
+
+use frame_support::weights::Weight;
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the simple event pallet
+pub trait WeightInfo {
+	fn do_something() -> Weight;
+}
+
+/// Weights for the simple event pallet. Its calls do not touch storage, so the runtime's database
+/// weights do not matter.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn do_something() -> Weight {
+		15_000_000 as Weight
+	}
+}
+
+// For tests
+impl WeightInfo for () {
+	fn do_something() -> Weight {
+		15_000_000 as Weight
+	}
+}
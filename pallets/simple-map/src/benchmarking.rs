@@ -0,0 +1,55 @@
+//! Benchmarks for the simple map pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	set_single_entry {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller.clone()), 42)
+	verify {
+		assert_eq!(Module::<T>::simple_map(&caller), 42);
+	}
+
+	get_single_entry {
+		let caller: T::AccountId = account("caller", 0, 0);
+		let account: T::AccountId = account("account", 0, 0);
+		SimpleMap::<T>::insert(&account, 42);
+	}: _(RawOrigin::Signed(caller), account)
+
+	take_single_entry {
+		let caller: T::AccountId = account("caller", 0, 0);
+		SimpleMap::<T>::insert(&caller, 42);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!SimpleMap::<T>::contains_key(&caller));
+	}
+
+	increase_single_entry {
+		let caller: T::AccountId = account("caller", 0, 0);
+		SimpleMap::<T>::insert(&caller, 42);
+	}: _(RawOrigin::Signed(caller.clone()), 8)
+	verify {
+		assert_eq!(Module::<T>::simple_map(&caller), 50);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_set_single_entry::<TestRuntime>());
+			assert_ok!(test_benchmark_get_single_entry::<TestRuntime>());
+			assert_ok!(test_benchmark_take_single_entry::<TestRuntime>());
+			assert_ok!(test_benchmark_increase_single_entry::<TestRuntime>());
+		});
+	}
+}
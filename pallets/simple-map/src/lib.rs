@@ -10,11 +10,19 @@ use frame_support::{
 };
 use frame_system::{self as system, ensure_signed};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -63,7 +71,7 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// Set the value stored at a particular key
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::set_single_entry()]
 		fn set_single_entry(origin, entry: u32) -> DispatchResult {
 			// A user can only set their own entry
 			let user = ensure_signed(origin)?;
@@ -75,7 +83,7 @@ decl_module! {
 		}
 
 		/// Read the value stored at a particular key and emit it in an event
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::get_single_entry()]
 		fn get_single_entry(origin, account: T::AccountId) -> DispatchResult {
 			// Any user can get any other user's entry
 			let getter = ensure_signed(origin)?;
@@ -88,7 +96,7 @@ decl_module! {
 
 		/// Read the value stored at a particular key,while removing it from the map.
 		/// Also emit the read value in an event
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::take_single_entry()]
 		fn take_single_entry(origin) -> DispatchResult {
 			// A user can only take (delete) their own entry
 			let user = ensure_signed(origin)?;
@@ -100,7 +108,7 @@ decl_module! {
 		}
 
 		/// Increase the value associated with a particular key
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::increase_single_entry()]
 		fn increase_single_entry(origin, add_this_val: u32) -> DispatchResult {
 			// A user can only mutate their own entry
 			let user = ensure_signed(origin)?;
@@ -0,0 +1,53 @@
+//! Weights for the simple map pallet, one for each benchmark in `benchmarking.rs`
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the simple map pallet
+pub trait WeightInfo {
+	fn set_single_entry() -> Weight;
+	fn get_single_entry() -> Weight;
+	fn take_single_entry() -> Weight;
+	fn increase_single_entry() -> Weight;
+}
+
+/// Weights for the simple map pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn set_single_entry() -> Weight {
+		(15_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn get_single_entry() -> Weight {
+		(15_000_000 as Weight).saturating_add(T::DbWeight::get().reads(2))
+	}
+	fn take_single_entry() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn increase_single_entry() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn set_single_entry() -> Weight {
+		(15_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn get_single_entry() -> Weight {
+		(15_000_000 as Weight).saturating_add(RocksDbWeight::get().reads(2))
+	}
+	fn take_single_entry() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn increase_single_entry() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
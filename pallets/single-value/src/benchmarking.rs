@@ -0,0 +1,38 @@
+//! Benchmarks for the single value pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	set_value {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller), 42)
+	verify {
+		assert_eq!(Module::<T>::stored_value(), 42);
+	}
+
+	set_account {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Module::<T>::stored_account(), caller);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_set_value::<TestRuntime>());
+			assert_ok!(test_benchmark_set_account::<TestRuntime>());
+		});
+	}
+}
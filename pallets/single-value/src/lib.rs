@@ -4,10 +4,18 @@
 use frame_support::{decl_module, decl_storage, dispatch::DispatchResult};
 use frame_system::{self as system, ensure_signed};
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
 
-pub trait Trait: system::Trait {}
+pub use weights::WeightInfo;
+
+pub trait Trait: system::Trait {
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
 
 decl_storage! {
 	trait Store for Module<T: Trait> as SingleValue {
@@ -20,7 +28,7 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 
 		/// Set the storaged u32 value
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::set_value()]
 		fn set_value(origin, value: u32) -> DispatchResult {
 			ensure_signed(origin)?;
 
@@ -32,7 +40,7 @@ decl_module! {
 
 		/// Set the stored Account Id. The syntax is slightly more complex than it was for the
 		/// stored u32 because the `AccountId` type comes from the pallet's configuration trait.
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::set_account()]
 		fn set_account(origin) -> DispatchResult {
 			let who = ensure_signed(origin)?;
 
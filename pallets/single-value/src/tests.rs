@@ -51,7 +51,9 @@ impl system::Trait for TestRuntime {
 	type OnKilledAccount = ();
 }
 
-impl Trait for TestRuntime {}
+impl Trait for TestRuntime {
+	type WeightInfo = ();
+}
 
 pub type SingleValue = Module<TestRuntime>;
 
@@ -0,0 +1,31 @@
+//! Weights for the single value pallet, one for each benchmark in `benchmarking.rs`
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the single value pallet
+pub trait WeightInfo {
+	fn set_value() -> Weight;
+	fn set_account() -> Weight;
+}
+
+/// Weights for the single value pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn set_value() -> Weight {
+		(10_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn set_account() -> Weight {
+		(10_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn set_value() -> Weight {
+		(10_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn set_account() -> Weight {
+		(10_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
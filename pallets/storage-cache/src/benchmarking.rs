@@ -0,0 +1,91 @@
+//! Benchmarks for the storage cache pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+use sp_std::vec;
+
+/// Makes the caller the only group member and crowns an account that is not a member
+fn setup_swap<T: Trait>() -> T::AccountId {
+	let caller: T::AccountId = account("caller", 0, 0);
+	let king: T::AccountId = account("king", 0, 0);
+	GroupMembers::<T>::put(vec![caller.clone()]);
+	KingMember::<T>::put(king);
+	caller
+}
+
+benchmarks! {
+	_ { }
+
+	increase_value_no_cache {
+		let caller: T::AccountId = account("caller", 0, 0);
+		SomeCopyValue::put(1);
+	}: _(RawOrigin::Signed(caller), 40)
+	verify {
+		assert_eq!(Module::<T>::some_copy_value(), 42);
+	}
+
+	increase_value_w_copy {
+		let caller: T::AccountId = account("caller", 0, 0);
+		SomeCopyValue::put(1);
+	}: _(RawOrigin::Signed(caller), 40)
+	verify {
+		assert_eq!(Module::<T>::some_copy_value(), 42);
+	}
+
+	swap_king_no_cache {
+		let caller = setup_swap::<T>();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Module::<T>::king_member(), caller);
+	}
+
+	swap_king_with_cache {
+		let caller = setup_swap::<T>();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Module::<T>::king_member(), caller);
+	}
+
+	set_copy {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller), 42)
+	verify {
+		assert_eq!(Module::<T>::some_copy_value(), 42);
+	}
+
+	set_king {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Module::<T>::king_member(), caller);
+	}
+
+	mock_add_member {
+		let caller: T::AccountId = account("caller", 0, 0);
+		GroupMembers::<T>::kill();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Module::<T>::is_member(&caller));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_increase_value_no_cache::<TestRuntime>());
+			assert_ok!(test_benchmark_increase_value_w_copy::<TestRuntime>());
+			assert_ok!(test_benchmark_swap_king_no_cache::<TestRuntime>());
+			assert_ok!(test_benchmark_swap_king_with_cache::<TestRuntime>());
+			assert_ok!(test_benchmark_set_copy::<TestRuntime>());
+			assert_ok!(test_benchmark_set_king::<TestRuntime>());
+			assert_ok!(test_benchmark_mock_add_member::<TestRuntime>());
+		});
+	}
+}
@@ -7,11 +7,19 @@ use frame_support::{decl_event, decl_module, decl_storage, dispatch::DispatchRes
 use frame_system::{self as system, ensure_signed};
 use sp_std::prelude::*;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -49,7 +57,7 @@ decl_module! {
 		///  (Copy) inefficient way of updating value in storage
 		///
 		/// storage value -> storage_value * 2 + input_val
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::increase_value_no_cache()]
 		fn increase_value_no_cache(origin, some_val: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 			let original_call = <SomeCopyValue>::get();
@@ -67,7 +75,7 @@ decl_module! {
 		/// (Copy) more efficient value change
 		///
 		/// storage value -> storage_value * 2 + input_val
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::increase_value_w_copy()]
 		fn increase_value_w_copy(origin, some_val: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 			let original_call = <SomeCopyValue>::get();
@@ -84,7 +92,7 @@ decl_module! {
 		/// swaps the king account with Origin::signed() if
 		/// (1) other account is member &&
 		/// (2) existing king isn't
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::swap_king_no_cache()]
 		fn swap_king_no_cache(origin) -> DispatchResult {
 			let new_king = ensure_signed(origin)?;
 			let existing_king = <KingMember<T>>::get();
@@ -108,7 +116,7 @@ decl_module! {
 		/// swaps the king account with Origin::signed() if
 		/// (1) other account is member &&
 		/// (2) existing king isn't
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::swap_king_with_cache()]
 		fn swap_king_with_cache(origin) -> DispatchResult {
 			let new_king = ensure_signed(origin)?;
 			let existing_king = <KingMember<T>>::get();
@@ -130,21 +138,21 @@ decl_module! {
 		}
 
 		// ---- for testing purposes ----
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::set_copy()]
 		fn set_copy(origin, val: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
 			<SomeCopyValue>::put(val);
 			Ok(())
 		}
 
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::set_king()]
 		fn set_king(origin) -> DispatchResult {
 			let user = ensure_signed(origin)?;
 			<KingMember<T>>::put(user);
 			Ok(())
 		}
 
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::mock_add_member()]
 		fn mock_add_member(origin) -> DispatchResult {
 			let added = ensure_signed(origin)?;
 			ensure!(!Self::is_member(&added), "member already in group");
@@ -0,0 +1,89 @@
+//! Weights for the storage cache pallet, one for each benchmark in `benchmarking.rs`
+//!
+//! The calls that cache values they have already read are cheaper than their `no_cache`
+//! counterparts by exactly the extra storage read.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the storage cache pallet
+pub trait WeightInfo {
+	fn increase_value_no_cache() -> Weight;
+	fn increase_value_w_copy() -> Weight;
+	fn swap_king_no_cache() -> Weight;
+	fn swap_king_with_cache() -> Weight;
+	fn set_copy() -> Weight;
+	fn set_king() -> Weight;
+	fn mock_add_member() -> Weight;
+}
+
+/// Weights for the storage cache pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn increase_value_no_cache() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn increase_value_w_copy() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn swap_king_no_cache() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn swap_king_with_cache() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn set_copy() -> Weight {
+		(10_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn set_king() -> Weight {
+		(10_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn mock_add_member() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn increase_value_no_cache() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn increase_value_w_copy() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn swap_king_no_cache() -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn swap_king_with_cache() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn set_copy() -> Weight {
+		(10_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn set_king() -> Weight {
+		(10_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn mock_add_member() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+}
@@ -0,0 +1,96 @@
+//! Benchmarks for the struct storage pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+/// Removes inner thing 1 and super thing 2
+fn reset<T: Trait>() {
+	InnerThingsByNumbers::<T>::remove(1);
+	SuperThingsBySuperNumbers::remove(2);
+}
+
+/// Stores inner thing 1, replacing any inner or super things left by an earlier run
+fn setup_inner<T: Trait>() -> Result<T::AccountId, &'static str> {
+	let caller: T::AccountId = account("caller", 0, 0);
+	reset::<T>();
+	Module::<T>::insert_inner_thing(
+		RawOrigin::Signed(caller.clone()).into(),
+		1,
+		Default::default(),
+		100u32.into(),
+	)?;
+	Ok(caller)
+}
+
+benchmarks! {
+	_ { }
+
+	insert_inner_thing {
+		let caller: T::AccountId = account("caller", 0, 0);
+		reset::<T>();
+	}: _(RawOrigin::Signed(caller), 1, Default::default(), 100u32.into())
+	verify {
+		assert!(InnerThingsByNumbers::<T>::contains_key(1));
+	}
+
+	update_inner_thing {
+		let caller = setup_inner::<T>()?;
+	}: _(RawOrigin::Signed(caller), 1, Default::default(), 200u32.into())
+	verify {
+		assert_eq!(Module::<T>::inner_things_by_numbers(1).balance, 200u32.into());
+	}
+
+	remove_inner_thing {
+		let caller = setup_inner::<T>()?;
+	}: _(RawOrigin::Signed(caller), 1)
+	verify {
+		assert!(!InnerThingsByNumbers::<T>::contains_key(1));
+	}
+
+	insert_super_thing_with_existing_inner {
+		let caller = setup_inner::<T>()?;
+	}: _(RawOrigin::Signed(caller), 1, 2)
+	verify {
+		assert_eq!(Module::<T>::inner_things_by_numbers(1).references, 1);
+	}
+
+	insert_super_thing_with_new_inner {
+		let caller: T::AccountId = account("caller", 0, 0);
+		reset::<T>();
+	}: _(RawOrigin::Signed(caller), 1, Default::default(), 100u32.into(), 2)
+	verify {
+		assert!(Module::<T>::super_thing_with_inner(2).is_some());
+	}
+
+	remove_super_thing {
+		let caller = setup_inner::<T>()?;
+		Module::<T>::insert_super_thing_with_existing_inner(
+			RawOrigin::Signed(caller.clone()).into(),
+			1,
+			2,
+		)?;
+	}: _(RawOrigin::Signed(caller), 2)
+	verify {
+		assert_eq!(Module::<T>::inner_things_by_numbers(1).references, 0);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_insert_inner_thing::<TestRuntime>());
+			assert_ok!(test_benchmark_update_inner_thing::<TestRuntime>());
+			assert_ok!(test_benchmark_remove_inner_thing::<TestRuntime>());
+			assert_ok!(test_benchmark_insert_super_thing_with_existing_inner::<TestRuntime>());
+			assert_ok!(test_benchmark_insert_super_thing_with_new_inner::<TestRuntime>());
+			assert_ok!(test_benchmark_remove_super_thing::<TestRuntime>());
+		});
+	}
+}
@@ -3,35 +3,81 @@
 //! Struct Storage
 //! This pallet demonstrates how to declare and store `strcuts` that contain types
 //! that come from the pallet's configuration trait.
+//!
+//! Changing the layout of a stored struct makes the entries already in storage undecodable, so
+//! the pallet records a `StorageVersion` and migrates old entries in `on_runtime_upgrade`. See the
+//! `migrations` module.
+//!
+//! A `SuperThing` refers to its `InnerThing` by number rather than holding a copy, so updates to
+//! the inner thing are seen through every super thing that refers to it. Each inner thing counts
+//! the super things that refer to it, and cannot be removed while that count is above zero.
 
 use frame_support::{
 	codec::{Decode, Encode},
-	decl_event, decl_module, decl_storage,
+	decl_error, decl_event, decl_module, decl_storage,
 	dispatch::DispatchResult,
+	ensure,
+	weights::Weight,
 };
 use frame_system::{self as system, ensure_signed};
 use sp_runtime::RuntimeDebug;
 
+pub mod migrations;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 pub trait Trait: balances::Trait + system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
+
+/// The layouts the pallet's storage has had. Each new layout needs a migration.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	/// The original layout, which had no `StorageVersion` item
+	V1,
+	/// `InnerThing` records the block in which it was written
+	V2,
+	/// `SuperThing` refers to its `InnerThing` by number, and `InnerThing` counts its references
+	V3,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		// Chains that stored things before the version was introduced have no version stored
+		Releases::V1
+	}
 }
 
 #[derive(Encode, Decode, Clone, Default, RuntimeDebug)]
-pub struct InnerThing<Hash, Balance> {
+pub struct InnerThing<Hash, Balance, BlockNumber> {
 	number: u32,
 	hash: Hash,
 	balance: Balance,
+	updated_at: BlockNumber,
+	/// The number of `SuperThing`s that refer to this inner thing
+	references: u32,
 }
 
-type InnerThingOf<T> = InnerThing<<T as system::Trait>::Hash, <T as balances::Trait>::Balance>;
+type InnerThingOf<T> = InnerThing<
+	<T as system::Trait>::Hash,
+	<T as balances::Trait>::Balance,
+	<T as system::Trait>::BlockNumber,
+>;
 
-#[derive(Encode, Decode, Default, RuntimeDebug)]
-pub struct SuperThing<Hash, Balance> {
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct SuperThing {
 	super_number: u32,
-	inner_thing: InnerThing<Hash, Balance>,
+	/// The number of the `InnerThing` in `InnerThingsByNumbers`
+	inner_number: u32,
 }
 
 decl_storage! {
@@ -39,7 +85,9 @@ decl_storage! {
 		InnerThingsByNumbers get(fn inner_things_by_numbers):
 			map hasher(blake2_128_concat) u32 => InnerThingOf<T>;
 		SuperThingsBySuperNumbers get(fn super_things_by_super_numbers):
-			map hasher(blake2_128_concat) u32 => SuperThing<T::Hash, T::Balance>;
+			map hasher(blake2_128_concat) u32 => SuperThing;
+		/// The layout of the items above. New chains start with the latest layout.
+		StorageVersion get(fn storage_version) build(|_| Releases::V3): Releases;
 	}
 }
 
@@ -55,63 +103,182 @@ decl_event! (
 		NewSuperThingByExistingInner(u32, u32, Hash, Balance),
 		// ""
 		NewSuperThingByNewInner(u32, u32, Hash, Balance),
+		// new fields of the updated inner thing
+		InnerThingUpdated(u32, Hash, Balance),
+		// number of the removed inner thing
+		InnerThingRemoved(u32),
+		// super_number of the removed super thing
+		SuperThingRemoved(u32),
 	}
 );
 
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// An inner thing with this number already exists
+		InnerThingExists,
+		/// No inner thing with this number exists
+		UnknownInnerThing,
+		/// The inner thing is referred to by a super thing, so it can't be removed
+		InnerThingInUse,
+		/// A super thing with this number already exists
+		SuperThingExists,
+		/// No super thing with this number exists
+		UnknownSuperThing,
+	}
+}
+
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 		fn deposit_event() = default;
 
+		type Error = Error<T>;
+
+		/// Migrates the stored things to the latest layout
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate_to_v2::<T>().saturating_add(migrations::migrate_to_v3::<T>())
+		}
+
 		/// Stores an `InnerThing` struct in the storage map
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::insert_inner_thing()]
 		fn insert_inner_thing(origin, number: u32, hash: T::Hash, balance: T::Balance) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
+			ensure!(
+				!<InnerThingsByNumbers<T>>::contains_key(number),
+				Error::<T>::InnerThingExists
+			);
 			let thing = InnerThing {
 							number,
 							hash,
 							balance,
+							updated_at: <system::Module<T>>::block_number(),
+							references: 0,
 						};
 			<InnerThingsByNumbers<T>>::insert(number, thing);
 			Self::deposit_event(RawEvent::NewInnerThing(number, hash, balance));
 			Ok(())
 		}
 
+		/// Changes the fields of a stored `InnerThing`. Every `SuperThing` that refers to it sees
+		/// the new fields.
+		#[weight = T::WeightInfo::update_inner_thing()]
+		fn update_inner_thing(
+			origin,
+			number: u32,
+			hash: T::Hash,
+			balance: T::Balance,
+		) -> DispatchResult {
+			let _ = ensure_signed(origin)?;
+			ensure!(
+				<InnerThingsByNumbers<T>>::contains_key(number),
+				Error::<T>::UnknownInnerThing
+			);
+			<InnerThingsByNumbers<T>>::mutate(number, |thing| {
+				thing.hash = hash;
+				thing.balance = balance;
+				thing.updated_at = <system::Module<T>>::block_number();
+			});
+			Self::deposit_event(RawEvent::InnerThingUpdated(number, hash, balance));
+			Ok(())
+		}
+
+		/// Removes an `InnerThing` that no `SuperThing` refers to
+		#[weight = T::WeightInfo::remove_inner_thing()]
+		fn remove_inner_thing(origin, number: u32) -> DispatchResult {
+			let _ = ensure_signed(origin)?;
+			ensure!(
+				<InnerThingsByNumbers<T>>::contains_key(number),
+				Error::<T>::UnknownInnerThing
+			);
+			let inner_thing = Self::inner_things_by_numbers(number);
+			ensure!(inner_thing.references == 0, Error::<T>::InnerThingInUse);
+			<InnerThingsByNumbers<T>>::remove(number);
+			Self::deposit_event(RawEvent::InnerThingRemoved(number));
+			Ok(())
+		}
+
 		/// Stores a `SuperThing` struct in the storage map using an `InnerThing` that was already
 		/// stored
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::insert_super_thing_with_existing_inner()]
 		fn insert_super_thing_with_existing_inner(origin, inner_number: u32, super_number: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
-			let inner_thing = Self::inner_things_by_numbers(inner_number);
+			ensure!(
+				<InnerThingsByNumbers<T>>::contains_key(inner_number),
+				Error::<T>::UnknownInnerThing
+			);
+			ensure!(
+				!<SuperThingsBySuperNumbers>::contains_key(super_number),
+				Error::<T>::SuperThingExists
+			);
+			let mut inner_thing = Self::inner_things_by_numbers(inner_number);
+			inner_thing.references += 1;
+			<InnerThingsByNumbers<T>>::insert(inner_number, inner_thing.clone());
 			let super_thing = SuperThing {
 				super_number,
-				inner_thing: inner_thing.clone(),
+				inner_number,
 			};
-			<SuperThingsBySuperNumbers<T>>::insert(super_number, super_thing);
+			<SuperThingsBySuperNumbers>::insert(super_number, super_thing);
 			Self::deposit_event(RawEvent::NewSuperThingByExistingInner(super_number, inner_thing.number, inner_thing.hash, inner_thing.balance));
 			Ok(())
 		}
 
 		/// Stores a `SuperThing` struct in the storage map using a new `InnerThing`
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::insert_super_thing_with_new_inner()]
 		fn insert_super_thing_with_new_inner(origin, inner_number: u32, hash: T::Hash, balance: T::Balance, super_number: u32) -> DispatchResult {
 			let _ = ensure_signed(origin)?;
+			ensure!(
+				!<InnerThingsByNumbers<T>>::contains_key(inner_number),
+				Error::<T>::InnerThingExists
+			);
+			ensure!(
+				!<SuperThingsBySuperNumbers>::contains_key(super_number),
+				Error::<T>::SuperThingExists
+			);
 			// construct and insert `inner_thing` first
 			let inner_thing = InnerThing {
 				number: inner_number,
 				hash,
 				balance,
+				updated_at: <system::Module<T>>::block_number(),
+				references: 1,
 			};
-			// overwrites any existing `InnerThing` with `number: inner_number` by default
-			<InnerThingsByNumbers<T>>::insert(inner_number, inner_thing.clone());
+			<InnerThingsByNumbers<T>>::insert(inner_number, inner_thing);
 			Self::deposit_event(RawEvent::NewInnerThing(inner_number, hash, balance));
 			// now construct and insert `super_thing`
 			let super_thing = SuperThing {
 				super_number,
-				inner_thing,
+				inner_number,
 			};
-			<SuperThingsBySuperNumbers<T>>::insert(super_number, super_thing);
+			<SuperThingsBySuperNumbers>::insert(super_number, super_thing);
 			Self::deposit_event(RawEvent::NewSuperThingByNewInner(super_number, inner_number, hash, balance));
 			Ok(())
 		}
+
+		/// Removes a `SuperThing`, releasing its reference to its `InnerThing`
+		#[weight = T::WeightInfo::remove_super_thing()]
+		fn remove_super_thing(origin, super_number: u32) -> DispatchResult {
+			let _ = ensure_signed(origin)?;
+			ensure!(
+				<SuperThingsBySuperNumbers>::contains_key(super_number),
+				Error::<T>::UnknownSuperThing
+			);
+			let super_thing = <SuperThingsBySuperNumbers>::take(super_number);
+			<InnerThingsByNumbers<T>>::mutate(super_thing.inner_number, |thing| {
+				thing.references = thing.references.saturating_sub(1);
+			});
+			Self::deposit_event(RawEvent::SuperThingRemoved(super_number));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Reads a `SuperThing` along with the current state of the `InnerThing` it refers to
+	pub fn super_thing_with_inner(super_number: u32) -> Option<(SuperThing, InnerThingOf<T>)> {
+		if !<SuperThingsBySuperNumbers>::contains_key(super_number) {
+			return None;
+		}
+		let super_thing = Self::super_things_by_super_numbers(super_number);
+		let inner_thing = Self::inner_things_by_numbers(super_thing.inner_number);
+		Some((super_thing, inner_thing))
 	}
 }
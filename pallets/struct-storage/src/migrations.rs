@@ -0,0 +1,314 @@
+//! Storage migrations for the struct storage pallet
+//!
+//! Each migration checks the `StorageVersion` first, so it only ever runs once, however many
+//! runtime upgrades follow it. Migrations run in order, so a chain that is several layouts behind
+//! goes through each of them.
+//!
+//! The layouts of past versions are kept here, because the structs in the pallet only describe
+//! the latest one.
+//!
+//! The `pre_migrate` and `post_migrate` checks are meant to run around the migrations on a copy of
+//! live chain state, in the style of `try-runtime`, and are only compiled with the `try-runtime`
+//! feature.
+
+use super::*;
+use frame_support::storage::{unhashed, StorageMap, StoragePrefixedMap, StorageValue};
+use sp_runtime::traits::Zero;
+use sp_std::collections::btree_map::BTreeMap;
+
+#[cfg(any(feature = "try-runtime", test))]
+use frame_support::storage::IterableStorageMap;
+#[cfg(any(feature = "try-runtime", test))]
+use sp_std::prelude::*;
+
+/// The layout of `InnerThing` before `Releases::V2`
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug)]
+pub struct InnerThingV1<Hash, Balance> {
+	pub number: u32,
+	pub hash: Hash,
+	pub balance: Balance,
+}
+
+/// The layout of `SuperThing` before `Releases::V2`
+#[derive(Encode, Decode, Default, RuntimeDebug)]
+pub struct SuperThingV1<Hash, Balance> {
+	pub super_number: u32,
+	pub inner_thing: InnerThingV1<Hash, Balance>,
+}
+
+/// The layout of `InnerThing` in `Releases::V2`
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug)]
+pub struct InnerThingV2<Hash, Balance, BlockNumber> {
+	pub number: u32,
+	pub hash: Hash,
+	pub balance: Balance,
+	pub updated_at: BlockNumber,
+}
+
+/// The layout of `SuperThing` in `Releases::V2`, which held a copy of its `InnerThing`
+#[derive(Encode, Decode, Default, RuntimeDebug)]
+pub struct SuperThingV2<Hash, Balance, BlockNumber> {
+	pub super_number: u32,
+	pub inner_thing: InnerThingV2<Hash, Balance, BlockNumber>,
+}
+
+type InnerThingV1Of<T> = InnerThingV1<<T as system::Trait>::Hash, <T as balances::Trait>::Balance>;
+type SuperThingV1Of<T> = SuperThingV1<<T as system::Trait>::Hash, <T as balances::Trait>::Balance>;
+type InnerThingV2Of<T> = InnerThingV2<
+	<T as system::Trait>::Hash,
+	<T as balances::Trait>::Balance,
+	<T as system::Trait>::BlockNumber,
+>;
+type SuperThingV2Of<T> = SuperThingV2<
+	<T as system::Trait>::Hash,
+	<T as balances::Trait>::Balance,
+	<T as system::Trait>::BlockNumber,
+>;
+
+impl<Hash, Balance> InnerThingV1<Hash, Balance> {
+	/// Converts to the V2 layout. Things written before V2 did not record when they were written,
+	/// so they get the given block instead.
+	fn upgrade<BlockNumber>(
+		self,
+		updated_at: BlockNumber,
+	) -> InnerThingV2<Hash, Balance, BlockNumber> {
+		InnerThingV2 {
+			number: self.number,
+			hash: self.hash,
+			balance: self.balance,
+			updated_at,
+		}
+	}
+}
+
+impl<Hash, Balance, BlockNumber> InnerThingV2<Hash, Balance, BlockNumber> {
+	/// Converts to the V3 layout, with the given number of references
+	fn upgrade(self, references: u32) -> InnerThing<Hash, Balance, BlockNumber> {
+		InnerThing {
+			number: self.number,
+			hash: self.hash,
+			balance: self.balance,
+			updated_at: self.updated_at,
+			references,
+		}
+	}
+}
+
+/// Adds the `updated_at` field to every `InnerThing`, both in `InnerThingsByNumbers` and inside
+/// the `SuperThing`s in `SuperThingsBySuperNumbers`. Migrated things record block zero.
+pub fn migrate_to_v2<T: Trait>() -> Weight {
+	let db = T::DbWeight::get();
+	if StorageVersion::get() != Releases::V1 {
+		return db.reads(1);
+	}
+
+	let mut translated = translate_values(
+		&InnerThingsByNumbers::<T>::final_prefix(),
+		|old: InnerThingV1Of<T>| old.upgrade::<T::BlockNumber>(Zero::zero()),
+	);
+	translated += translate_values(
+		&SuperThingsBySuperNumbers::final_prefix(),
+		|old: SuperThingV1Of<T>| SuperThingV2 {
+			super_number: old.super_number,
+			inner_thing: old.inner_thing.upgrade::<T::BlockNumber>(Zero::zero()),
+		},
+	);
+	StorageVersion::put(Releases::V2);
+
+	db.reads_writes(translated + 1, translated + 1)
+}
+
+/// Replaces the copy of the `InnerThing` in each `SuperThing` with its number, and counts the
+/// references to each `InnerThing`
+///
+/// In V2 a super thing could hold a copy of an inner thing that was later overwritten, or that
+/// was never stored at all. The super thing keeps referring to the same number, so it sees the
+/// stored inner thing from now on. If nothing is stored under that number, the copy is stored.
+pub fn migrate_to_v3<T: Trait>() -> Weight {
+	let db = T::DbWeight::get();
+	if StorageVersion::get() != Releases::V2 {
+		return db.reads(1);
+	}
+
+	let mut references = BTreeMap::<u32, u32>::new();
+	let mut missing = BTreeMap::<u32, InnerThingV2Of<T>>::new();
+	let mut translated = translate_values(
+		&SuperThingsBySuperNumbers::final_prefix(),
+		|old: SuperThingV2Of<T>| {
+			let inner_number = old.inner_thing.number;
+			*references.entry(inner_number).or_default() += 1;
+			if !InnerThingsByNumbers::<T>::contains_key(inner_number) {
+				missing.entry(inner_number).or_insert(old.inner_thing);
+			}
+			SuperThing {
+				super_number: old.super_number,
+				inner_number,
+			}
+		},
+	);
+	translated += translate_values(
+		&InnerThingsByNumbers::<T>::final_prefix(),
+		|old: InnerThingV2Of<T>| {
+			let count = references.get(&old.number).copied().unwrap_or_default();
+			old.upgrade(count)
+		},
+	);
+	for (number, copy) in missing {
+		let count = references.get(&number).copied().unwrap_or_default();
+		InnerThingsByNumbers::<T>::insert(number, copy.upgrade(count));
+		translated += 1;
+	}
+	StorageVersion::put(Releases::V3);
+
+	db.reads_writes(translated + 1, translated + 1)
+}
+
+/// Decodes every value under `prefix` as an `O` and replaces it with `f`'s result. Returns the
+/// number of values translated.
+///
+/// Values are read and written as raw bytes, because the storage items only know the latest
+/// layout. Values that do not decode as an `O` are left alone.
+fn translate_values<O: Decode, V: Encode>(prefix: &[u8], mut f: impl FnMut(O) -> V) -> u64 {
+	let mut translated = 0;
+	let mut key = prefix.to_vec();
+	while let Some(next) = sp_io::storage::next_key(&key) {
+		if !next.starts_with(prefix) {
+			break;
+		}
+		if let Some(old) = unhashed::get::<O>(&next) {
+			unhashed::put(&next, &f(old));
+			translated += 1;
+		}
+		key = next;
+	}
+	translated
+}
+
+/// Checks that every stored thing can be decoded in the layout of the stored version. Returns the
+/// number of inner things and super things there should be after migrating to the latest layout,
+/// for `post_migrate` to compare against.
+#[cfg(any(feature = "try-runtime", test))]
+pub fn pre_migrate<T: Trait>() -> Result<(u32, u32), &'static str> {
+	let inner_prefix = InnerThingsByNumbers::<T>::final_prefix();
+	let super_prefix = SuperThingsBySuperNumbers::final_prefix();
+	let inner_things = count_keys(&inner_prefix);
+	let super_things = count_keys(&super_prefix);
+
+	// The numbers of the inner things the super things hold copies of
+	let mut copied = Vec::new();
+	match StorageVersion::get() {
+		Releases::V1 => {
+			ensure!(
+				decode_all::<InnerThingV1Of<T>>(&inner_prefix).len() as u32 == inner_things,
+				"an inner thing is not in the V1 layout"
+			);
+			let supers = decode_all::<SuperThingV1Of<T>>(&super_prefix);
+			ensure!(supers.len() as u32 == super_things, "a super thing is not in the V1 layout");
+			copied.extend(supers.into_iter().map(|s| s.inner_thing.number));
+		}
+		Releases::V2 => {
+			ensure!(
+				decode_all::<InnerThingV2Of<T>>(&inner_prefix).len() as u32 == inner_things,
+				"an inner thing is not in the V2 layout"
+			);
+			let supers = decode_all::<SuperThingV2Of<T>>(&super_prefix);
+			ensure!(supers.len() as u32 == super_things, "a super thing is not in the V2 layout");
+			copied.extend(supers.into_iter().map(|s| s.inner_thing.number));
+		}
+		Releases::V3 => return Err("already migrated to V3"),
+	}
+
+	// Copies of inner things that are not stored will be stored by the migration
+	copied.sort();
+	copied.dedup();
+	let restored = copied
+		.into_iter()
+		.filter(|number| !InnerThingsByNumbers::<T>::contains_key(number))
+		.count() as u32;
+
+	Ok((inner_things + restored, super_things))
+}
+
+/// Checks that no thing was lost by the migrations, that every stored thing can be decoded in the
+/// latest layout, and that the references between them are consistent
+#[cfg(any(feature = "try-runtime", test))]
+pub fn post_migrate<T: Trait>(
+	(inner_things, super_things): (u32, u32),
+) -> Result<(), &'static str> {
+	ensure!(StorageVersion::get() == Releases::V3, "storage version was not updated");
+
+	let prefix = InnerThingsByNumbers::<T>::final_prefix();
+	ensure!(count_keys(&prefix) == inner_things, "inner things were lost");
+	ensure!(
+		decode_all::<InnerThingOf<T>>(&prefix).len() as u32 == inner_things,
+		"an inner thing is not in the V3 layout"
+	);
+
+	let prefix = SuperThingsBySuperNumbers::final_prefix();
+	ensure!(count_keys(&prefix) == super_things, "super things were lost");
+	ensure!(
+		decode_all::<SuperThing>(&prefix).len() as u32 == super_things,
+		"a super thing is not in the V3 layout"
+	);
+
+	check_references::<T>()
+}
+
+/// Checks that every super thing refers to a stored inner thing, and that every inner thing
+/// counts the super things that refer to it
+#[cfg(any(feature = "try-runtime", test))]
+pub fn check_references<T: Trait>() -> Result<(), &'static str> {
+	let mut references = BTreeMap::<u32, u32>::new();
+	for (_, super_thing) in SuperThingsBySuperNumbers::iter() {
+		ensure!(
+			InnerThingsByNumbers::<T>::contains_key(super_thing.inner_number),
+			"a super thing refers to a missing inner thing"
+		);
+		*references.entry(super_thing.inner_number).or_default() += 1;
+	}
+	for (number, inner_thing) in InnerThingsByNumbers::<T>::iter() {
+		ensure!(
+			inner_thing.references == references.get(&number).copied().unwrap_or_default(),
+			"an inner thing has the wrong number of references"
+		);
+	}
+	Ok(())
+}
+
+/// The number of keys in storage that start with `prefix`
+#[cfg(any(feature = "try-runtime", test))]
+fn count_keys(prefix: &[u8]) -> u32 {
+	let mut count = 0;
+	let mut key = prefix.to_vec();
+	while let Some(next) = sp_io::storage::next_key(&key) {
+		if !next.starts_with(prefix) {
+			break;
+		}
+		count += 1;
+		key = next;
+	}
+	count
+}
+
+/// The values under `prefix` that decode as a `V`, without any bytes left over
+#[cfg(any(feature = "try-runtime", test))]
+fn decode_all<V: Decode>(prefix: &[u8]) -> Vec<V> {
+	let mut values = Vec::new();
+	let mut key = prefix.to_vec();
+	while let Some(next) = sp_io::storage::next_key(&key) {
+		if !next.starts_with(prefix) {
+			break;
+		}
+		if let Some(raw) = sp_io::storage::get(&next) {
+			let mut input = &raw[..];
+			// A value in a newer layout may decode in an older one, with bytes left over
+			if let Ok(value) = V::decode(&mut input) {
+				if input.is_empty() {
+					values.push(value);
+				}
+			}
+		}
+		key = next;
+	}
+	values
+}
@@ -1,5 +1,10 @@
+use crate::migrations::{self, InnerThingV1, InnerThingV2, SuperThingV1, SuperThingV2};
 use crate::*;
-use frame_support::{assert_ok, impl_outer_event, impl_outer_origin, parameter_types};
+use frame_support::{
+	assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types,
+	storage::{StorageMap, StorageValue},
+	traits::OnRuntimeUpgrade,
+};
 use sp_core::H256;
 use sp_io::TestExternalities;
 use sp_runtime::{
@@ -9,19 +14,17 @@ use sp_runtime::{
 };
 
 // hacky Eq implementation for testing InnerThing
-impl<Hash: Clone, Balance: Copy + AtLeast32Bit> PartialEq for InnerThing<Hash, Balance> {
+impl<Hash: Clone, Balance: Copy + AtLeast32Bit, BlockNumber> PartialEq
+	for InnerThing<Hash, Balance, BlockNumber>
+{
 	fn eq(&self, other: &Self) -> bool {
 		self.number == other.number
 	}
 }
-impl<Hash: Clone, Balance: Copy + AtLeast32Bit> Eq for InnerThing<Hash, Balance> {}
-// "" for SuperThing
-impl<Hash: Clone, Balance: Copy + AtLeast32Bit> PartialEq for SuperThing<Hash, Balance> {
-	fn eq(&self, other: &Self) -> bool {
-		self.super_number == other.super_number
-	}
+impl<Hash: Clone, Balance: Copy + AtLeast32Bit, BlockNumber> Eq
+	for InnerThing<Hash, Balance, BlockNumber>
+{
 }
-impl<Hash: Clone, Balance: Copy + AtLeast32Bit> Eq for SuperThing<Hash, Balance> {}
 
 impl_outer_origin! {
 	pub enum Origin for TestRuntime {}
@@ -89,6 +92,7 @@ impl_outer_event! {
 
 impl Trait for TestRuntime {
 	type Event = TestEvent;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
@@ -125,6 +129,8 @@ fn insert_inner_works() {
 			number: 3u32,
 			hash: data,
 			balance: 7u64,
+			updated_at: 1,
+			references: 0,
 		};
 		assert_eq!(
 			StructStorage::inner_things_by_numbers(3u32),
@@ -161,11 +167,14 @@ fn insert_super_thing_with_existing_works() {
 			number: 3u32,
 			hash: data,
 			balance: 7u64,
+			updated_at: 1,
+			references: 1,
 		};
 		assert_eq!(StructStorage::inner_things_by_numbers(3u32), expected_inner);
+		assert_eq!(StructStorage::inner_things_by_numbers(3u32).references, 1);
 		let expected_outer = SuperThing {
 			super_number: 5u32,
-			inner_thing: expected_inner.clone(),
+			inner_number: 3u32,
 		};
 		assert_eq!(
 			StructStorage::super_things_by_super_numbers(5u32),
@@ -201,11 +210,14 @@ fn insert_super_with_new_inner_works() {
 			number: 3u32,
 			hash: data,
 			balance: 7u64,
+			updated_at: 1,
+			references: 1,
 		};
 		assert_eq!(StructStorage::inner_things_by_numbers(3u32), expected_inner);
+		assert_eq!(StructStorage::inner_things_by_numbers(3u32).references, 1);
 		let expected_outer = SuperThing {
 			super_number: 5u32,
-			inner_thing: expected_inner.clone(),
+			inner_number: 3u32,
 		};
 		assert_eq!(
 			StructStorage::super_things_by_super_numbers(5u32),
@@ -223,3 +235,315 @@ fn insert_super_with_new_inner_works() {
 		assert!(System::events().iter().any(|a| a.event == expected_event2));
 	})
 }
+
+/// Writes an inner thing in the V1 layout, as a chain that stored it before V2 would have
+fn put_v1_inner_thing(number: u32, hash: H256, balance: u64) {
+	let old = InnerThingV1 {
+		number,
+		hash,
+		balance,
+	};
+	sp_io::storage::set(
+		&InnerThingsByNumbers::<TestRuntime>::hashed_key_for(number),
+		&old.encode(),
+	);
+}
+
+/// Writes a super thing in the V1 layout
+fn put_v1_super_thing(super_number: u32, inner_number: u32, hash: H256, balance: u64) {
+	let old = SuperThingV1 {
+		super_number,
+		inner_thing: InnerThingV1 {
+			number: inner_number,
+			hash,
+			balance,
+		},
+	};
+	sp_io::storage::set(
+		&SuperThingsBySuperNumbers::hashed_key_for(super_number),
+		&old.encode(),
+	);
+}
+
+#[test]
+fn new_chains_start_at_latest_version() {
+	let mut storage = system::GenesisConfig::default()
+		.build_storage::<TestRuntime>()
+		.unwrap();
+	GenesisConfig::default()
+		.assimilate_storage::<TestRuntime>(&mut storage)
+		.unwrap();
+	TestExternalities::from(storage).execute_with(|| {
+		assert_eq!(StructStorage::storage_version(), Releases::V3);
+		assert!(migrations::pre_migrate::<TestRuntime>().is_err());
+	})
+}
+
+#[test]
+fn v1_things_are_migrated() {
+	ExtBuilder::build().execute_with(|| {
+		let data = H256::from_low_u64_be(16);
+		put_v1_inner_thing(3, data, 7);
+		put_v1_inner_thing(4, data, 8);
+		put_v1_super_thing(5, 3, data, 7);
+		assert_eq!(StructStorage::storage_version(), Releases::V1);
+
+		let counts = migrations::pre_migrate::<TestRuntime>().unwrap();
+		assert_eq!(counts, (2, 1));
+		StructStorage::on_runtime_upgrade();
+		assert_eq!(migrations::post_migrate::<TestRuntime>(counts), Ok(()));
+		assert_eq!(StructStorage::storage_version(), Releases::V3);
+
+		// Every field survives, and migrated things record block zero
+		let inner = StructStorage::inner_things_by_numbers(4);
+		assert_eq!(inner.number, 4);
+		assert_eq!(inner.hash, data);
+		assert_eq!(inner.balance, 8);
+		assert_eq!(inner.updated_at, 0);
+		assert_eq!(inner.references, 0);
+
+		let (outer, inner) = StructStorage::super_thing_with_inner(5).unwrap();
+		assert_eq!(outer, SuperThing { super_number: 5, inner_number: 3 });
+		assert_eq!(inner.number, 3);
+		assert_eq!(inner.hash, data);
+		assert_eq!(inner.balance, 7);
+		assert_eq!(inner.updated_at, 0);
+		assert_eq!(inner.references, 1);
+	})
+}
+
+#[test]
+fn migration_runs_once() {
+	ExtBuilder::build().execute_with(|| {
+		let data = H256::from_low_u64_be(16);
+		put_v1_inner_thing(3, data, 7);
+		StructStorage::on_runtime_upgrade();
+
+		// Things written after the upgrade are already in the V2 layout, and a second upgrade
+		// must not translate them again
+		assert_ok!(StructStorage::insert_inner_thing(
+			Origin::signed(1),
+			4u32,
+			data,
+			8u64.into()
+		));
+		StructStorage::on_runtime_upgrade();
+		assert!(migrations::pre_migrate::<TestRuntime>().is_err());
+		assert_eq!(migrations::post_migrate::<TestRuntime>((2, 0)), Ok(()));
+
+		let inner = StructStorage::inner_things_by_numbers(4);
+		assert_eq!(inner.balance, 8);
+		assert_eq!(inner.updated_at, 1);
+	})
+}
+
+#[test]
+fn pre_migrate_rejects_unknown_layouts() {
+	ExtBuilder::build().execute_with(|| {
+		sp_io::storage::set(
+			&InnerThingsByNumbers::<TestRuntime>::hashed_key_for(3),
+			&[1, 2, 3],
+		);
+		assert!(migrations::pre_migrate::<TestRuntime>().is_err());
+	})
+}
+
+#[test]
+fn post_migrate_detects_lost_things() {
+	ExtBuilder::build().execute_with(|| {
+		let data = H256::from_low_u64_be(16);
+		put_v1_inner_thing(3, data, 7);
+		let counts = migrations::pre_migrate::<TestRuntime>().unwrap();
+		StructStorage::on_runtime_upgrade();
+
+		InnerThingsByNumbers::<TestRuntime>::remove(3);
+		assert!(migrations::post_migrate::<TestRuntime>(counts).is_err());
+		StorageVersion::put(Releases::V1);
+		assert!(migrations::post_migrate::<TestRuntime>((0, 0)).is_err());
+	})
+}
+
+/// Writes an inner thing in the V2 layout
+fn put_v2_inner_thing(number: u32, hash: H256, balance: u64) {
+	let old = InnerThingV2 {
+		number,
+		hash,
+		balance,
+		updated_at: 1u64,
+	};
+	sp_io::storage::set(
+		&InnerThingsByNumbers::<TestRuntime>::hashed_key_for(number),
+		&old.encode(),
+	);
+}
+
+/// Writes a super thing in the V2 layout, holding a copy of its inner thing
+fn put_v2_super_thing(super_number: u32, inner_number: u32, hash: H256, balance: u64) {
+	let old = SuperThingV2 {
+		super_number,
+		inner_thing: InnerThingV2 {
+			number: inner_number,
+			hash,
+			balance,
+			updated_at: 1u64,
+		},
+	};
+	sp_io::storage::set(
+		&SuperThingsBySuperNumbers::hashed_key_for(super_number),
+		&old.encode(),
+	);
+}
+
+#[test]
+fn v2_copies_become_references() {
+	ExtBuilder::build().execute_with(|| {
+		StorageVersion::put(Releases::V2);
+		let old_data = H256::from_low_u64_be(16);
+		let new_data = H256::from_low_u64_be(17);
+		// 5 and 6 hold copies of 3, which was overwritten since
+		put_v2_inner_thing(3, new_data, 8);
+		put_v2_super_thing(5, 3, old_data, 7);
+		put_v2_super_thing(6, 3, old_data, 7);
+		// 7 holds a copy of 4, which was never stored
+		put_v2_super_thing(7, 4, old_data, 9);
+
+		let counts = migrations::pre_migrate::<TestRuntime>().unwrap();
+		assert_eq!(counts, (2, 3));
+		StructStorage::on_runtime_upgrade();
+		assert_eq!(migrations::post_migrate::<TestRuntime>(counts), Ok(()));
+
+		// Super things see the stored inner thing
+		let (_, inner) = StructStorage::super_thing_with_inner(5).unwrap();
+		assert_eq!(inner.hash, new_data);
+		assert_eq!(inner.balance, 8);
+		assert_eq!(inner.references, 2);
+
+		// The copy of a missing inner thing is stored
+		let (_, inner) = StructStorage::super_thing_with_inner(7).unwrap();
+		assert_eq!(inner.number, 4);
+		assert_eq!(inner.balance, 9);
+		assert_eq!(inner.references, 1);
+	})
+}
+
+#[test]
+fn updates_are_seen_through_super_things() {
+	ExtBuilder::build().execute_with(|| {
+		let data = H256::from_low_u64_be(16);
+		let new_data = H256::from_low_u64_be(17);
+		assert_ok!(StructStorage::insert_super_thing_with_new_inner(
+			Origin::signed(1),
+			3u32,
+			data,
+			7u64.into(),
+			5u32,
+		));
+		assert_ok!(StructStorage::insert_super_thing_with_existing_inner(
+			Origin::signed(1),
+			3u32,
+			6u32
+		));
+
+		System::set_block_number(2);
+		assert_ok!(StructStorage::update_inner_thing(
+			Origin::signed(1),
+			3u32,
+			new_data,
+			8u64.into()
+		));
+
+		for super_number in 5..=6 {
+			let (_, inner) = StructStorage::super_thing_with_inner(super_number).unwrap();
+			assert_eq!(inner.hash, new_data);
+			assert_eq!(inner.balance, 8);
+			assert_eq!(inner.updated_at, 2);
+			assert_eq!(inner.references, 2);
+		}
+		assert_eq!(StructStorage::super_thing_with_inner(7), None);
+
+		let expected_event = TestEvent::struct_storage(RawEvent::InnerThingUpdated(3, new_data, 8));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		assert_eq!(migrations::check_references::<TestRuntime>(), Ok(()));
+
+		assert_noop!(
+			StructStorage::update_inner_thing(Origin::signed(1), 4u32, new_data, 8u64.into()),
+			Error::<TestRuntime>::UnknownInnerThing
+		);
+	})
+}
+
+#[test]
+fn things_cannot_be_overwritten() {
+	ExtBuilder::build().execute_with(|| {
+		let data = H256::from_low_u64_be(16);
+		assert_ok!(StructStorage::insert_super_thing_with_new_inner(
+			Origin::signed(1),
+			3u32,
+			data,
+			7u64.into(),
+			5u32,
+		));
+
+		assert_noop!(
+			StructStorage::insert_inner_thing(Origin::signed(1), 3u32, data, 8u64.into()),
+			Error::<TestRuntime>::InnerThingExists
+		);
+		assert_noop!(
+			StructStorage::insert_super_thing_with_new_inner(
+				Origin::signed(1),
+				3u32,
+				data,
+				8u64.into(),
+				6u32,
+			),
+			Error::<TestRuntime>::InnerThingExists
+		);
+		assert_noop!(
+			StructStorage::insert_super_thing_with_existing_inner(Origin::signed(1), 3u32, 5u32),
+			Error::<TestRuntime>::SuperThingExists
+		);
+		assert_noop!(
+			StructStorage::insert_super_thing_with_existing_inner(Origin::signed(1), 4u32, 6u32),
+			Error::<TestRuntime>::UnknownInnerThing
+		);
+	})
+}
+
+#[test]
+fn referenced_inner_things_cannot_be_removed() {
+	ExtBuilder::build().execute_with(|| {
+		let data = H256::from_low_u64_be(16);
+		assert_ok!(StructStorage::insert_super_thing_with_new_inner(
+			Origin::signed(1),
+			3u32,
+			data,
+			7u64.into(),
+			5u32,
+		));
+		assert_noop!(
+			StructStorage::remove_inner_thing(Origin::signed(1), 3u32),
+			Error::<TestRuntime>::InnerThingInUse
+		);
+
+		// Removing the super thing releases its reference
+		assert_ok!(StructStorage::remove_super_thing(Origin::signed(1), 5u32));
+		assert_eq!(StructStorage::inner_things_by_numbers(3u32).references, 0);
+		assert_eq!(migrations::check_references::<TestRuntime>(), Ok(()));
+		assert_noop!(
+			StructStorage::remove_super_thing(Origin::signed(1), 5u32),
+			Error::<TestRuntime>::UnknownSuperThing
+		);
+
+		assert_ok!(StructStorage::remove_inner_thing(Origin::signed(1), 3u32));
+		assert!(!InnerThingsByNumbers::<TestRuntime>::contains_key(3u32));
+		assert_noop!(
+			StructStorage::remove_inner_thing(Origin::signed(1), 3u32),
+			Error::<TestRuntime>::UnknownInnerThing
+		);
+
+		let expected_event = TestEvent::struct_storage(RawEvent::SuperThingRemoved(5));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+		let expected_event = TestEvent::struct_storage(RawEvent::InnerThingRemoved(3));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
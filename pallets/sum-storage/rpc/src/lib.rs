@@ -1,17 +1,51 @@
-//! RPC interface for the transaction payment module.
+//! RPC interface for the sum storage pallet.
 
+use futures::{future, StreamExt, TryStreamExt};
+use futures01::{Future, Sink};
 use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
 use jsonrpc_derive::rpc;
-use sp_api::ProvideRuntimeApi;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use log::warn;
+use sc_client_api::BlockchainEvents;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
-use std::sync::Arc;
+use std::{fmt::Debug, sync::Arc};
 use sum_storage_runtime_api::SumStorageApi as SumStorageRuntimeApi;
+pub use sum_storage_runtime_api::SumStorageState;
+
+#[cfg(test)]
+mod tests;
 
 #[rpc]
 pub trait SumStorageApi<BlockHash> {
+	/// RPC metadata
+	type Metadata;
+
+	/// The sum of the two storage values
 	#[rpc(name = "sumStorage_getSum")]
 	fn get_sum(&self, at: Option<BlockHash>) -> Result<u32>;
+
+	/// Both storage values and their sum
+	#[rpc(name = "sumStorage_getState")]
+	fn get_state(&self, at: Option<BlockHash>) -> Result<SumStorageState>;
+
+	/// The sum at the best block, and then the new sum each time a new best block changes it
+	#[pubsub(
+		subscription = "sumStorage_sum",
+		subscribe,
+		name = "sumStorage_subscribeSum"
+	)]
+	fn subscribe_sum(&self, metadata: Self::Metadata, subscriber: Subscriber<u32>);
+
+	/// Unsubscribe from sum updates
+	#[pubsub(
+		subscription = "sumStorage_sum",
+		unsubscribe,
+		name = "sumStorage_unsubscribeSum"
+	)]
+	fn unsubscribe_sum(&self, metadata: Option<Self::Metadata>, id: SubscriptionId)
+		-> Result<bool>;
 }
 
 /// A struct that implements the `SumStorageApi`.
@@ -19,35 +53,126 @@ pub struct SumStorage<C, M> {
 	// If you have more generics, no need to SumStorage<C, M, N, P, ...>
 	// just use a tuple like SumStorage<C, (M, N, P, ...)>
 	client: Arc<C>,
+	subscriptions: SubscriptionManager,
 	_marker: std::marker::PhantomData<M>,
 }
 
 impl<C, M> SumStorage<C, M> {
-	/// Create new `SumStorage` instance with the given reference to the client.
-	pub fn new(client: Arc<C>) -> Self {
+	/// Create new `SumStorage` instance with the given reference to the client, and the manager
+	/// whose executor runs the subscriptions.
+	pub fn new(client: Arc<C>, subscriptions: SubscriptionManager) -> Self {
 		Self {
 			client,
+			subscriptions,
 			_marker: Default::default(),
 		}
 	}
 }
 
 /// Error type of this RPC api.
-// pub enum Error {
-// 	/// The transaction was not decodable.
-// 	DecodeError,
-// 	/// The call to runtime failed.
-// 	RuntimeError,
-// }
-//
-// impl From<Error> for i64 {
-// 	fn from(e: Error) -> i64 {
-// 		match e {
-// 			Error::RuntimeError => 1,
-// 			Error::DecodeError => 2,
-// 		}
-// 	}
-// }
+pub enum Error {
+	/// The node does not know the block.
+	UnknownBlock,
+	/// The runtime at the block does not implement the runtime API, or not the version of it
+	/// that the call needs.
+	RuntimeApiMissing,
+	/// The runtime's answer could not be decoded.
+	DecodeError,
+	/// The sum does not fit in a `u32`.
+	SumOverflow,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::UnknownBlock => 1,
+			Error::RuntimeApiMissing => 2,
+			Error::DecodeError => 3,
+			Error::SumOverflow => 4,
+		}
+	}
+}
+
+/// Builds the RPC error for `error`, with `data` to help debug it
+fn rpc_error(error: Error, message: &str, data: impl Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(error.into()),
+		message: message.into(),
+		data: Some(format!("{:?}", data).into()),
+	}
+}
+
+/// The block to query, which is the best block unless `at` is given
+fn block_id<C, Block>(client: &C, at: Option<<Block as BlockT>::Hash>) -> Result<BlockId<Block>>
+where
+	Block: BlockT,
+	C: HeaderBackend<Block>,
+{
+	let hash = at.unwrap_or_else(||
+		// If the block hash is not supplied assume the best block.
+		client.info().best_hash);
+	let at = BlockId::hash(hash);
+
+	match client.header(at) {
+		Ok(Some(_)) => Ok(at),
+		Ok(None) => Err(rpc_error(Error::UnknownBlock, "Unknown block", hash)),
+		Err(e) => Err(rpc_error(Error::UnknownBlock, "Unknown block", e)),
+	}
+}
+
+/// The version of the runtime API that the runtime at `at` implements
+fn api_version<C, Block>(client: &C, at: &BlockId<Block>) -> Result<u32>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: SumStorageRuntimeApi<Block>,
+{
+	let api = client.runtime_api();
+	let has_version = |version: u32| {
+		api.has_api_with::<dyn SumStorageRuntimeApi<Block, Error = sp_blockchain::Error>, _>(
+			at,
+			|v| v >= version,
+		)
+		.map_err(|e| rpc_error(Error::UnknownBlock, "Unable to read the runtime version", e))
+	};
+
+	if has_version(2)? {
+		Ok(2)
+	} else if has_version(1)? {
+		Ok(1)
+	} else {
+		Err(rpc_error(
+			Error::RuntimeApiMissing,
+			"The runtime does not implement the sum storage API",
+			at,
+		))
+	}
+}
+
+/// The sum at `at`, from whichever version of the runtime API the runtime implements
+fn sum_at<C, Block>(client: &C, at: &BlockId<Block>) -> Result<u32>
+where
+	Block: BlockT,
+	C: ProvideRuntimeApi<Block>,
+	C::Api: SumStorageRuntimeApi<Block>,
+{
+	let api = client.runtime_api();
+	let decode_error = |e| rpc_error(Error::DecodeError, "Unable to query the sum", e);
+
+	let sum = if api_version(client, at)? >= 2 {
+		api.get_sum(at).map_err(decode_error)?
+	} else {
+		// Runtimes before version 2 let the sum wrap around, so there is no overflow to report
+		#[allow(deprecated)]
+		{
+			api.get_sum_before_version_2(at)
+				.map(Some)
+				.map_err(decode_error)?
+		}
+	};
+
+	sum.ok_or_else(|| rpc_error(Error::SumOverflow, "The sum does not fit in a u32", at))
+}
 
 impl<C, Block> SumStorageApi<<Block as BlockT>::Hash> for SumStorage<C, Block>
 where
@@ -55,19 +180,70 @@ where
 	C: Send + Sync + 'static,
 	C: ProvideRuntimeApi<Block>,
 	C: HeaderBackend<Block>,
+	C: BlockchainEvents<Block>,
 	C::Api: SumStorageRuntimeApi<Block>,
 {
+	type Metadata = sc_rpc::Metadata;
+
 	fn get_sum(&self, at: Option<<Block as BlockT>::Hash>) -> Result<u32> {
-		let api = self.client.runtime_api();
-		let at = BlockId::hash(at.unwrap_or_else(||
-			// If the block hash is not supplied assume the best block.
-			self.client.info().best_hash));
-
-		let runtime_api_result = api.get_sum(&at);
-		runtime_api_result.map_err(|e| RpcError {
-			code: ErrorCode::ServerError(9876), // No real reason for this value
-			message: "Something wrong".into(),
-			data: Some(format!("{:?}", e).into()),
-		})
+		let at = block_id(&*self.client, at)?;
+		sum_at(&*self.client, &at)
+	}
+
+	fn get_state(&self, at: Option<<Block as BlockT>::Hash>) -> Result<SumStorageState> {
+		let at = block_id(&*self.client, at)?;
+
+		if api_version(&*self.client, &at)? < 2 {
+			return Err(rpc_error(
+				Error::RuntimeApiMissing,
+				"The runtime only implements version 1 of the sum storage API, which has no state",
+				at,
+			));
+		}
+
+		self.client
+			.runtime_api()
+			.get_state(&at)
+			.map_err(|e| rpc_error(Error::DecodeError, "Unable to query the state", e))
+	}
+
+	fn subscribe_sum(&self, _metadata: Self::Metadata, subscriber: Subscriber<u32>) {
+		let client = self.client.clone();
+		let best = sum_at(&*client, &BlockId::hash(client.info().best_hash));
+
+		// Listen before the subscription's task starts, so no block imported in between is missed
+		let imports = client.import_notification_stream();
+
+		// A new best block is only sent when its sum, or the error in its place, differs from
+		// the last one sent
+		let mut last = best.as_ref().map(|sum| *sum).map_err(|e| e.code.clone());
+		let changes = imports
+			.filter(|notification| future::ready(notification.is_new_best))
+			.filter_map(move |notification| {
+				let sum = sum_at(&*client, &BlockId::hash(notification.hash));
+				let sent = sum.as_ref().map(|sum| *sum).map_err(|e| e.code.clone());
+				let changed = sent != last;
+				last = sent;
+				future::ready(if changed { Some(sum) } else { None })
+			});
+
+		self.subscriptions.add(subscriber, |sink| {
+			let sums = futures::stream::once(future::ready(best))
+				.chain(changes)
+				.map(Ok::<_, ()>)
+				.compat();
+
+			sink.sink_map_err(|e| warn!("Error sending sum notifications: {:?}", e))
+				.send_all(sums)
+				.map(|_| ())
+		});
+	}
+
+	fn unsubscribe_sum(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		id: SubscriptionId,
+	) -> Result<bool> {
+		Ok(self.subscriptions.cancel(id))
 	}
 }
@@ -0,0 +1,160 @@
+use super::*;
+use api_runtime::{
+	genesis::dev_genesis, Block, BuildStorage, Call, Index, RuntimeApi, SignedExtra, TimestampCall,
+	UncheckedExtrinsic, VERSION,
+};
+use futures01::Stream;
+use sc_block_builder::BlockBuilderProvider;
+use sc_executor::{NativeExecutor, WasmExecutionMethod};
+use sc_service::client::LocalCallExecutor;
+use sp_consensus::BlockOrigin;
+use sp_core::{storage::Storage, Encode};
+use sp_keyring::AccountKeyring;
+use sp_runtime::generic::{Era, SignedPayload};
+use substrate_test_client::{ClientBlockImportExt, GenesisInit, TestClientBuilder};
+
+sc_executor::native_executor_instance!(
+	Executor,
+	api_runtime::api::dispatch,
+	api_runtime::native_version,
+);
+
+type Backend = substrate_test_client::Backend<Block>;
+type Client = sc_service::client::Client<
+	Backend,
+	LocalCallExecutor<Backend, NativeExecutor<Executor>>,
+	Block,
+	RuntimeApi,
+>;
+
+/// Starts the chain from the api-runtime's development genesis
+#[derive(Default)]
+struct GenesisParams;
+
+impl GenesisInit for GenesisParams {
+	fn genesis_storage(&self) -> Storage {
+		dev_genesis().build_storage().unwrap()
+	}
+}
+
+/// A client of the api-runtime, along with the state needed to author blocks for it
+struct TestChain {
+	client: Arc<Client>,
+	nonce: Index,
+	timestamp: u64,
+}
+
+impl TestChain {
+	fn new() -> Self {
+		let (client, _) = TestClientBuilder::<_, _, _, GenesisParams>::with_default_backend()
+			.build_with_native_executor::<RuntimeApi, _>(
+			NativeExecutor::<Executor>::new(WasmExecutionMethod::Interpreted, None, 8),
+		);
+		TestChain {
+			client: Arc::new(client),
+			nonce: 0,
+			timestamp: 0,
+		}
+	}
+
+	/// Signs `call` as Alice
+	fn sign(&mut self, call: Call) -> UncheckedExtrinsic {
+		let genesis = self.client.info().genesis_hash;
+		let extra: SignedExtra = (
+			frame_system::CheckTxVersion::new(),
+			frame_system::CheckGenesis::new(),
+			frame_system::CheckEra::from(Era::Immortal),
+			frame_system::CheckNonce::from(self.nonce),
+			frame_system::CheckWeight::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::from(0),
+		);
+		self.nonce += 1;
+
+		let payload = SignedPayload::from_raw(
+			call,
+			extra,
+			(VERSION.transaction_version, genesis, genesis, (), (), ()),
+		);
+		let signature = payload.using_encoded(|payload| AccountKeyring::Alice.sign(payload));
+		let (call, extra, _) = payload.deconstruct();
+		UncheckedExtrinsic::new_signed(
+			call,
+			AccountKeyring::Alice.to_account_id(),
+			signature.into(),
+			extra,
+		)
+	}
+
+	/// Authors and imports a block on top of the best block, with `calls` signed by Alice
+	fn import_block(&mut self, calls: Vec<Call>) {
+		// Every block must set the timestamp, at least `MinimumPeriod` after the last one
+		self.timestamp += 2000;
+		let mut extrinsics = vec![UncheckedExtrinsic::new_unsigned(Call::Timestamp(
+			TimestampCall::set(self.timestamp),
+		))];
+		for call in calls {
+			extrinsics.push(self.sign(call));
+		}
+
+		let mut builder = self.client.new_block(Default::default()).unwrap();
+		for extrinsic in extrinsics {
+			builder.push(extrinsic).unwrap();
+		}
+		let block = builder.build().unwrap().block;
+		self.client.import(BlockOrigin::Own, block).unwrap();
+	}
+}
+
+fn set_thing_1(val: u32) -> Call {
+	Call::SumStorage(sum_storage::Call::set_thing_1(val))
+}
+
+fn set_thing_2(val: u32) -> Call {
+	Call::SumStorage(sum_storage::Call::set_thing_2(val))
+}
+
+#[test]
+fn subscription_sends_the_sum_when_it_changes() {
+	let mut chain = TestChain::new();
+
+	let mut runtime = tokio::runtime::Runtime::new().unwrap();
+	let subscriptions = SubscriptionManager::new(Arc::new(runtime.executor()));
+	let api = SumStorage::new(chain.client.clone(), subscriptions);
+
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+	api.subscribe_sum(Default::default(), subscriber);
+	assert!(matches!(runtime.block_on(id), Ok(Ok(_))));
+
+	chain.import_block(vec![set_thing_1(2)]);
+	chain.import_block(vec![set_thing_2(3)]);
+	// Neither of these blocks change the sum
+	chain.import_block(vec![]);
+	chain.import_block(vec![set_thing_1(2)]);
+	chain.import_block(vec![set_thing_1(1), set_thing_2(5)]);
+
+	let sums: Vec<u64> = runtime
+		.block_on(transport.take(4).collect())
+		.unwrap()
+		.into_iter()
+		.map(|notification| {
+			let notification: serde_json::Value = serde_json::from_str(&notification).unwrap();
+			notification["params"]["result"].as_u64().unwrap()
+		})
+		.collect();
+	assert_eq!(sums, vec![0, 2, 5, 6]);
+}
+
+#[test]
+fn unsubscribe_cancels_the_subscription() {
+	let chain = TestChain::new();
+	let mut runtime = tokio::runtime::Runtime::new().unwrap();
+	let subscriptions = SubscriptionManager::new(Arc::new(runtime.executor()));
+	let api = SumStorage::new(chain.client.clone(), subscriptions);
+
+	let (subscriber, id, _transport) = Subscriber::new_test("test");
+	api.subscribe_sum(Default::default(), subscriber);
+	let id = runtime.block_on(id).unwrap().unwrap();
+
+	assert_eq!(api.unsubscribe_sum(None, id.clone()), Ok(true));
+	assert_eq!(api.unsubscribe_sum(None, id), Ok(false));
+}
@@ -1,10 +1,56 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::too_many_arguments)]
 
+use codec::{Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+
+/// The two storage values of the sum-storage pallet, and their sum
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SumStorageState {
+	pub thing1: u32,
+	pub thing2: u32,
+	/// The sum of the two values, or `None` when it does not fit in a `u32`
+	pub sum: Option<u32>,
+}
+
 // Here we declare the runtime API. It is implemented it the `impl` block in
 // runtime amalgamator file (the `runtime/src/lib.rs`)
 sp_api::decl_runtime_apis! {
+	/// Version 1 only had `get_sum`, which wrapped around when the sum overflowed. Clients that
+	/// query old blocks can still call it as `get_sum_before_version_2`.
+	#[api_version(2)]
 	pub trait SumStorageApi {
+		#[changed_in(2)]
 		fn get_sum() -> u32;
+
+		/// The sum of the two values, or `None` when it does not fit in a `u32`
+		fn get_sum() -> Option<u32>;
+
+		/// Both values and their sum
+		fn get_state() -> SumStorageState;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn state_serializes_to_camel_case_json() {
+		let state = SumStorageState {
+			thing1: 1,
+			thing2: 2,
+			sum: Some(3),
+		};
+		let json = serde_json::to_string(&state).unwrap();
+		assert_eq!(json, r#"{"thing1":1,"thing2":2,"sum":3}"#);
+		assert_eq!(
+			serde_json::from_str::<SumStorageState>(&json).unwrap(),
+			state
+		);
 	}
 }
@@ -0,0 +1,38 @@
+//! Benchmarks for the sum storage pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	set_thing_1 {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller), 42)
+	verify {
+		assert_eq!(Module::<T>::thing1(), 42);
+	}
+
+	set_thing_2 {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller), 42)
+	verify {
+		assert_eq!(Module::<T>::thing2(), 42);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_thing_1::<Test>());
+			assert_ok!(test_benchmark_set_thing_2::<Test>());
+		});
+	}
+}
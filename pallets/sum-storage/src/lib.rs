@@ -7,14 +7,23 @@
 
 use frame_support::{decl_event, decl_module, decl_storage, dispatch};
 use frame_system::{self as system, ensure_signed};
+pub use sum_storage_runtime_api::SumStorageState;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
 
 /// The module's configuration trait.
 pub trait Trait: system::Trait {
 	/// The overarching event type.
 	type Event: From<Event> + Into<<Self as system::Trait>::Event>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
@@ -31,7 +40,7 @@ decl_module! {
 		fn deposit_event() = default;
 
 		/// Sets the first simple storage value
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::set_thing_1()]
 		pub fn set_thing_1(origin, val: u32) -> dispatch::DispatchResult {
 			let _ = ensure_signed(origin)?;
 
@@ -42,7 +51,7 @@ decl_module! {
 		}
 
 		/// Sets the second stored value
-		#[weight = 10_000]
+		#[weight = T::WeightInfo::set_thing_2()]
 		pub fn set_thing_2(origin, val: u32) -> dispatch::DispatchResult {
 			let _ = ensure_signed(origin)?;
 
@@ -55,8 +64,18 @@ decl_module! {
 }
 
 impl<T: Trait> Module<T> {
-	pub fn get_sum() -> u32 {
-		Thing1::get() + Thing2::get()
+	/// The sum of the two values, or `None` when it does not fit in a `u32`
+	pub fn get_sum() -> Option<u32> {
+		Thing1::get().checked_add(Thing2::get())
+	}
+
+	/// Both values and their sum
+	pub fn get_state() -> SumStorageState {
+		SumStorageState {
+			thing1: Thing1::get(),
+			thing2: Thing2::get(),
+			sum: Self::get_sum(),
+		}
 	}
 }
 
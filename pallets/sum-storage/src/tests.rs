@@ -50,12 +50,13 @@ impl system::Trait for Test {
 }
 impl Trait for Test {
 	type Event = ();
+	type WeightInfo = ();
 }
 type TemplateModule = Module<Test>;
 
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
-fn new_test_ext() -> sp_io::TestExternalities {
+pub fn new_test_ext() -> sp_io::TestExternalities {
 	system::GenesisConfig::default()
 		.build_storage::<Test>()
 		.unwrap()
@@ -65,7 +66,7 @@ fn new_test_ext() -> sp_io::TestExternalities {
 #[test]
 fn default_sum_zero() {
 	new_test_ext().execute_with(|| {
-		assert_eq!(TemplateModule::get_sum(), 0);
+		assert_eq!(TemplateModule::get_sum(), Some(0));
 	});
 }
 
@@ -73,7 +74,7 @@ fn default_sum_zero() {
 fn sums_thing_one() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(TemplateModule::set_thing_1(Origin::signed(1), 42));
-		assert_eq!(TemplateModule::get_sum(), 42);
+		assert_eq!(TemplateModule::get_sum(), Some(42));
 	});
 }
 
@@ -81,7 +82,7 @@ fn sums_thing_one() {
 fn sums_thing_two() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(TemplateModule::set_thing_2(Origin::signed(1), 42));
-		assert_eq!(TemplateModule::get_sum(), 42);
+		assert_eq!(TemplateModule::get_sum(), Some(42));
 	});
 }
 
@@ -90,6 +91,34 @@ fn sums_both_values() {
 	new_test_ext().execute_with(|| {
 		assert_ok!(TemplateModule::set_thing_1(Origin::signed(1), 42));
 		assert_ok!(TemplateModule::set_thing_2(Origin::signed(1), 43));
-		assert_eq!(TemplateModule::get_sum(), 85);
+		assert_eq!(TemplateModule::get_sum(), Some(85));
+	});
+}
+
+#[test]
+fn reports_overflow() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_thing_1(
+			Origin::signed(1),
+			u32::max_value()
+		));
+		assert_ok!(TemplateModule::set_thing_2(Origin::signed(1), 1));
+		assert_eq!(TemplateModule::get_sum(), None);
+	});
+}
+
+#[test]
+fn state_includes_both_values() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_thing_1(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::set_thing_2(Origin::signed(1), 43));
+		assert_eq!(
+			TemplateModule::get_state(),
+			SumStorageState {
+				thing1: 42,
+				thing2: 43,
+				sum: Some(85),
+			}
+		);
 	});
 }
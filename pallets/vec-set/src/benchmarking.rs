@@ -0,0 +1,79 @@
+//! Benchmarks for the vec set pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+/// Replaces the members vec with `m` sorted accounts that did not reserve a deposit
+fn set_members<T: Trait>(m: u32) {
+	let mut members: Vec<T::AccountId> = (0..m).map(|i| account("member", i, 0)).collect();
+	members.sort();
+	Members::<T>::put(members);
+}
+
+/// An account that is not a member, with enough funds for the admission deposit
+fn funded_account<T: Trait>() -> T::AccountId {
+	let who: T::AccountId = account("caller", 0, 0);
+	let deposit = T::AdmissionDeposit::get().max(T::Currency::minimum_balance());
+	T::Currency::make_free_balance_be(&who, deposit * 10u32.into());
+	who
+}
+
+benchmarks! {
+	_ { }
+
+	add_member {
+		let m in 0 .. T::MaxMembers::get().saturating_sub(1);
+		set_members::<T>(m);
+		let caller = funded_account::<T>();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Module::<T>::members().contains(&caller));
+	}
+
+	admit_member {
+		let m in 0 .. T::MaxMembers::get().saturating_sub(1);
+		set_members::<T>(m);
+		let member = funded_account::<T>();
+	}: _(T::AddOrigin::successful_origin(), member.clone())
+	verify {
+		assert!(Module::<T>::members().contains(&member));
+	}
+
+	remove_member {
+		let m in 0 .. T::MaxMembers::get().saturating_sub(1);
+		set_members::<T>(m);
+		let caller = funded_account::<T>();
+		Module::<T>::add_member(RawOrigin::Signed(caller.clone()).into())?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!Module::<T>::members().contains(&caller));
+	}
+
+	expel_member {
+		let m in 0 .. T::MaxMembers::get().saturating_sub(1);
+		set_members::<T>(m);
+		let member = funded_account::<T>();
+		Module::<T>::add_member(RawOrigin::Signed(member.clone()).into())?;
+	}: _(T::RemoveOrigin::successful_origin(), member.clone())
+	verify {
+		assert!(!Module::<T>::members().contains(&member));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_add_member::<TestRuntime>());
+			assert_ok!(test_benchmark_admit_member::<TestRuntime>());
+			assert_ok!(test_benchmark_remove_member::<TestRuntime>());
+			assert_ok!(test_benchmark_expel_member::<TestRuntime>());
+		});
+	}
+}
@@ -2,27 +2,69 @@
 
 //! A pallet that implements a storage set on top of a sorted vec and demonstrates performance
 //! tradeoffs when using map sets.
-
-use frame_support::{decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure};
+//!
+//! Accounts may join the set themselves by reserving an `AdmissionDeposit`, or be admitted by the
+//! `AddOrigin` without a deposit. Members who leave get their deposit back. Members expelled by
+//! the `RemoveOrigin` have their deposit slashed.
+
+use account_set::{AccountSet, OnMembersChanged};
+use frame_support::{
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::DispatchResult,
+	ensure,
+	traits::{Currency, EnsureOrigin, Get, Imbalance, OnUnbalanced, ReservableCurrency},
+};
 use frame_system::{self as system, ensure_signed};
-use sp_std::prelude::*;
+use sp_runtime::traits::Zero;
 use sp_std::collections::btree_set::BTreeSet;
-use account_set::AccountSet;
+use sp_std::prelude::*;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 #[cfg(test)]
 mod tests;
+pub mod weights;
 
-/// A maximum number of members. When membership reaches this number, no new members may join.
-pub const MAX_MEMBERS: usize = 16;
+pub use weights::WeightInfo;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> =
+	<<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::NegativeImbalance;
 
 pub trait Trait: system::Trait {
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+
+	/// The currency in which admission deposits are reserved
+	type Currency: ReservableCurrency<Self::AccountId>;
+
+	/// The origin that may admit members without a deposit
+	type AddOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The origin that may expel members
+	type RemoveOrigin: EnsureOrigin<Self::Origin>;
+
+	/// The deposit reserved from accounts that join by themselves. Zero means joining is free.
+	type AdmissionDeposit: Get<BalanceOf<Self>>;
+
+	/// A maximum number of members. When membership reaches this number, no new members may join.
+	type MaxMembers: Get<u32>;
+
+	/// Handler for the deposits slashed from expelled members
+	type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+	/// Notified whenever members join or leave
+	type MembershipChanged: OnMembersChanged<Self::AccountId>;
+
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
 }
 
 decl_storage! {
 	trait Store for Module<T: Trait> as VecSet {
 		// The set of all members. Stored as a single vec
 		Members get(fn members): Vec<T::AccountId>;
+		// The deposit each member reserved when joining
+		Deposits get(fn deposit_of): map hasher(blake2_128_concat) T::AccountId => BalanceOf<T>;
 	}
 }
 
@@ -30,11 +72,14 @@ decl_event!(
 	pub enum Event<T>
 	where
 		AccountId = <T as system::Trait>::AccountId,
+		Balance = BalanceOf<T>,
 	{
 		/// Added a member
 		MemberAdded(AccountId),
 		/// Removed a member
 		MemberRemoved(AccountId),
+		/// Expelled a member and slashed what was left of their deposit (member, amount slashed)
+		MemberExpelled(AccountId, Balance),
 	}
 );
 
@@ -55,60 +100,134 @@ decl_module! {
 
 		type Error = Error<T>;
 
-		/// Adds a member to the membership set unless the max is reached
-		#[weight = 10_000]
+		/// Adds the caller to the membership set unless the max is reached, reserving the
+		/// admission deposit
+		#[weight = T::WeightInfo::add_member(T::MaxMembers::get())]
 		pub fn add_member(origin) -> DispatchResult {
 			let new_member = ensure_signed(origin)?;
+			Self::insert_member(new_member, T::AdmissionDeposit::get())
+		}
 
-			let mut members = Members::<T>::get();
-			ensure!(members.len() < MAX_MEMBERS, Error::<T>::MembershipLimitReached);
-
-			// We don't want to add duplicate members, so we check whether the potential new
-			// member is already present in the list. Because the list is always ordered, we can
-			// leverage the binary search which makes this check O(log n).
-			match members.binary_search(&new_member) {
-				// If the search succeeds, the caller is already a member, so just return
-				Ok(_) => Err(Error::<T>::AlreadyMember.into()),
-				// If the search fails, the caller is not a member and we learned the index where
-				// they should be inserted
-				Err(index) => {
-					members.insert(index, new_member.clone());
-					Members::<T>::put(members);
-					Self::deposit_event(RawEvent::MemberAdded(new_member));
-					Ok(())
-				}
-			}
+		/// Admits an account to the membership set without a deposit
+		#[weight = T::WeightInfo::admit_member(T::MaxMembers::get())]
+		pub fn admit_member(origin, new_member: T::AccountId) -> DispatchResult {
+			T::AddOrigin::ensure_origin(origin)?;
+			Self::insert_member(new_member, Zero::zero())
 		}
 
-		/// Removes a member.
-		#[weight = 10_000]
+		/// Removes the caller from the membership set, returning their deposit
+		#[weight = T::WeightInfo::remove_member(T::MaxMembers::get())]
 		fn remove_member(origin) -> DispatchResult {
 			let old_member = ensure_signed(origin)?;
 
-			let mut members = Members::<T>::get();
-
-			// We have to find out if the member exists in the sorted vec, and, if so, where.
-			match members.binary_search(&old_member) {
-				// If the search succeeds, the caller is a member, so remove her
-				Ok(index) => {
-					members.remove(index);
-					Members::<T>::put(members);
-					Self::deposit_event(RawEvent::MemberRemoved(old_member));
-					Ok(())
-				},
-				// If the search fails, the caller is not a member, so just return
-				Err(_) => Err(Error::<T>::NotMember.into()),
-			}
+			Self::take_member(&old_member)?;
+			T::Currency::unreserve(&old_member, Deposits::<T>::take(&old_member));
+
+			Self::deposit_event(RawEvent::MemberRemoved(old_member));
+			Ok(())
+		}
+
+		/// Expels a member from the membership set, slashing their deposit
+		#[weight = T::WeightInfo::expel_member(T::MaxMembers::get())]
+		fn expel_member(origin, old_member: T::AccountId) -> DispatchResult {
+			T::RemoveOrigin::ensure_origin(origin)?;
+
+			Self::take_member(&old_member)?;
+			let deposit = Deposits::<T>::take(&old_member);
+			// Another pallet may already have slashed part of the reserved deposit
+			let (imbalance, _) = T::Currency::slash_reserved(&old_member, deposit);
+			let slashed = imbalance.peek();
+			T::Slashed::on_unbalanced(imbalance);
+
+			Self::deposit_event(RawEvent::MemberExpelled(old_member, slashed));
+			Ok(())
 		}
 
 		// also see `append_or_insert`, `append_or_put` in pallet-elections/phragmen, democracy
 	}
 }
 
+impl<T: Trait> Module<T> {
+	/// Inserts a new member into the sorted vec after reserving their deposit
+	fn insert_member(new_member: T::AccountId, deposit: BalanceOf<T>) -> DispatchResult {
+		let mut members = Members::<T>::get();
+		ensure!(
+			(members.len() as u32) < T::MaxMembers::get(),
+			Error::<T>::MembershipLimitReached
+		);
+
+		// We don't want to add duplicate members, so we check whether the potential new
+		// member is already present in the list. Because the list is always ordered, we can
+		// leverage the binary search which makes this check O(log n).
+		match members.binary_search(&new_member) {
+			// If the search succeeds, the caller is already a member, so just return
+			Ok(_) => Err(Error::<T>::AlreadyMember.into()),
+			// If the search fails, the caller is not a member and we learned the index where
+			// they should be inserted
+			Err(index) => {
+				// Reserving is the last check that can fail, so nothing is written before it
+				T::Currency::reserve(&new_member, deposit)?;
+				if !deposit.is_zero() {
+					Deposits::<T>::insert(&new_member, deposit);
+				}
+
+				members.insert(index, new_member.clone());
+				Members::<T>::put(members);
+				T::MembershipChanged::on_members_changed(&[new_member.clone()], &[]);
+				Self::deposit_event(RawEvent::MemberAdded(new_member));
+				Ok(())
+			}
+		}
+	}
+
+	/// Removes a member from the sorted vec
+	fn take_member(old_member: &T::AccountId) -> DispatchResult {
+		let mut members = Members::<T>::get();
+
+		// We have to find out if the member exists in the sorted vec, and, if so, where.
+		match members.binary_search(old_member) {
+			// If the search succeeds, the account is a member, so remove them
+			Ok(index) => {
+				members.remove(index);
+				Members::<T>::put(members);
+				T::MembershipChanged::on_members_changed(&[], &[old_member.clone()]);
+				Ok(())
+			}
+			// If the search fails, the account is not a member, so just return
+			Err(_) => Err(Error::<T>::NotMember.into()),
+		}
+	}
+}
+
 impl<T: Trait> AccountSet for Module<T> {
 	type AccountId = T::AccountId;
 
 	fn accounts() -> BTreeSet<T::AccountId> {
 		Self::members().into_iter().collect::<BTreeSet<_>>()
 	}
+
+	/// Decodes the whole vec, but the binary search is O(log n)
+	fn contains(who: &T::AccountId) -> bool {
+		Self::members().binary_search(who).is_ok()
+	}
+
+	/// Only decodes the length prefix of the vec
+	fn count() -> u32 {
+		Members::<T>::decode_len().unwrap_or(0) as u32
+	}
+
+	/// Pages through the members in sorted order
+	fn accounts_paged(start_after: Option<&T::AccountId>, limit: u32) -> Vec<T::AccountId> {
+		let members = Self::members();
+		let start = match start_after {
+			// Whether or not the account is a member, the page starts at the next larger one
+			Some(who) => match members.binary_search(who) {
+				Ok(index) => index + 1,
+				Err(index) => index,
+			},
+			None => 0,
+		};
+
+		members.into_iter().skip(start).take(limit as usize).collect()
+	}
 }
@@ -1,6 +1,11 @@
 use crate::*;
-use frame_support::{assert_noop, assert_ok, impl_outer_event, impl_outer_origin, parameter_types};
-use frame_system as system;
+use frame_support::{
+	assert_noop, assert_ok, dispatch::DispatchError, impl_outer_event, impl_outer_origin,
+	parameter_types,
+};
+use frame_system::{self as system, EnsureRoot, RawOrigin};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
 use sp_core::H256;
 use sp_io::TestExternalities;
 use sp_runtime::{
@@ -21,6 +26,10 @@ parameter_types! {
 	pub const MaximumBlockWeight: u32 = 1024;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::one();
+
+	pub const ExistentialDeposit: u64 = 1;
+	pub const AdmissionDeposit: u64 = 10;
+	pub const MaxMembers: u32 = 16;
 }
 impl system::Trait for TestRuntime {
 	type Origin = Origin;
@@ -43,7 +52,7 @@ impl system::Trait for TestRuntime {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type ModuleToIndex = ();
-	type AccountData = ();
+	type AccountData = balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 }
@@ -56,23 +65,63 @@ impl_outer_event! {
 	pub enum TestEvent for TestRuntime {
 		vec_set<T>,
 		system<T>,
+		balances<T>,
+	}
+}
+
+impl balances::Trait for TestRuntime {
+	type Balance = u64;
+	type Event = TestEvent;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = system::Module<TestRuntime>;
+}
+
+thread_local! {
+	static MEMBERSHIP_CHANGES: RefCell<Vec<(Vec<u64>, Vec<u64>)>> = RefCell::new(vec![]);
+}
+
+/// Records every membership change it is notified of
+pub struct RecordChanges;
+impl OnMembersChanged<u64> for RecordChanges {
+	fn on_members_changed(incoming: &[u64], outgoing: &[u64]) {
+		MEMBERSHIP_CHANGES.with(|c| c.borrow_mut().push((incoming.to_vec(), outgoing.to_vec())));
 	}
 }
 
+fn membership_changes() -> Vec<(Vec<u64>, Vec<u64>)> {
+	MEMBERSHIP_CHANGES.with(|c| c.borrow().clone())
+}
+
 impl Trait for TestRuntime {
 	type Event = TestEvent;
+	type Currency = balances::Module<Self>;
+	type AddOrigin = EnsureRoot<u64>;
+	type RemoveOrigin = EnsureRoot<u64>;
+	type AdmissionDeposit = AdmissionDeposit;
+	type MaxMembers = MaxMembers;
+	type Slashed = ();
+	type MembershipChanged = RecordChanges;
+	type WeightInfo = ();
 }
 
 pub type System = system::Module<TestRuntime>;
+pub type Balances = balances::Module<TestRuntime>;
 pub type VecSet = Module<TestRuntime>;
 
 pub struct ExtBuilder;
 
 impl ExtBuilder {
 	pub fn build() -> TestExternalities {
-		let storage = system::GenesisConfig::default()
+		let mut storage = system::GenesisConfig::default()
 			.build_storage::<TestRuntime>()
 			.unwrap();
+		balances::GenesisConfig::<TestRuntime> {
+			balances: (0..20).map(|i| (i, 100)).collect(),
+		}
+		.assimilate_storage(&mut storage)
+		.unwrap();
+		MEMBERSHIP_CHANGES.with(|c| c.borrow_mut().clear());
 		let mut ext = TestExternalities::from(storage);
 		ext.execute_with(|| System::set_block_number(1));
 		ext
@@ -144,3 +193,126 @@ fn remove_member_handles_errors() {
 		);
 	})
 }
+
+#[test]
+fn joining_reserves_deposit() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(VecSet::add_member(Origin::signed(1)));
+		assert_eq!(Balances::reserved_balance(&1), 10);
+		assert_eq!(VecSet::deposit_of(&1), 10);
+
+		// Leaving returns the deposit
+		assert_ok!(VecSet::remove_member(Origin::signed(1)));
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 100);
+
+		assert_eq!(membership_changes(), vec![(vec![1], vec![]), (vec![], vec![1])]);
+	})
+}
+
+#[test]
+fn cant_join_without_deposit() {
+	ExtBuilder::build().execute_with(|| {
+		// 50 has no funds to reserve
+		assert_noop!(
+			VecSet::add_member(Origin::signed(50)),
+			balances::Error::<TestRuntime, _>::InsufficientBalance
+		);
+	})
+}
+
+#[test]
+fn add_origin_admits_without_deposit() {
+	ExtBuilder::build().execute_with(|| {
+		assert_noop!(
+			VecSet::admit_member(Origin::signed(1), 50),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(VecSet::admit_member(RawOrigin::Root.into(), 50));
+		assert_eq!(VecSet::deposit_of(&50), 0);
+
+		let expected_event = TestEvent::vec_set(RawEvent::MemberAdded(50));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn expelling_slashes_deposit() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(VecSet::add_member(Origin::signed(1)));
+
+		assert_noop!(
+			VecSet::expel_member(Origin::signed(2), 1),
+			DispatchError::BadOrigin
+		);
+		assert_ok!(VecSet::expel_member(RawOrigin::Root.into(), 1));
+
+		assert_eq!(VecSet::members(), Vec::<u64>::new());
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 90);
+		assert_eq!(membership_changes(), vec![(vec![1], vec![]), (vec![], vec![1])]);
+
+		let expected_event = TestEvent::vec_set(RawEvent::MemberExpelled(1, 10));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+
+		assert_noop!(
+			VecSet::expel_member(RawOrigin::Root.into(), 1),
+			Error::<TestRuntime>::NotMember
+		);
+	})
+}
+
+#[test]
+fn expelling_reports_what_was_slashed() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(VecSet::add_member(Origin::signed(1)));
+
+		// Another pallet slashes part of the reserved deposit first
+		let _ = Balances::slash_reserved(&1, 4);
+		assert_ok!(VecSet::expel_member(RawOrigin::Root.into(), 1));
+
+		assert_eq!(Balances::reserved_balance(&1), 0);
+		assert_eq!(Balances::free_balance(&1), 90);
+
+		let expected_event = TestEvent::vec_set(RawEvent::MemberExpelled(1, 6));
+		assert!(System::events().iter().any(|a| a.event == expected_event));
+	})
+}
+
+#[test]
+fn account_set_queries_work() {
+	ExtBuilder::build().execute_with(|| {
+		assert_eq!(VecSet::count(), 0);
+		assert_eq!(VecSet::accounts_paged(None, 3), Vec::<u64>::new());
+
+		for i in 1..=15 {
+			assert_ok!(VecSet::add_member(Origin::signed(i)));
+		}
+		assert_ok!(VecSet::remove_member(Origin::signed(10)));
+
+		assert_eq!(VecSet::count(), 14);
+		assert!(VecSet::contains(&9));
+		assert!(!VecSet::contains(&10));
+
+		// Pages come in sorted order
+		assert_eq!(VecSet::accounts_paged(None, 3), vec![1, 2, 3]);
+		assert_eq!(VecSet::accounts_paged(Some(&3), 3), vec![4, 5, 6]);
+		// The cursor does not need to be a member
+		assert_eq!(VecSet::accounts_paged(Some(&11), 3), vec![12, 13, 14]);
+
+		// Following the cursor visits every member exactly once
+		let mut seen = Vec::new();
+		let mut cursor = None;
+		loop {
+			let page = VecSet::accounts_paged(cursor.as_ref(), 4);
+			if page.is_empty() {
+				break;
+			}
+			assert!(page.len() <= 4);
+			cursor = page.last().cloned();
+			seen.extend(page);
+		}
+		assert_eq!(seen.len(), 14);
+		assert_eq!(seen.into_iter().collect::<BTreeSet<_>>(), VecSet::accounts());
+	})
+}
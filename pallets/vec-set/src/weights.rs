@@ -0,0 +1,73 @@
+//! Weights for the vec set pallet, one for each benchmark in `benchmarking.rs`
+//!
+//! Every call decodes and re-encodes the whole members vec, so each weight is linear in the
+//! number of members `m`. The pallet charges for `MaxMembers` because the actual count is only
+//! known once the vec has been read.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the vec set pallet
+pub trait WeightInfo {
+	fn add_member(m: u32) -> Weight;
+	fn admit_member(m: u32) -> Weight;
+	fn remove_member(m: u32) -> Weight;
+	fn expel_member(m: u32) -> Weight;
+}
+
+/// Weights for the vec set pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn add_member(m: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn admit_member(m: u32) -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(2))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	fn remove_member(m: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+	fn expel_member(m: u32) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(3))
+			.saturating_add(T::DbWeight::get().writes(3))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn add_member(m: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn admit_member(m: u32) -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn remove_member(m: u32) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+	fn expel_member(m: u32) -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add((250_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3))
+			.saturating_add(RocksDbWeight::get().writes(3))
+	}
+}
@@ -0,0 +1,60 @@
+//! Benchmarks for the weights pallet
+
+use super::*;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+benchmarks! {
+	_ { }
+
+	store_value {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: _(RawOrigin::Signed(caller), 42)
+
+	add_n {
+		let n in 0 .. 1_000;
+		let caller: T::AccountId = account("caller", 0, 0);
+		StoredValue::put(0);
+	}: _(RawOrigin::Signed(caller), n)
+
+	double {
+		let n in 0 .. 1_000;
+		let caller: T::AccountId = account("caller", 0, 0);
+		StoredValue::put(n);
+	}: _(RawOrigin::Signed(caller), n)
+
+	complex_calculations {
+		let x in 0 .. 100;
+		let y in 0 .. 1_000;
+		let caller: T::AccountId = account("caller", 0, 0);
+		StoredValue::put(0);
+	}: _(RawOrigin::Signed(caller), x, y)
+
+	add_or_set_flagged {
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: add_or_set(RawOrigin::Signed(caller), true, 42)
+
+	add_or_set_unflagged {
+		let v in 0 .. 1_000;
+		let caller: T::AccountId = account("caller", 0, 0);
+	}: add_or_set(RawOrigin::Signed(caller), false, v)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tests::{ExtBuilder, TestRuntime};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn benchmarks_run() {
+		ExtBuilder::build().execute_with(|| {
+			assert_ok!(test_benchmark_store_value::<TestRuntime>());
+			assert_ok!(test_benchmark_add_n::<TestRuntime>());
+			assert_ok!(test_benchmark_double::<TestRuntime>());
+			assert_ok!(test_benchmark_complex_calculations::<TestRuntime>());
+			assert_ok!(test_benchmark_add_or_set_flagged::<TestRuntime>());
+			assert_ok!(test_benchmark_add_or_set_unflagged::<TestRuntime>());
+		});
+	}
+}
@@ -4,13 +4,24 @@
 
 use frame_support::{
 	decl_module, decl_storage,
-	dispatch::{DispatchResult, PaysFee, WeighData},
+	dispatch::{DispatchResult, DispatchResultWithPostInfo, PaysFee, WeighData},
 	ensure,
 	weights::{ClassifyDispatch, DispatchClass, Pays, Weight},
 };
 use frame_system as system;
 
-pub trait Trait: system::Trait {}
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+pub trait Trait: system::Trait {
+	/// Weight information for the calls in this pallet
+	type WeightInfo: WeightInfo;
+}
 
 decl_storage! {
 	trait Store for Module<T: Trait> as SimpleMap {
@@ -19,16 +30,14 @@ decl_storage! {
 }
 
 // A "scale" to weigh transactions. This scale can be used with any transactions that take a
-// single argument of type u32. The ultimate weight of the transaction is the / product of the
-// transaction parameter and the field of this struct.
-pub struct Linear(u32);
+// single argument of type u32. The ultimate weight of the transaction is computed by the weight
+// function this struct holds, which comes from the pallet's `WeightInfo`.
+pub struct Linear(fn(u32) -> Weight);
 
 // The actual weight calculation happens in the `impl WeighData` block
 impl WeighData<(&u32,)> for Linear {
 	fn weigh_data(&self, (x,): (&u32,)) -> Weight {
-		// Use saturation so that an extremely large parameter value
-		// Does not cause overflow.
-		x.saturating_mul(self.0).into()
+		(self.0)(*x)
 	}
 }
 
@@ -49,18 +58,13 @@ impl<T> ClassifyDispatch<T> for Linear {
 	}
 }
 
-// Another scale to weight transactions. This one is more complex. / It computes weight according
-// to the formula a*x^2 + b*y + c where / a, b, and c are fields in the struct, and x and y are
-// transaction / parameters.
-pub struct Quadratic(u32, u32, u32);
+// Another scale to weight transactions. This one is for transactions that take two u32
+// arguments, x and y. The benchmarks show that the weight grows with x^2 and with y.
+pub struct Quadratic(fn(u32, u32) -> Weight);
 
 impl WeighData<(&u32, &u32)> for Quadratic {
 	fn weigh_data(&self, (x, y): (&u32, &u32)) -> Weight {
-		let ax2 = x.saturating_mul(*x).saturating_mul(self.0);
-		let by = y.saturating_mul(self.1);
-		let c = self.2;
-
-		ax2.saturating_add(by).saturating_add(c).into()
+		(self.0)(*x, *y)
 	}
 }
 
@@ -78,18 +82,17 @@ impl<T> PaysFee<T> for Quadratic {
 }
 
 // A final scale to weight transactions. This one weighs transactions where the first parameter
-// is bool. If the bool is true, then the weight is linear in the second parameter. Otherwise
-// the weight is constant.
-pub struct Conditional(u32);
+// is bool. If the bool is true, the weight is constant. Otherwise the weight is linear in the
+// second parameter.
+pub struct Conditional(fn() -> Weight, fn(u32) -> Weight);
 
 impl WeighData<(&bool, &u32)> for Conditional {
 	fn weigh_data(&self, (switch, val): (&bool, &u32)) -> Weight {
 		if *switch {
-			val.saturating_mul(self.0)
+			(self.0)()
 		} else {
-			self.0
+			(self.1)(*val)
 		}
-		.into()
 	}
 }
 
@@ -110,9 +113,9 @@ decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
 
 		// Store value does not loop at all so a fixed weight is appropriate. Fixed weights can
-		// be assigned using integer constants. No custom coding is
-		// necessary.
-		#[weight = 10_000]
+		// be assigned using any expression that evaluates to a `Weight`, such as the benchmarked
+		// weight function. No custom coding is necessary.
+		#[weight = T::WeightInfo::store_value()]
 		fn store_value(_origin, entry: u32) -> DispatchResult {
 
 			StoredValue::put(entry);
@@ -123,80 +126,104 @@ decl_module! {
 		// WARNING: The functions that follow, allow the caller to control the
 		// amount of computation being performed. This is ONLY SAFE when using
 		// custom weighting structs as shown here.
-
-		// add_n sets the storage value n times, so it should cost n times as much as
-		// store_value. Because it performs both a read and a write, the multiplier is set to 200
-		// instead of 100 as before.
-		#[weight = Linear(200)]
-		fn add_n(_origin, n: u32) -> DispatchResult {
-
-			let mut old : u32;
-			for _i in 1..=n {
-				old = StoredValue::get();
-				StoredValue::put(old + 1);
-			}
-			Ok(())
+		//
+		// The weight declared with `#[weight]` is charged before the call runs, so it must cover
+		// the worst case. These calls return `DispatchResultWithPostInfo` with the weight of the
+		// work they actually did, and the transaction payment pallet refunds the difference.
+
+		// add_n sets the storage value n times, so it should cost roughly n times as much as
+		// store_value. Because it performs both a read and a write, each iteration costs a bit
+		// more than store_value does. It fails without changing anything if the value would
+		// overflow, and is then charged the declared weight in full.
+		#[weight = Linear(T::WeightInfo::add_n)]
+		fn add_n(_origin, n: u32) -> DispatchResultWithPostInfo {
+			Self::increment(n)?;
+
+			Ok(Some(T::WeightInfo::add_n(n)).into())
 		}
 
 		// The actual expense of `double` is proportional to a storage value. Dispatch
 		// weightings can't use storage values directly, because the weight should be computable
-		// ahead of time. Instead we have the caller pass in the expected storage value and we
-		// ensure it is correct.
-		#[weight = Linear(200)]
-		fn double(_origin, initial_value: u32) -> DispatchResult {
+		// ahead of time. Instead we have the caller pass in an upper bound on the storage value,
+		// ensure it holds, and refund whatever the bound overestimated once the call succeeds.
+		#[weight = Linear(T::WeightInfo::double)]
+		fn double(_origin, max_initial_value: u32) -> DispatchResultWithPostInfo {
 
-			// Ensure the value passed by the caller actually matches storage If this condition
-			// were not true, the caller would be able to avoid paying appropriate fees.
+			// Ensure the bound passed by the caller actually holds. If this condition were not
+			// true, the caller would be able to avoid paying appropriate fees.
 			let initial = StoredValue::get();
-			ensure!(initial == initial_value, "Storage value did not match parameter");
+			ensure!(initial <= max_initial_value, "Storage value exceeded parameter");
 
-			for _i in 1..=initial {
-				let old = StoredValue::get();
-				StoredValue::put(old + 1);
-			}
-			Ok(())
+			Self::increment(initial)?;
+
+			Ok(Some(T::WeightInfo::double(initial)).into())
 		}
 
 		// This one is quadratic in the first argument plus linear in the second plus a constant.
 		// This calculation is not meant to do something really useful or common other than
 		// demonstrate that weights should grow by the same order as the compute required by the
 		// transaction.
-		#[weight = Quadratic(200, 30, 100)]
-		fn complex_calculations(_origin, x: u32, y: u32) -> DispatchResult {
-			// This first part performs a relatively cheap (hence 30)
-			// in-memory calculations.
-			let mut part1 = 0;
+		#[weight = Quadratic(T::WeightInfo::complex_calculations)]
+		fn complex_calculations(_origin, x: u32, y: u32) -> DispatchResultWithPostInfo {
+			// This first part performs relatively cheap in-memory calculations.
+			let mut part1: u32 = 0;
 			for _i in 1..=y {
-				part1 += 2
+				part1 = part1.saturating_add(2);
 			}
 
-			// The second part performs x^2 storage read-writes (hence 200)
+			// The second part performs x rows of x storage read-writes, and skips the remaining
+			// rows once a row would overflow the value
+			let mut rows = 0;
 			for _j in 1..=x {
-				for _k in 1..=x {
-					StoredValue::put(StoredValue::get() + 1);
+				if Self::increment(x).is_err() {
+					break;
 				}
+				rows += 1;
 			}
 
-			// One final storage write (hence 100)
+			// One final storage write
 			StoredValue::put(part1);
 
-			Ok(())
+			// Refund the share of the quadratic part that belongs to the rows that were skipped
+			let declared = T::WeightInfo::complex_calculations(x, y);
+			let all_rows = T::WeightInfo::complex_calculations(x, 0)
+				.saturating_sub(T::WeightInfo::complex_calculations(0, 0));
+			let skipped = all_rows / (x.max(1) as Weight) * ((x - rows) as Weight);
+			Ok(Some(declared.saturating_sub(skipped)).into())
 		}
 
 		// Here the first parameter, a boolean has a significant effect on the computational
 		// intensity of the call.
-		#[weight = Conditional(200)]
-		fn add_or_set(_origin, add_flag: bool, val: u32) -> DispatchResult {
+		#[weight = Conditional(
+			T::WeightInfo::add_or_set_flagged,
+			T::WeightInfo::add_or_set_unflagged,
+		)]
+		fn add_or_set(_origin, add_flag: bool, val: u32) -> DispatchResultWithPostInfo {
 			if add_flag {
 				StoredValue::put(&val);
+
+				Ok(Some(T::WeightInfo::add_or_set_flagged()).into())
 			}
 			else {
 				for _i in 1..=val {
 					StoredValue::put(StoredValue::get());
 				}
+
+				// Both branches do exactly the work they were charged for, so nothing is refunded
+				Ok(Some(T::WeightInfo::add_or_set_unflagged(val)).into())
 			}
+		}
+	}
+}
 
-			Ok(())
+impl<T: Trait> Module<T> {
+	/// Increments the stored value `n` times, one read and write at a time. Fails without
+	/// changing anything if the value would overflow.
+	fn increment(n: u32) -> DispatchResult {
+		ensure!(StoredValue::get().checked_add(n).is_some(), "Storage value would overflow");
+		for _i in 1..=n {
+			StoredValue::put(StoredValue::get() + 1);
 		}
+		Ok(())
 	}
 }
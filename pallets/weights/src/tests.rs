@@ -0,0 +1,133 @@
+use crate::*;
+use frame_support::{assert_noop, assert_ok, impl_outer_origin, parameter_types};
+use frame_system as system;
+use sp_core::H256;
+use sp_io::TestExternalities;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for TestRuntime {}
+}
+
+// Workaround for https://github.com/rust-lang/rust/issues/26925 . Remove when sorted.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TestRuntime;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: u32 = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+impl system::Trait for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = ();
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+impl Trait for TestRuntime {
+	type WeightInfo = ();
+}
+
+pub type Weights = Module<TestRuntime>;
+
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+	pub fn build() -> TestExternalities {
+		let storage = system::GenesisConfig::default()
+			.build_storage::<TestRuntime>()
+			.unwrap();
+		TestExternalities::from(storage)
+	}
+}
+
+#[test]
+fn store_value_works() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(Weights::store_value(Origin::signed(1), 10));
+
+		assert_eq!(Weights::stored_value(), 10);
+	})
+}
+
+#[test]
+fn add_n_works() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(Weights::store_value(Origin::signed(1), 10));
+		assert_ok!(Weights::add_n(Origin::signed(1), 5));
+
+		assert_eq!(Weights::stored_value(), 15);
+	})
+}
+
+#[test]
+fn add_n_fails_on_overflow() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(Weights::store_value(Origin::signed(1), u32::max_value() - 3));
+		assert_noop!(Weights::add_n(Origin::signed(1), 4), "Storage value would overflow");
+
+		assert_ok!(Weights::add_n(Origin::signed(1), 3));
+		assert_eq!(Weights::stored_value(), u32::max_value());
+	})
+}
+
+#[test]
+fn double_fails_on_overflow() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(Weights::store_value(Origin::signed(1), u32::max_value() / 2 + 1));
+		assert_noop!(
+			Weights::double(Origin::signed(1), u32::max_value()),
+			"Storage value would overflow"
+		);
+	})
+}
+
+#[test]
+fn double_checks_the_bound() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(Weights::store_value(Origin::signed(1), 10));
+		assert_noop!(
+			Weights::double(Origin::signed(1), 9),
+			"Storage value exceeded parameter"
+		);
+
+		assert_ok!(Weights::double(Origin::signed(1), 10));
+		assert_eq!(Weights::stored_value(), 20);
+	})
+}
+
+#[test]
+fn add_or_set_works() {
+	ExtBuilder::build().execute_with(|| {
+		assert_ok!(Weights::add_or_set(Origin::signed(1), true, 7));
+		assert_eq!(Weights::stored_value(), 7);
+
+		// Without the flag, the value is rewritten `val` times, but does not change
+		assert_ok!(Weights::add_or_set(Origin::signed(1), false, 3));
+		assert_eq!(Weights::stored_value(), 7);
+	})
+}
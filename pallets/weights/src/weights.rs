@@ -0,0 +1,94 @@
+//! Weights for the weights pallet, one for each benchmark in `benchmarking.rs`
+//!
+//! The `Linear`, `Quadratic` and `Conditional` scales in `lib.rs` pass the call's arguments to
+//! these functions. `add_or_set` has one benchmark for each branch.
+
+use frame_support::weights::{constants::RocksDbWeight, Weight};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed by the weights pallet
+pub trait WeightInfo {
+	fn store_value() -> Weight;
+	fn add_n(n: u32) -> Weight;
+	fn double(n: u32) -> Weight;
+	fn complex_calculations(x: u32, y: u32) -> Weight;
+	fn add_or_set_flagged() -> Weight;
+	fn add_or_set_unflagged(v: u32) -> Weight;
+}
+
+/// Weights for the weights pallet, using the runtime's database weights
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Trait> WeightInfo for SubstrateWeight<T> {
+	fn store_value() -> Weight {
+		(5_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn add_n(n: u32) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(n as Weight))
+			.saturating_add(T::DbWeight::get().writes(n as Weight))
+	}
+	fn double(n: u32) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads(n as Weight))
+			.saturating_add(T::DbWeight::get().writes(n as Weight))
+	}
+	fn complex_calculations(x: u32, y: u32) -> Weight {
+		let x2 = (x as Weight).saturating_mul(x as Weight);
+		(5_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(x2))
+			.saturating_add((10_000 as Weight).saturating_mul(y as Weight))
+			.saturating_add(T::DbWeight::get().reads(x2))
+			.saturating_add(T::DbWeight::get().writes(x2))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn add_or_set_flagged() -> Weight {
+		(5_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1))
+	}
+	fn add_or_set_unflagged(v: u32) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(v as Weight))
+			.saturating_add(T::DbWeight::get().writes(v as Weight))
+	}
+}
+
+// For tests, whose runtimes do not configure database weights
+impl WeightInfo for () {
+	fn store_value() -> Weight {
+		(5_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn add_n(n: u32) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(n as Weight))
+			.saturating_add(RocksDbWeight::get().writes(n as Weight))
+	}
+	fn double(n: u32) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(n as Weight))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().reads(n as Weight))
+			.saturating_add(RocksDbWeight::get().writes(n as Weight))
+	}
+	fn complex_calculations(x: u32, y: u32) -> Weight {
+		let x2 = (x as Weight).saturating_mul(x as Weight);
+		(5_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(x2))
+			.saturating_add((10_000 as Weight).saturating_mul(y as Weight))
+			.saturating_add(RocksDbWeight::get().reads(x2))
+			.saturating_add(RocksDbWeight::get().writes(x2))
+			.saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn add_or_set_flagged() -> Weight {
+		(5_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1))
+	}
+	fn add_or_set_unflagged(v: u32) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((1_500_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(RocksDbWeight::get().reads(v as Weight))
+			.saturating_add(RocksDbWeight::get().writes(v as Weight))
+	}
+}
@@ -216,6 +216,7 @@ impl transaction_payment::Trait for Runtime {
 // ---------------------- Recipe Pallet Configurations ----------------------
 impl sum_storage::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = sum_storage::weights::SubstrateWeight<Runtime>;
 }
 
 construct_runtime!(
@@ -316,6 +317,25 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Index> for Runtime {
+		fn account_nonce(account: AccountId) -> Index {
+			System::account_nonce(account)
+		}
+	}
+
+	impl transaction_payment_rpc_runtime_api::TransactionPaymentApi<
+		Block,
+		Balance,
+		UncheckedExtrinsic,
+	> for Runtime {
+		fn query_info(
+			uxt: UncheckedExtrinsic,
+			len: u32,
+		) -> transaction_payment_rpc_runtime_api::RuntimeDispatchInfo<Balance> {
+			TransactionPayment::query_info(uxt, len)
+		}
+	}
+
 	impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
 		fn offchain_worker(header: &<Block as BlockT>::Header) {
 			Executive::offchain_worker(header)
@@ -324,13 +344,17 @@ impl_runtime_apis! {
 
 	// Here we implement our custom runtime API.
 	impl sum_storage_runtime_api::SumStorageApi<Block> for Runtime {
-		fn get_sum() -> u32 {
+		fn get_sum() -> Option<u32> {
 			// This Runtime API calls into a specific pallet. Calling a pallet is a common
 			// design pattern. You can see most other APIs in this file do the same.
 			// It is also possible to write your logic right here in the runtime
 			// amalgamator file
 			SumStorage::get_sum()
 		}
+
+		fn get_state() -> sum_storage_runtime_api::SumStorageState {
+			SumStorage::get_state()
+		}
 	}
 
 	impl sp_session::SessionKeys<Block> for Runtime {
@@ -1,7 +1,8 @@
 //! Helper module to build a genesis configuration for the super-runtime
 
 use super::{
-	AccountId, BalancesConfig, GenesisConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, BalancesConfig, DoubleMapConfig, GenesisConfig, LotteryConfig, Signature,
+	StructStorageConfig, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sp_core::{sr25519, Pair};
 use sp_runtime::traits::{IdentifyAccount, Verify};
@@ -52,5 +53,9 @@ pub fn testnet_genesis(root_key: AccountId, endowed_accounts: Vec<AccountId>) ->
 				.collect(),
 		}),
 		sudo: Some(SudoConfig { key: root_key }),
+		double_map: Some(DoubleMapConfig::default()),
+		// Creates the lottery's pot account
+		lottery: Some(LotteryConfig::default()),
+		struct_storage: Some(StructStorageConfig::default()),
 	}
 }
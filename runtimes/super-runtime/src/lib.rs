@@ -224,19 +224,41 @@ impl sudo::Trait for Runtime {
 
 // ---------------------- Recipe Pallet Configurations ----------------------
 
-impl adding_machine::Trait for Runtime {}
+impl adding_machine::Trait for Runtime {
+	type WeightInfo = adding_machine::weights::SubstrateWeight<Runtime>;
+}
 
 impl basic_token::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = basic_token::weights::SubstrateWeight<Runtime>;
 }
 
 impl charity::Trait for Runtime {
 	type Event = Event;
 	type Currency = Balances;
+	type WeightInfo = charity::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	// Small per-block rates keep the growth factors within fixed-point range
+	pub const DefaultDiscreteInterestRate: Permill = Permill::from_parts(10);
+	pub const DefaultInterestRateModel: compounding_interest::InterestRateModel =
+		compounding_interest::InterestRateModel {
+			base_rate: Permill::from_parts(1),
+			slope_low: Permill::from_parts(10),
+			slope_high: Permill::from_parts(100),
+			kink: Permill::from_parts(800_000),
+		};
+	pub const CollateralFactor: Permill = Permill::from_parts(500_000);
 }
 
 impl compounding_interest::Trait for Runtime {
 	type Event = Event;
+	type Currency = Balances;
+	type DefaultDiscreteInterestRate = DefaultDiscreteInterestRate;
+	type DefaultInterestRateModel = DefaultInterestRateModel;
+	type CollateralFactor = CollateralFactor;
+	type WeightInfo = compounding_interest::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -248,33 +270,49 @@ impl constant_config::Trait for Runtime {
 	type Event = Event;
 	type MaxAddend = MaxAddend;
 	type ClearFrequency = ClearFrequency;
+	type WeightInfo = constant_config::weights::SubstrateWeight<Runtime>;
 }
 
 // The following two configuration traits are for the loosely and tightly coupled variants
 // of the check membership pallet. Both pallets are located in the same `check-membership` crate.
 impl check_membership_loose::Trait for Runtime {
 	type Event = Event;
-	// You can choose either the `vec-set` or `map-set` implementation of the `AccountSet` trait
+	// You can choose the `vec-set`, `map-set` or `expiring-set` implementation of the `AccountSet`
+	// trait
 	type MembershipSource = VecSet;
 	// type MembershipSource = MapSet;
+	// type MembershipSource = ExpiringSet;
+	type WeightInfo = check_membership_loose::weights::SubstrateWeight<Runtime>;
 }
 
 impl check_membership_tight::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = check_membership_tight::weights::SubstrateWeight<Runtime>;
 }
 
 // The following two configuration traits are for two different instances of the deafult-instance
 // pallet. Notice that only the second instance has to explicitly specify an instance.
 impl default_instance::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = default_instance::weights::SubstrateWeight<Runtime>;
 }
 
 impl default_instance::Trait<default_instance::Instance2> for Runtime {
 	type Event = Event;
+	type WeightInfo = default_instance::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MaxGroupSize: u32 = 100;
+	pub const MaxJoinRequests: u32 = 100;
 }
 
 impl double_map::Trait for Runtime {
 	type Event = Event;
+	type ScoreOrigin = system::EnsureRoot<AccountId>;
+	type MaxGroupSize = MaxGroupSize;
+	type MaxJoinRequests = MaxJoinRequests;
+	type WeightInfo = double_map::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -288,33 +326,96 @@ impl execution_schedule::Trait for Runtime {
 	type ExecutionFrequency = ExecutionFrequency;
 	type SignalQuota = SignalQuota;
 	type TaskLimit = TaskLimit;
+	type WeightInfo = execution_schedule::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const MembershipFee: u128 = 10;
+	pub const MembershipPeriod: u32 = 100_000;
+	pub const ExpiryBatchSize: u32 = 10;
+}
+
+impl expiring_set::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type MembershipFee = MembershipFee;
+	type MembershipPeriod = MembershipPeriod;
+	type MaxMembers = MaxMembers;
+	type ExpiryBatchSize = ExpiryBatchSize;
+	type FeeDestination = Charity;
+	type WeightInfo = expiring_set::weights::SubstrateWeight<Runtime>;
 }
 
 impl fixed_point::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = fixed_point::weights::SubstrateWeight<Runtime>;
 }
 
 impl generic_event::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = generic_event::weights::SubstrateWeight<Runtime>;
 }
 
-impl hello_substrate::Trait for Runtime {}
+impl hello_substrate::Trait for Runtime {
+	type WeightInfo = hello_substrate::weights::SubstrateWeight<Runtime>;
+}
 
 // The following two configuration traits are for two different instances of the last-caller pallet
 impl last_caller::Trait<last_caller::Instance1> for Runtime {
 	type Event = Event;
+	type WeightInfo = last_caller::weights::SubstrateWeight<Runtime>;
 }
 
 impl last_caller::Trait<last_caller::Instance2> for Runtime {
 	type Event = Event;
+	type WeightInfo = last_caller::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const TicketPrice: u128 = 1_000;
+	pub const SalesPeriod: u32 = 100;
+	// Two full rounds of the randomness beacon, `2 * (CommitPeriod + RevealPeriod)`. The draw also
+	// waits for two new beacon outputs, so this is the soonest the draw can happen anyway.
+	pub const DrawDelay: u32 = 60;
+	pub const MaxTicketsPerAccount: u32 = 10;
+	pub const MaxTicketsPerRound: u32 = 1_000;
+	pub const HouseFee: Perbill = Perbill::from_percent(5);
+}
+
+impl lottery::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type Randomness = RandomnessBeacon;
+	type TicketPrice = TicketPrice;
+	type SalesPeriod = SalesPeriod;
+	type DrawDelay = DrawDelay;
+	type MaxTicketsPerAccount = MaxTicketsPerAccount;
+	type MaxTicketsPerRound = MaxTicketsPerRound;
+	type HouseFee = HouseFee;
+	type HouseFeeDestination = Charity;
+	type WeightInfo = lottery::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const AdmissionDeposit: u128 = 10;
+	pub const MaxMembers: u32 = 16;
 }
 
 impl map_set::Trait for Runtime {
 	type Event = Event;
+	type Currency = Balances;
+	type AddOrigin = system::EnsureRoot<AccountId>;
+	type RemoveOrigin = system::EnsureRoot<AccountId>;
+	type AdmissionDeposit = AdmissionDeposit;
+	type MaxMembers = MaxMembers;
+	type Slashed = Charity;
+	type MembershipChanged = ();
+	type WeightInfo = map_set::weights::SubstrateWeight<Runtime>;
 }
 
 impl ringbuffer_queue::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = ringbuffer_queue::weights::SubstrateWeight<Runtime>;
 }
 
 impl randomness::Trait for Runtime {
@@ -322,6 +423,25 @@ impl randomness::Trait for Runtime {
 	type CollectiveFlipRandomnessSource = RandomnessCollectiveFlip;
 	//TODO Refactor this recipe to only take one randomness source like a normal pallet would.
 	type BabeRandomnessSource = RandomnessCollectiveFlip;
+	type WeightInfo = randomness::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const CommitBond: u128 = 100;
+	pub const CommitPeriod: u32 = 20;
+	pub const RevealPeriod: u32 = 10;
+	pub const MaxParticipants: u32 = 50;
+}
+
+impl randomness_beacon::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type CommitBond = CommitBond;
+	type CommitPeriod = CommitPeriod;
+	type RevealPeriod = RevealPeriod;
+	type MaxParticipants = MaxParticipants;
+	type Slashed = Charity;
+	type WeightInfo = randomness_beacon::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -336,28 +456,43 @@ impl simple_crowdfund::Trait for Runtime {
 	type SubmissionDeposit = SubmissionDeposit;
 	type MinContribution = MinContribution;
 	type RetirementPeriod = RetirementPeriod;
+	type WeightInfo = simple_crowdfund::weights::SubstrateWeight<Runtime>;
 }
 
 impl simple_event::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = simple_event::weights::SubstrateWeight<Runtime>;
 }
 
 impl simple_map::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = simple_map::weights::SubstrateWeight<Runtime>;
 }
 
-impl single_value::Trait for Runtime {}
+impl single_value::Trait for Runtime {
+	type WeightInfo = single_value::weights::SubstrateWeight<Runtime>;
+}
 
 impl storage_cache::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = storage_cache::weights::SubstrateWeight<Runtime>;
 }
 
 impl struct_storage::Trait for Runtime {
 	type Event = Event;
+	type WeightInfo = struct_storage::weights::SubstrateWeight<Runtime>;
 }
 
 impl vec_set::Trait for Runtime {
 	type Event = Event;
+	type Currency = Balances;
+	type AddOrigin = system::EnsureRoot<AccountId>;
+	type RemoveOrigin = system::EnsureRoot<AccountId>;
+	type AdmissionDeposit = AdmissionDeposit;
+	type MaxMembers = MaxMembers;
+	type Slashed = Charity;
+	type MembershipChanged = ();
+	type WeightInfo = vec_set::weights::SubstrateWeight<Runtime>;
 }
 
 // ---------------------- End of Recipe Pallet Configurations ----------------------
@@ -380,26 +515,29 @@ construct_runtime!(
 		Charity: charity::{Module, Call, Storage, Event<T>},
 		CheckMembershipLoose: check_membership_loose::{Module, Call, Event<T>},
 		CheckMembershipTight: check_membership_tight::{Module, Call, Event<T>},
-		ConmpoundingInterest: compounding_interest::{Module, Call, Storage, Event},
+		ConmpoundingInterest: compounding_interest::{Module, Call, Storage, Event<T>},
 		ConstantConfig: constant_config::{Module, Call, Storage, Event},
 		DefaultInstance1: default_instance::{Module, Call, Storage, Event<T>},
 		DefaultInstance2: default_instance::<Instance2>::{Module, Call, Storage, Event<T>},
-		DoubleMap: double_map::{Module, Call, Storage, Event<T>},
+		DoubleMap: double_map::{Module, Call, Storage, Config, Event<T>},
 		ExecutionSchedule: execution_schedule::{Module, Call, Storage, Event<T>},
+		ExpiringSet: expiring_set::{Module, Call, Storage, Event<T>},
 		FixedPoint: fixed_point::{Module, Call, Storage, Event},
 		HelloSubstrate: hello_substrate::{Module, Call},
 		GenericEvent: generic_event::{Module, Call, Event<T>},
 		LastCaller1: last_caller::<Instance1>::{Module, Call, Storage, Event<T>},
 		LastCaller2: last_caller::<Instance2>::{Module, Call, Storage, Event<T>},
+		Lottery: lottery::{Module, Call, Storage, Event<T>, Config},
 		MapSet: map_set::{Module, Call, Storage, Event<T>},
 		RingbufferQueue: ringbuffer_queue::{Module, Call, Storage, Event<T>},
 		RandomnessDemo: randomness::{Module, Call, Storage, Event},
+		RandomnessBeacon: randomness_beacon::{Module, Call, Storage, Event<T>},
 		SimpleCrowdfund: simple_crowdfund::{Module, Call, Storage, Event<T>},
 		SimpleEvent: simple_event::{Module, Call, Event},
 		SimpleMap: simple_map::{Module, Call, Storage, Event<T>},
 		SingleValue: single_value::{Module, Call, Storage},
 		StorageCache: storage_cache::{Module, Call, Storage, Event<T>},
-		StructStorage: struct_storage::{Module, Call, Storage, Event<T>},
+		StructStorage: struct_storage::{Module, Call, Storage, Config, Event<T>},
 		VecSet: vec_set::{Module, Call, Storage, Event<T>},
 	}
 );
@@ -486,6 +624,25 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Index> for Runtime {
+		fn account_nonce(account: AccountId) -> Index {
+			System::account_nonce(account)
+		}
+	}
+
+	impl transaction_payment_rpc_runtime_api::TransactionPaymentApi<
+		Block,
+		Balance,
+		UncheckedExtrinsic,
+	> for Runtime {
+		fn query_info(
+			uxt: UncheckedExtrinsic,
+			len: u32,
+		) -> transaction_payment_rpc_runtime_api::RuntimeDispatchInfo<Balance> {
+			TransactionPayment::query_info(uxt, len)
+		}
+	}
+
 	impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
 		fn offchain_worker(header: &<Block as BlockT>::Header) {
 			Executive::offchain_worker(header)
@@ -503,4 +660,10 @@ impl_runtime_apis! {
 			None
 		}
 	}
+
+	impl double_map_runtime_api::DoubleMapApi<Block, AccountId> for Runtime {
+		fn leaderboard(group: double_map::GroupIndex) -> Vec<(AccountId, u32)> {
+			DoubleMap::leaderboard(group)
+		}
+	}
 }
@@ -1,7 +1,7 @@
 //! Helper module to build a genesis configuration for the weight-fee-runtime
 
 use super::{
-	AccountId, BalancesConfig, GenericAssetConfig, GenesisConfig, Signature, SudoConfig,
+	AccountId, AssetFees, BalancesConfig, GenericAssetConfig, GenesisConfig, Signature, SudoConfig,
 	SystemConfig, WASM_BINARY,
 };
 use sp_core::{sr25519, Pair};
@@ -49,6 +49,8 @@ pub fn testnet_genesis(root_key: AccountId, endowed_accounts: Vec<AccountId>) ->
 			balances: endowed_accounts
 				.iter()
 				.cloned()
+				// The asset fees exchange account pays native fees for fees paid in assets
+				.chain(Some(AssetFees::account_id()))
 				.map(|k| (k, 1 << 60))
 				.collect(),
 		}),
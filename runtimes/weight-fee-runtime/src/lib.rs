@@ -15,16 +15,21 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 #[cfg(feature = "std")]
 pub mod genesis;
 
+#[cfg(test)]
+mod tests;
+
 use frame_support::{
-	traits::Get,
+	traits::{Currency, FindAuthor, Get, Imbalance, OnUnbalanced},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		Weight, WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial,
 	},
 };
 use frame_system as system;
+use parity_scale_codec::Decode;
 use smallvec::smallvec;
 use sp_api::impl_runtime_apis;
+use sp_consensus_pow::POW_ENGINE_ID;
 use sp_core::{OpaqueMetadata, H256};
 use sp_runtime::traits::{
 	BlakeTwo256, Block as BlockT, IdentifyAccount, IdentityLookup, Saturating, Verify,
@@ -32,26 +37,22 @@ use sp_runtime::traits::{
 use sp_runtime::{
 	create_runtime_str, generic,
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	ApplyExtrinsicResult, ConsensusEngineId, FixedPointNumber, MultiSignature,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
 use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
 
-// These structs are used in one of the commented-by-default implementations of
-// transaction_payment::Trait. Don't warn when they are unused.
-#[allow(unused_imports)]
-use frame_support::weights::IdentityFee;
-#[allow(unused_imports)]
-use generic_asset::{AssetCurrency, AssetIdProvider, SpendingAssetCurrency};
+use fee_adjustment::{Multiplier, TargetedFeeAdjustment};
+use fee_curve::StoredWeightToFee;
 
 // A few exports that help ease life for downstream crates.
 pub use balances::Call as BalancesCall;
 pub use frame_support::{construct_runtime, parameter_types, traits::Randomness, StorageValue};
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
-pub use sp_runtime::{Perbill, Permill};
+pub use sp_runtime::{Perbill, Permill, Perquintill};
 pub use timestamp::Call as TimestampCall;
 
 /// An index to a block.
@@ -222,10 +223,15 @@ impl sudo::Trait for Runtime {
 	type Call = Call;
 }
 
-impl weights::Trait for Runtime {}
+impl weights::Trait for Runtime {
+	type WeightInfo = weights::weights::SubstrateWeight<Runtime>;
+}
 
 // --------------------- Multiple Options for WeightToFee -----------------------
 
+// The fee curve pallet keeps the active curve in storage, so governance can switch between these
+// curves, or any other, without a runtime upgrade. They also serve as its `DefaultCurve`.
+
 /// Convert from weight to fee via a simple coefficient multiplication. The associated type C
 /// encapsulates an integer constant in units of balance per weight.
 pub struct LinearWeightToFee<C>(sp_std::marker::PhantomData<C>);
@@ -281,50 +287,151 @@ impl WeightToFeePolynomial for QuadraticWeightToFee {
 	}
 }
 
-// --------------------- An Option to Currency to Collect Fees -----------------------
-#[allow(dead_code)]
-type FixedGenericAsset<T> = AssetCurrency<T, FixedAssetId>;
-
-pub struct FixedAssetId;
-impl AssetIdProvider for FixedAssetId {
-	type AssetId = u32;
-	fn asset_id() -> Self::AssetId {
-		13
-	}
-}
-
 parameter_types! {
-	// Used with LinearWeightToFee conversion. Leaving this constant in tact when using other
-	// conversion techniques is harmless.
+	// Used with LinearWeightToFee conversion, which is the curve until governance sets another.
 	pub const FeeWeightRatio: u128 = 1_000;
 
+	// The most terms a stored weight to fee curve may have
+	pub const MaxCurveCoefficients: u32 = 8;
+
 	// Establish the byte-fee. It is used in all configurations.
 	pub const TransactionByteFee: u128 = 1;
+
+	// Used with TargetedFeeAdjustment. Fees rise when blocks are more than a quarter full and
+	// fall when they are less. The weight fee may drop to nothing, or rise to ten times its
+	// usual amount (a multiplier of 9 on top of the fee itself).
+	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_integer(-1);
+	pub MaximumMultiplier: Multiplier = Multiplier::saturating_from_integer(9);
 }
 
 impl transaction_payment::Trait for Runtime {
-	// The asset in which fees will be collected.
-	// Enable exactly one of the following options.
-	type Currency = Balances; // The balances pallet (The most common choice)
-						  // type Currency = FixedGenericAsset<Self>; // A generic asset whose ID is hard-coded above.
-						  //type Currency = SpendingAssetCurrency<Self>; // A generic asset whose ID is stored in the
-						  // generic_asset pallet's runtime storage
+	// The native currency, in which fees are computed. Senders may still pay in a generic asset
+	// through the `ChargeAssetTxPayment` signed extension.
+	type Currency = Balances;
 
-	// What to do when fees are paid. () means take no additional actions.
-	type OnTransactionPayment = ();
+	// What to do when fees are paid. () means take no additional actions. Fees paid in generic
+	// assets are handed here too, once they have been exchanged for native currency.
+	// Enable exactly one of the following options.
+	// type OnTransactionPayment = (); // Fees are burned
+	type OnTransactionPayment = DealWithFees;
 
 	// Byte fee is multiplied by the length of the
 	// serialized transaction in bytes
 	type TransactionByteFee = TransactionByteFee;
 
-	// Convert dispatch weight to a chargeable fee.
+	// Convert dispatch weight to a chargeable fee with the curve kept by the fee curve pallet.
+	type WeightToFee = StoredWeightToFee<Self>;
+
+	// Adjust fees to how full blocks are.
 	// Enable exactly one of the following options.
-	//type WeightToFee = IdentityFee<Balance>;
-	type WeightToFee = LinearWeightToFee<FeeWeightRatio>;
-	// type WeightToFee = QuadraticWeightToFee<WeightFeeConstant, WeightFeeLinear, WeightFeeQuadratic>;
+	// type FeeMultiplierUpdate = (); // Fees never change
+	type FeeMultiplierUpdate = TargetedFeeAdjustment<Self>;
+}
+
+impl fee_adjustment::Trait for Runtime {
+	type Event = Event;
+	type TargetBlockFullness = TargetBlockFullness;
+	type MinimumMultiplier = MinimumMultiplier;
+	type MaximumMultiplier = MaximumMultiplier;
+	type WeightInfo = fee_adjustment::weights::SubstrateWeight<Runtime>;
+}
+
+impl fee_curve::Trait for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	// The curve before governance sets one. `IdentityFee<Balance>` and `QuadraticWeightToFee`
+	// work here too.
+	type DefaultCurve = LinearWeightToFee<FeeWeightRatio>;
+	type MaxCoefficients = MaxCurveCoefficients;
+	type WeightInfo = fee_curve::weights::SubstrateWeight<Runtime>;
+}
+
+// --------------------- Paying Fees in Generic Assets -----------------------
+
+impl asset_fees::Trait for Runtime {
+	type Event = Event;
+	// The rates at which each asset pays for fees are set by governance.
+	type RateOrigin = system::EnsureRoot<AccountId>;
+	type WeightInfo = asset_fees::weights::SubstrateWeight<Runtime>;
+}
+
+// --------------------- Distributing Collected Fees -----------------------
+
+impl charity::Trait for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type WeightInfo = charity::weights::SubstrateWeight<Runtime>;
+}
+
+/// Finds the block author in the pre-runtime digest that proof of work nodes may add to blocks
+pub struct PowAuthor;
+
+impl FindAuthor<AccountId> for PowAuthor {
+	fn find_author<'a, I>(digests: I) -> Option<AccountId>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		digests.into_iter().find_map(|(id, mut data)| {
+			if id == POW_ENGINE_ID {
+				AccountId::decode(&mut data).ok()
+			} else {
+				None
+			}
+		})
+	}
+}
 
-	//TODO Explore how to change FeeMultiplierUpdate
-	type FeeMultiplierUpdate = ();
+parameter_types! {
+	pub const UncleGenerations: BlockNumber = 0;
+}
+
+impl authorship::Trait for Runtime {
+	type FindAuthor = PowAuthor;
+	type UncleGenerations = UncleGenerations;
+	type FilterUncle = ();
+	type EventHandler = ();
+}
+
+parameter_types! {
+	// How DealWithFees splits collected fees. Each destination receives its share divided by
+	// the sum of all three shares.
+	pub const AuthorFeeShare: u32 = 40;
+	pub const CharityFeeShare: u32 = 40;
+	pub const BurnFeeShare: u32 = 20;
+}
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Splits collected fees between the block author, the charity, and burning
+///
+/// The transaction payment pallet hands over each transaction's fee and tip together, so both
+/// are split by the same shares. When the block's author is not known, the author's share goes
+/// to the charity.
+pub struct DealWithFees;
+
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		let charity_share = CharityFeeShare::get();
+		let burn_share = BurnFeeShare::get();
+		let (to_author, rest) = amount.ration(
+			AuthorFeeShare::get(),
+			charity_share.saturating_add(burn_share),
+		);
+		let (to_charity, to_burn) = rest.ration(charity_share, burn_share);
+
+		// The authorship pallet reports the default account when no author was found
+		let author = Authorship::author();
+		if author == AccountId::default() {
+			Charity::on_unbalanced(to_author);
+		} else {
+			Balances::resolve_creating(&author, to_author);
+		}
+		Charity::on_unbalanced(to_charity);
+
+		// Dropping a negative imbalance reduces the total issuance
+		drop(to_burn);
+	}
 }
 
 // --------------------------------------------
@@ -342,8 +449,13 @@ construct_runtime!(
 		RandomnessCollectiveFlip: randomness_collective_flip::{Module, Call, Storage},
 		Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
 		TransactionPayment: transaction_payment::{Module, Storage},
+		Authorship: authorship::{Module, Call, Storage},
 		// The Recipe Pallets
 		Weights: weights::{Module, Call, Storage},
+		FeeAdjustment: fee_adjustment::{Module, Call, Storage, Event},
+		FeeCurve: fee_curve::{Module, Call, Storage, Event<T>},
+		AssetFees: asset_fees::{Module, Call, Storage, Event<T>},
+		Charity: charity::{Module, Call, Storage, Event<T>},
 	}
 );
 
@@ -364,7 +476,7 @@ pub type SignedExtra = (
 	system::CheckEra<Runtime>,
 	system::CheckNonce<Runtime>,
 	system::CheckWeight<Runtime>,
-	transaction_payment::ChargeTransactionPayment<Runtime>,
+	asset_fees::ChargeAssetTxPayment<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -429,6 +541,31 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl fee_curve_runtime_api::FeeCurveApi<Block, Balance> for Runtime {
+		fn query_fee_curve() -> Vec<fee_curve::Coefficient<Balance>> {
+			FeeCurve::coefficients()
+		}
+	}
+
+	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Index> for Runtime {
+		fn account_nonce(account: AccountId) -> Index {
+			System::account_nonce(account)
+		}
+	}
+
+	impl transaction_payment_rpc_runtime_api::TransactionPaymentApi<
+		Block,
+		Balance,
+		UncheckedExtrinsic,
+	> for Runtime {
+		fn query_info(
+			uxt: UncheckedExtrinsic,
+			len: u32,
+		) -> transaction_payment_rpc_runtime_api::RuntimeDispatchInfo<Balance> {
+			TransactionPayment::query_info(uxt, len)
+		}
+	}
+
 	impl sp_offchain::OffchainWorkerApi<Block> for Runtime {
 		fn offchain_worker(header: &<Block as BlockT>::Header) {
 			Executive::offchain_worker(header)
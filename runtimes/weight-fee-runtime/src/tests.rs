@@ -0,0 +1,354 @@
+use super::*;
+use frame_support::{
+	dispatch::{DispatchInfo, GetDispatchInfo},
+	traits::OnFinalize,
+	weights::PostDispatchInfo,
+};
+use parity_scale_codec::Encode;
+use sp_core::{sr25519, Pair};
+use sp_runtime::{
+	generic::{Era, SignedPayload},
+	traits::{Dispatchable, Header as HeaderT, SignedExtension},
+	MultiSigner,
+};
+use transaction_payment::ChargeTransactionPayment;
+
+/// The length we pretend every extrinsic has when computing the length fee
+const LEN: usize = 10;
+
+/// A generic asset that is endowed in genesis
+const ASSET: u32 = 13;
+
+fn alice_pair() -> sr25519::Pair {
+	sr25519::Pair::from_string("//Alice", None).unwrap()
+}
+
+fn alice() -> AccountId {
+	MultiSigner::from(alice_pair().public()).into_account()
+}
+
+/// The author of the blocks built through the executive
+fn author() -> AccountId {
+	AccountId::from([7u8; 32])
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = system::GenesisConfig::default()
+		.build_storage::<Runtime>()
+		.unwrap();
+	balances::GenesisConfig::<Runtime> {
+		balances: vec![(alice(), 1 << 60), (AssetFees::account_id(), 1 << 60)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	generic_asset::GenesisConfig::<Runtime> {
+		assets: vec![ASSET],
+		initial_balance: 1 << 60,
+		endowed_accounts: vec![alice()],
+		next_asset_id: 100,
+		staking_asset_id: 1,
+		spending_asset_id: 1,
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+	storage.into()
+}
+
+/// Sets the stored value without charging any fee
+fn set_value(value: u32) {
+	Call::Weights(weights::Call::add_or_set(true, value))
+		.dispatch(Origin::signed(alice()))
+		.unwrap();
+}
+
+/// Charges the fee for `call`, dispatches it, and settles the fee the way the executive would.
+/// Returns the dispatch info and the post dispatch info of the call.
+fn charge_and_dispatch(call: Call) -> (DispatchInfo, PostDispatchInfo) {
+	let info = call.get_dispatch_info();
+	let pre = ChargeTransactionPayment::<Runtime>::from(0)
+		.pre_dispatch(&alice(), &call, &info, LEN)
+		.unwrap();
+
+	let (post_info, result) = match call.dispatch(Origin::signed(alice())) {
+		Ok(post_info) => (post_info, Ok(())),
+		Err(e) => (e.post_info, Err(e.error)),
+	};
+
+	ChargeTransactionPayment::<Runtime>::post_dispatch(pre, &info, &post_info, LEN, &result)
+		.unwrap();
+
+	(info, post_info)
+}
+
+/// The fee for `info` if the call had used `weight`
+fn fee_for(info: &DispatchInfo, weight: Weight) -> Balance {
+	TransactionPayment::compute_fee(LEN as u32, &DispatchInfo { weight, ..*info }, 0)
+}
+
+/// Starts block 1, with `author` in its pre-runtime digest when there is one
+///
+/// The digest is built like the basic-pow node's miner builds it: the encoded `--author` account
+/// under the PoW engine id.
+fn initialize_block(author: Option<AccountId>) {
+	let logs = author
+		.into_iter()
+		.map(|author| DigestItem::PreRuntime(POW_ENGINE_ID, author.encode()))
+		.collect();
+	let header = Header::new(
+		1,
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		generic::Digest { logs },
+	);
+	Executive::initialize_block(&header);
+}
+
+/// A transaction from Alice, which pays its fee and `tip` in `asset_id` or in native currency
+fn signed(call: Call, tip: Balance, asset_id: Option<u32>) -> UncheckedExtrinsic {
+	let extra: SignedExtra = (
+		system::CheckTxVersion::new(),
+		system::CheckGenesis::new(),
+		system::CheckEra::from(Era::Immortal),
+		system::CheckNonce::from(System::account_nonce(alice())),
+		system::CheckWeight::new(),
+		asset_fees::ChargeAssetTxPayment::new(tip, asset_id),
+	);
+	let payload = SignedPayload::new(call, extra).unwrap();
+	let signature = payload.using_encoded(|payload| alice_pair().sign(payload));
+	let (call, extra, _) = payload.deconstruct();
+	UncheckedExtrinsic::new_signed(call, alice(), signature.into(), extra)
+}
+
+/// How `DealWithFees` splits `fee` between the author, the charity, and burning
+fn split(fee: Balance) -> (Balance, Balance, Balance) {
+	let to_author = fee * 40 / 100;
+	let rest = fee - to_author;
+	let to_charity = rest * 40 / 60;
+	(to_author, to_charity, rest - to_charity)
+}
+
+/// Applies `xt` through the executive, and checks that the fee Alice paid in native currency
+/// was split between the author, the charity, and burning
+fn apply_and_check_split(xt: UncheckedExtrinsic, author_known: bool) -> Balance {
+	let charity_before = Balances::free_balance(Charity::account_id());
+	let issuance_before = Balances::total_issuance();
+	let exchange_before = Balances::free_balance(AssetFees::account_id());
+	let alice_before = Balances::free_balance(alice());
+
+	assert_eq!(Executive::apply_extrinsic(xt), Ok(Ok(())));
+
+	// Whoever paid the native fee, Alice or the asset fees exchange account, paid it all
+	let fee = (alice_before - Balances::free_balance(alice()))
+		+ (exchange_before - Balances::free_balance(AssetFees::account_id()));
+	let (to_author, to_charity, to_burn) = split(fee);
+	assert!(fee > 0);
+
+	let charity_gain = Balances::free_balance(Charity::account_id()) - charity_before;
+	if author_known {
+		assert_eq!(Balances::free_balance(author()), to_author);
+		assert_eq!(charity_gain, to_charity);
+	} else {
+		assert_eq!(charity_gain, to_author + to_charity);
+	}
+	assert_eq!(issuance_before - Balances::total_issuance(), to_burn);
+
+	fee
+}
+
+#[test]
+fn double_refunds_an_overestimated_bound() {
+	new_test_ext().execute_with(|| {
+		set_value(5);
+		let before = Balances::free_balance(alice());
+
+		let (info, post_info) = charge_and_dispatch(Call::Weights(weights::Call::double(1_000)));
+
+		let actual = <Runtime as weights::Trait>::WeightInfo::double(5);
+		assert_eq!(post_info.actual_weight, Some(actual));
+		assert!(actual < info.weight);
+		assert_eq!(Weights::stored_value(), 10);
+		assert_eq!(
+			before - Balances::free_balance(alice()),
+			fee_for(&info, actual)
+		);
+		assert!(fee_for(&info, actual) < fee_for(&info, info.weight));
+	});
+}
+
+#[test]
+fn add_n_overflow_pays_the_declared_weight() {
+	new_test_ext().execute_with(|| {
+		set_value(u32::max_value() - 3);
+		let before = Balances::free_balance(alice());
+
+		let (info, post_info) = charge_and_dispatch(Call::Weights(weights::Call::add_n(100)));
+
+		assert_eq!(post_info.actual_weight, None);
+		assert_eq!(Weights::stored_value(), u32::max_value() - 3);
+		assert_eq!(
+			before - Balances::free_balance(alice()),
+			fee_for(&info, info.weight)
+		);
+	});
+}
+
+#[test]
+fn complex_calculations_refunds_skipped_rows() {
+	new_test_ext().execute_with(|| {
+		// Only the first row of ten read-writes fits before the value would overflow
+		set_value(u32::max_value() - 10);
+		let before = Balances::free_balance(alice());
+
+		let (info, post_info) =
+			charge_and_dispatch(Call::Weights(weights::Call::complex_calculations(10, 5)));
+
+		let actual = post_info.actual_weight.unwrap();
+		assert!(actual < info.weight);
+		assert_eq!(
+			before - Balances::free_balance(alice()),
+			fee_for(&info, actual)
+		);
+	});
+}
+
+#[test]
+fn add_or_set_pays_the_declared_weight() {
+	new_test_ext().execute_with(|| {
+		let before = Balances::free_balance(alice());
+
+		let (info, post_info) =
+			charge_and_dispatch(Call::Weights(weights::Call::add_or_set(true, 42)));
+
+		assert_eq!(post_info.actual_weight, Some(info.weight));
+		assert_eq!(Weights::stored_value(), 42);
+		assert_eq!(
+			before - Balances::free_balance(alice()),
+			fee_for(&info, info.weight)
+		);
+	});
+}
+
+#[test]
+fn failed_double_pays_the_declared_weight() {
+	new_test_ext().execute_with(|| {
+		set_value(50);
+		let before = Balances::free_balance(alice());
+
+		let (info, post_info) = charge_and_dispatch(Call::Weights(weights::Call::double(10)));
+
+		assert_eq!(post_info.actual_weight, None);
+		assert_eq!(Weights::stored_value(), 50);
+		assert_eq!(
+			before - Balances::free_balance(alice()),
+			fee_for(&info, info.weight)
+		);
+	});
+}
+
+#[test]
+fn empty_blocks_lower_the_fee_multiplier() {
+	new_test_ext().execute_with(|| {
+		let call = Call::Weights(weights::Call::add_or_set(true, 42));
+		let info = call.get_dispatch_info();
+		let fee_before = TransactionPayment::compute_fee(LEN as u32, &info, 0);
+
+		// Nothing was executed, so the block is emptier than the target
+		TransactionPayment::on_finalize(1);
+
+		assert!(TransactionPayment::next_fee_multiplier() < Multiplier::saturating_from_integer(0));
+		assert!(TransactionPayment::compute_fee(LEN as u32, &info, 0) < fee_before);
+	});
+}
+
+#[test]
+fn author_is_found_in_the_mined_digest() {
+	new_test_ext().execute_with(|| {
+		// Alice's address, as the basic-pow node would be given it with `--author`
+		let author: AccountId = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+			.parse()
+			.unwrap();
+		initialize_block(Some(author));
+
+		assert_eq!(Authorship::author(), alice());
+	});
+}
+
+#[test]
+fn fees_and_tips_are_split() {
+	new_test_ext().execute_with(|| {
+		initialize_block(Some(author()));
+
+		let call = Call::Weights(weights::Call::store_value(5));
+		let info = call.get_dispatch_info();
+		let xt = signed(call, 1_000, None);
+		let len = xt.encode().len() as u32;
+
+		let fee = apply_and_check_split(xt, true);
+		assert_eq!(fee, TransactionPayment::compute_fee(len, &info, 1_000));
+	});
+}
+
+#[test]
+fn author_share_goes_to_charity_without_an_author() {
+	new_test_ext().execute_with(|| {
+		initialize_block(None);
+
+		let xt = signed(Call::Weights(weights::Call::store_value(5)), 0, None);
+		apply_and_check_split(xt, false);
+		assert_eq!(Balances::free_balance(author()), 0);
+	});
+}
+
+#[test]
+fn fees_paid_in_assets_are_split() {
+	new_test_ext().execute_with(|| {
+		initialize_block(Some(author()));
+		let rate = asset_fees::Rate::saturating_from_integer(2);
+		Call::AssetFees(asset_fees::Call::set_rate(ASSET, rate))
+			.dispatch(system::RawOrigin::Root.into())
+			.unwrap();
+		let alice_before = Balances::free_balance(alice());
+		let assets_before = GenericAsset::free_balance(&ASSET, &alice());
+
+		let xt = signed(Call::Weights(weights::Call::store_value(5)), 0, Some(ASSET));
+		let fee = apply_and_check_split(xt, true);
+
+		assert_eq!(Balances::free_balance(alice()), alice_before);
+		assert_eq!(
+			assets_before - GenericAsset::free_balance(&ASSET, &alice()),
+			fee * 2
+		);
+	});
+}
+
+#[test]
+fn governance_switches_the_fee_curve() {
+	new_test_ext().execute_with(|| {
+		let linear: Vec<fee_curve::Coefficient<Balance>> =
+			LinearWeightToFee::<FeeWeightRatio>::polynomial()
+				.into_iter()
+				.map(Into::into)
+				.collect();
+		assert_eq!(FeeCurve::coefficients(), linear);
+
+		let call = Call::Weights(weights::Call::add_or_set(true, 42));
+		let info = call.get_dispatch_info();
+		let linear_fee = TransactionPayment::compute_fee(LEN as u32, &info, 0);
+
+		let quadratic: Vec<fee_curve::Coefficient<Balance>> = QuadraticWeightToFee::polynomial()
+			.into_iter()
+			.map(Into::into)
+			.collect();
+		Call::FeeCurve(fee_curve::Call::set_curve(quadratic.clone()))
+			.dispatch(system::RawOrigin::Root.into())
+			.unwrap();
+		assert_eq!(FeeCurve::coefficients(), quadratic);
+
+		assert_eq!(
+			StoredWeightToFee::<Runtime>::calc(&info.weight),
+			QuadraticWeightToFee::calc(&info.weight)
+		);
+		assert!(TransactionPayment::compute_fee(LEN as u32, &info, 0) > linear_fee);
+	});
+}
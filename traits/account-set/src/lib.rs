@@ -1,11 +1,36 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use sp_std::collections::btree_set::BTreeSet;
+use sp_std::prelude::*;
 
 /// Types that implement the AccountSet trait are able to supply a set of accounts
 /// The trait is generic over the notion of Account used.
 pub trait AccountSet {
 	type AccountId;
 
+	/// All accounts in the set. This reads the entire set, so prefer the other methods for large
+	/// sets.
 	fn accounts() -> BTreeSet<Self::AccountId>;
+
+	/// Whether the account is in the set
+	fn contains(who: &Self::AccountId) -> bool;
+
+	/// The number of accounts in the set
+	fn count() -> u32;
+
+	/// Up to `limit` accounts, starting after the account `start_after`, or from the beginning if
+	/// it is `None`. Passing the last account of one page as `start_after` yields the next page.
+	///
+	/// The order is up to the implementation, but stays the same while the set does not change.
+	fn accounts_paged(start_after: Option<&Self::AccountId>, limit: u32) -> Vec<Self::AccountId>;
+}
+
+/// Types that implement the OnMembersChanged trait are notified when a set of accounts changes
+pub trait OnMembersChanged<AccountId> {
+	/// Called after `incoming` accounts joined the set and `outgoing` accounts left it
+	fn on_members_changed(incoming: &[AccountId], outgoing: &[AccountId]);
+}
+
+impl<AccountId> OnMembersChanged<AccountId> for () {
+	fn on_members_changed(_incoming: &[AccountId], _outgoing: &[AccountId]) {}
 }
@@ -0,0 +1,308 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Overflow-safe math for the fixed point types used throughout the recipes.
+//!
+//! Every function returns a `Result` rather than panicking, so it is safe to call from a
+//! runtime. The functions are implemented for substrate-fixed's `I32F32` and `U16F16`, and for
+//! Substrate's `Permill`.
+//!
+//! Internally the transcendental functions are evaluated in `I64F64`, which has plenty of
+//! headroom for intermediate values, and the result is then narrowed back to the caller's type.
+//! A result that does not fit in the caller's type is reported as `Error::Overflow`.
+//!
+//! Prefer the free functions such as `fixed_math::exp(x)` over the `FixedMath` methods. The
+//! substrate-fixed types have an inherent `checked_mul` that returns an `Option`, and method
+//! syntax would pick that one instead.
+
+use sp_arithmetic::Permill;
+use sp_std::convert::TryFrom;
+use substrate_fixed::types::{I32F32, I64F64, U16F16};
+
+#[cfg(test)]
+mod tests;
+
+/// The ways a fixed point calculation can fail
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum Error {
+	/// The result does not fit in the type
+	Overflow,
+	/// The function is not defined for the given input
+	Domain,
+}
+
+/// Fixed point types that support overflow-safe arithmetic and transcendental functions
+pub trait FixedMath: Sized {
+	/// Multiply two numbers
+	fn checked_mul(self, other: Self) -> Result<Self, Error>;
+
+	/// Calculate `e ^ self`
+	fn checked_exp(self) -> Result<Self, Error>;
+
+	/// Raise the number to an integer power
+	fn checked_pow(self, exp: u32) -> Result<Self, Error>;
+
+	/// Calculate the natural logarithm. Fails with `Error::Domain` for non-positive numbers.
+	fn checked_ln(self) -> Result<Self, Error>;
+
+	/// Calculate the square root. Fails with `Error::Domain` for negative numbers.
+	fn checked_sqrt(self) -> Result<Self, Error>;
+}
+
+/// Multiply two fixed point numbers
+pub fn mul<F: FixedMath>(a: F, b: F) -> Result<F, Error> {
+	a.checked_mul(b)
+}
+
+/// Calculate `e ^ x`
+pub fn exp<F: FixedMath>(x: F) -> Result<F, Error> {
+	x.checked_exp()
+}
+
+/// Raise `base` to the integer power `exp`
+pub fn pow<F: FixedMath>(base: F, exp: u32) -> Result<F, Error> {
+	base.checked_pow(exp)
+}
+
+/// Calculate the natural logarithm of `x`
+pub fn ln<F: FixedMath>(x: F) -> Result<F, Error> {
+	x.checked_ln()
+}
+
+/// Calculate the square root of `x`
+pub fn sqrt<F: FixedMath>(x: F) -> Result<F, Error> {
+	x.checked_sqrt()
+}
+
+/// Zero in the working type
+const ZERO: I64F64 = I64F64::from_bits(0);
+
+/// One in the working type
+const ONE: I64F64 = I64F64::from_bits(1 << 64);
+
+/// The natural logarithm of two, to the 64 fractional bits of the working type
+const LN_2: I64F64 = I64F64::from_bits(0xB172_17F7_D1CF_79AB);
+
+/// `e ^ 43` is the largest power of `e` with an integer part that fits in the working type
+const EXP_MAX: i32 = 43;
+
+/// `e ^ -45` is smaller than the working type's resolution
+const EXP_MIN: i32 = -45;
+
+macro_rules! impl_fixed_math {
+	($fixed:ty, $bits:ty, $wide:ty, $frac:expr) => {
+		impl FixedMath for $fixed {
+			fn checked_mul(self, other: Self) -> Result<Self, Error> {
+				// Multiplying doubles the number of integer and fractional bits, so the product
+				// is calculated in a wider type and the extra fractional bits are shifted away.
+				// If anything remains in the extra integer bits, the product has overflowed.
+				let product =
+					(<$wide>::from(self.to_bits()) * <$wide>::from(other.to_bits())) >> $frac;
+				<$bits>::try_from(product)
+					.map(Self::from_bits)
+					.map_err(|_| Error::Overflow)
+			}
+
+			fn checked_exp(self) -> Result<Self, Error> {
+				Self::checked_from_num(exp_wide(I64F64::from_num(self))?).ok_or(Error::Overflow)
+			}
+
+			fn checked_pow(self, exp: u32) -> Result<Self, Error> {
+				Self::checked_from_num(pow_wide(I64F64::from_num(self), exp)?)
+					.ok_or(Error::Overflow)
+			}
+
+			fn checked_ln(self) -> Result<Self, Error> {
+				Self::checked_from_num(ln_wide(I64F64::from_num(self))?).ok_or(Error::Overflow)
+			}
+
+			fn checked_sqrt(self) -> Result<Self, Error> {
+				// The square root of `bits / 2^frac` is `sqrt(bits * 2^frac) / 2^frac`, so an
+				// integer square root of the shifted bits gives the bits of the result.
+				let bits = u128::try_from(self.to_bits()).map_err(|_| Error::Domain)?;
+				<$bits>::try_from(isqrt(bits << $frac))
+					.map(Self::from_bits)
+					.map_err(|_| Error::Overflow)
+			}
+		}
+	};
+}
+
+impl_fixed_math!(I32F32, i64, i128, 32);
+impl_fixed_math!(U16F16, u32, u64, 16);
+
+/// `Permill` only holds values between zero and one. Multiplication, powers and square roots
+/// stay within that range, but `exp` and `ln` only do so for inputs of zero and one respectively.
+impl FixedMath for Permill {
+	fn checked_mul(self, other: Self) -> Result<Self, Error> {
+		Ok(self * other)
+	}
+
+	fn checked_exp(self) -> Result<Self, Error> {
+		permill_from_wide(exp_wide(permill_to_wide(self))?)
+	}
+
+	fn checked_pow(self, exp: u32) -> Result<Self, Error> {
+		// Exponentiation by squaring. Permill can not overflow, so no checks are needed.
+		let mut result = Permill::one();
+		let mut base = self;
+		let mut exp = exp;
+
+		while exp > 0 {
+			if exp & 1 == 1 {
+				result = result * base;
+			}
+			exp >>= 1;
+			base = base * base;
+		}
+
+		Ok(result)
+	}
+
+	fn checked_ln(self) -> Result<Self, Error> {
+		permill_from_wide(ln_wide(permill_to_wide(self))?)
+	}
+
+	fn checked_sqrt(self) -> Result<Self, Error> {
+		// As for the other types, `sqrt(parts / 10^6)` is `sqrt(parts * 10^6) / 10^6`
+		let parts = isqrt(u128::from(self.deconstruct()) * 1_000_000);
+		u32::try_from(parts)
+			.map(Permill::from_parts)
+			.map_err(|_| Error::Overflow)
+	}
+}
+
+/// Convert a `Permill` into the working type
+fn permill_to_wide(p: Permill) -> I64F64 {
+	I64F64::from_num(p.deconstruct()) / 1_000_000
+}
+
+/// Convert a value of the working type into a `Permill`, failing if it is not between zero and
+/// one
+fn permill_from_wide(x: I64F64) -> Result<Permill, Error> {
+	if x < ZERO || x > ONE {
+		return Err(Error::Overflow);
+	}
+	Ok(Permill::from_parts((x * 1_000_000).to_num::<u32>()))
+}
+
+/// Calculate `e ^ x` in the working type
+///
+/// We write `x = k * ln(2) + r` with an integer `k` and `0 <= r < ln(2)`. Then
+/// `e ^ x = 2 ^ k * e ^ r`. The Taylor series for `e ^ r` converges quickly for such a small
+/// `r`, and multiplying by `2 ^ k` is just a shift.
+fn exp_wide(x: I64F64) -> Result<I64F64, Error> {
+	if x > I64F64::from_num(EXP_MAX) {
+		return Err(Error::Overflow);
+	}
+	if x < I64F64::from_num(EXP_MIN) {
+		return Ok(ZERO);
+	}
+
+	// Converting to an integer rounds towards negative infinity, so `r` is never negative
+	let k = (x / LN_2).to_num::<i32>();
+	let r = x - LN_2 * I64F64::from_num(k);
+
+	// Sum the Taylor series until the terms are too small to represent
+	let mut sum = ONE;
+	let mut term = ONE;
+	let mut n: i128 = 1;
+	loop {
+		term = term * r / n;
+		if term == ZERO {
+			break;
+		}
+		sum += term;
+		n += 1;
+	}
+
+	// `sum` is less than two, and `k` is at most 62, so shifting left can not overflow
+	if k >= 0 {
+		Ok(I64F64::from_bits(sum.to_bits() << k))
+	} else {
+		Ok(I64F64::from_bits(sum.to_bits() >> -k))
+	}
+}
+
+/// Calculate the natural logarithm of `x` in the working type
+///
+/// We write `x = 2 ^ k * m` with an integer `k` and `1 <= m < 2`. Then
+/// `ln(x) = k * ln(2) + ln(m)`. With `y = (m - 1) / (m + 1)` we have `0 <= y < 1/3`, and
+/// `ln(m) = 2 * (y + y^3 / 3 + y^5 / 5 + ...)` converges quickly.
+fn ln_wide(x: I64F64) -> Result<I64F64, Error> {
+	if x <= ZERO {
+		return Err(Error::Domain);
+	}
+
+	// Find the position of the most significant bit relative to the binary point
+	let bits = x.to_bits();
+	let k = 63 - bits.leading_zeros() as i32;
+	let m = if k >= 0 {
+		I64F64::from_bits(bits >> k)
+	} else {
+		I64F64::from_bits(bits << -k)
+	};
+
+	let y = (m - ONE) / (m + ONE);
+	let y_squared = y * y;
+	let mut sum = y;
+	let mut power = y;
+	let mut n: i128 = 3;
+	loop {
+		power *= y_squared;
+		let term = power / n;
+		if term == ZERO {
+			break;
+		}
+		sum += term;
+		n += 2;
+	}
+
+	Ok(LN_2 * I64F64::from_num(k) + sum + sum)
+}
+
+/// Raise `base` to the integer power `exp` in the working type using exponentiation by squaring
+///
+/// The cost depends only on the number of bits in `exp`, not on its size.
+fn pow_wide(base: I64F64, exp: u32) -> Result<I64F64, Error> {
+	let mut result = ONE;
+	let mut base = base;
+	let mut exp = exp;
+
+	while exp > 0 {
+		if exp & 1 == 1 {
+			result = result.checked_mul(base).ok_or(Error::Overflow)?;
+		}
+		exp >>= 1;
+		if exp > 0 {
+			base = base.checked_mul(base).ok_or(Error::Overflow)?;
+		}
+	}
+
+	Ok(result)
+}
+
+/// The integer square root, rounded down
+///
+/// Calculated digit by digit, the same way one would by hand, but in base four.
+fn isqrt(n: u128) -> u128 {
+	let mut remainder = n;
+	let mut root = 0;
+	let mut bit = 1 << 126;
+
+	while bit > remainder {
+		bit >>= 2;
+	}
+
+	while bit != 0 {
+		if remainder >= root + bit {
+			remainder -= root + bit;
+			root = (root >> 1) + bit;
+		} else {
+			root >>= 1;
+		}
+		bit >>= 2;
+	}
+
+	root
+}
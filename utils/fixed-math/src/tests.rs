@@ -0,0 +1,198 @@
+use crate::*;
+use proptest::prelude::*;
+
+/// Whether `actual` is within `absolute + relative * |expected|` of `expected`
+fn close(actual: f64, expected: f64, absolute: f64, relative: f64) -> bool {
+	(actual - expected).abs() <= absolute + relative * expected.abs()
+}
+
+/// The resolution of `I32F32` is about 2.3e-10
+const I32F32_TOLERANCE: f64 = 1e-9;
+
+/// The resolution of `U16F16` is about 1.5e-5
+const U16F16_TOLERANCE: f64 = 3e-5;
+
+/// The resolution of `Permill` is 1e-6
+const PERMILL_TOLERANCE: f64 = 2e-6;
+
+fn permill_to_f64(p: Permill) -> f64 {
+	f64::from(p.deconstruct()) / 1_000_000.0
+}
+
+proptest! {
+	// I32F32
+
+	#[test]
+	fn i32f32_mul_matches_f64(a in -40_000.0f64..40_000.0, b in -40_000.0f64..40_000.0) {
+		let (a, b) = (I32F32::from_num(a), I32F32::from_num(b));
+		let expected = a.to_num::<f64>() * b.to_num::<f64>();
+		let actual = mul(a, b).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, I32F32_TOLERANCE, 1e-12));
+	}
+
+	#[test]
+	fn i32f32_exp_matches_f64(x in -30.0f64..21.0) {
+		let x = I32F32::from_num(x);
+		let expected = x.to_num::<f64>().exp();
+		let actual = exp(x).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, I32F32_TOLERANCE, 1e-12));
+	}
+
+	#[test]
+	fn i32f32_exp_overflows(x in 21.5f64..2_000_000_000.0) {
+		prop_assert_eq!(exp(I32F32::from_num(x)), Err(Error::Overflow));
+	}
+
+	#[test]
+	fn i32f32_pow_matches_f64(base in -20.0f64..20.0, n in 0u32..7) {
+		let base = I32F32::from_num(base);
+		let expected = base.to_num::<f64>().powi(n as i32);
+		let actual = pow(base, n).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, I32F32_TOLERANCE, 1e-12));
+	}
+
+	#[test]
+	fn i32f32_ln_matches_f64(x in 1e-6f64..2_000_000_000.0) {
+		let x = I32F32::from_num(x);
+		let expected = x.to_num::<f64>().ln();
+		let actual = ln(x).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, I32F32_TOLERANCE, 1e-12));
+	}
+
+	#[test]
+	fn i32f32_ln_of_non_positive_fails(x in -2_000_000_000.0f64..=0.0) {
+		prop_assert_eq!(ln(I32F32::from_num(x)), Err(Error::Domain));
+	}
+
+	#[test]
+	fn i32f32_sqrt_matches_f64(x in 0.0f64..2_000_000_000.0) {
+		let x = I32F32::from_num(x);
+		let expected = x.to_num::<f64>().sqrt();
+		let actual = sqrt(x).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, I32F32_TOLERANCE, 1e-12));
+	}
+
+	#[test]
+	fn i32f32_sqrt_of_negative_fails(x in -2_000_000_000.0f64..-1e-9) {
+		prop_assert_eq!(sqrt(I32F32::from_num(x)), Err(Error::Domain));
+	}
+
+	// U16F16
+
+	#[test]
+	fn u16f16_mul_matches_f64(a in 0.0f64..250.0, b in 0.0f64..250.0) {
+		let (a, b) = (U16F16::from_num(a), U16F16::from_num(b));
+		let expected = a.to_num::<f64>() * b.to_num::<f64>();
+		let actual = mul(a, b).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, U16F16_TOLERANCE, 0.0));
+	}
+
+	#[test]
+	fn u16f16_exp_matches_f64(x in 0.0f64..11.0) {
+		let x = U16F16::from_num(x);
+		let expected = x.to_num::<f64>().exp();
+		let actual = exp(x).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, U16F16_TOLERANCE, 1e-12));
+	}
+
+	#[test]
+	fn u16f16_exp_overflows(x in 11.1f64..65_535.0) {
+		prop_assert_eq!(exp(U16F16::from_num(x)), Err(Error::Overflow));
+	}
+
+	#[test]
+	fn u16f16_pow_matches_f64(base in 0.0f64..4.0, n in 0u32..7) {
+		let base = U16F16::from_num(base);
+		let expected = base.to_num::<f64>().powi(n as i32);
+		let actual = pow(base, n).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, U16F16_TOLERANCE, 0.0));
+	}
+
+	#[test]
+	fn u16f16_ln_matches_f64(x in 1.0f64..65_535.0) {
+		let x = U16F16::from_num(x);
+		let expected = x.to_num::<f64>().ln();
+		let actual = ln(x).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, U16F16_TOLERANCE, 0.0));
+	}
+
+	#[test]
+	fn u16f16_sqrt_matches_f64(x in 0.0f64..65_535.0) {
+		let x = U16F16::from_num(x);
+		let expected = x.to_num::<f64>().sqrt();
+		let actual = sqrt(x).unwrap().to_num::<f64>();
+		prop_assert!(close(actual, expected, U16F16_TOLERANCE, 0.0));
+	}
+
+	// Permill
+
+	#[test]
+	fn permill_mul_matches_f64(a in 0u32..=1_000_000, b in 0u32..=1_000_000) {
+		let (a, b) = (Permill::from_parts(a), Permill::from_parts(b));
+		let expected = permill_to_f64(a) * permill_to_f64(b);
+		let actual = permill_to_f64(mul(a, b).unwrap());
+		prop_assert!(close(actual, expected, PERMILL_TOLERANCE, 0.0));
+	}
+
+	#[test]
+	fn permill_pow_matches_f64(base in 0u32..=1_000_000, n in 0u32..7) {
+		let base = Permill::from_parts(base);
+		let expected = permill_to_f64(base).powi(n as i32);
+		let actual = permill_to_f64(pow(base, n).unwrap());
+		// Each multiplication may round, so allow for one rounding per multiplication
+		prop_assert!(close(actual, expected, PERMILL_TOLERANCE * f64::from(n.max(1)), 0.0));
+	}
+
+	#[test]
+	fn permill_sqrt_matches_f64(x in 0u32..=1_000_000) {
+		let x = Permill::from_parts(x);
+		let expected = permill_to_f64(x).sqrt();
+		let actual = permill_to_f64(sqrt(x).unwrap());
+		prop_assert!(close(actual, expected, PERMILL_TOLERANCE, 0.0));
+	}
+
+	#[test]
+	fn permill_exp_overflows(x in 1u32..=1_000_000) {
+		prop_assert_eq!(exp(Permill::from_parts(x)), Err(Error::Overflow));
+	}
+
+	#[test]
+	fn permill_ln_overflows(x in 1u32..1_000_000) {
+		prop_assert_eq!(ln(Permill::from_parts(x)), Err(Error::Overflow));
+	}
+}
+
+#[test]
+fn identities_hold_exactly() {
+	assert_eq!(exp(I32F32::from_num(0)), Ok(I32F32::from_num(1)));
+	assert_eq!(ln(I32F32::from_num(1)), Ok(I32F32::from_num(0)));
+	assert_eq!(pow(I32F32::from_num(3), 0), Ok(I32F32::from_num(1)));
+	assert_eq!(sqrt(I32F32::from_num(16)), Ok(I32F32::from_num(4)));
+
+	assert_eq!(exp(U16F16::from_num(0)), Ok(U16F16::from_num(1)));
+	assert_eq!(ln(U16F16::from_num(1)), Ok(U16F16::from_num(0)));
+	assert_eq!(sqrt(U16F16::from_num(0.25)), Ok(U16F16::from_num(0.5)));
+
+	assert_eq!(exp(Permill::zero()), Ok(Permill::one()));
+	assert_eq!(ln(Permill::one()), Ok(Permill::zero()));
+	assert_eq!(ln(Permill::zero()), Err(Error::Domain));
+	assert_eq!(sqrt(Permill::from_percent(25)), Ok(Permill::from_percent(50)));
+}
+
+#[test]
+fn overflowing_products_are_errors() {
+	let big = I32F32::from_num(1 << 16);
+	assert_eq!(mul(big, big), Err(Error::Overflow));
+	assert_eq!(pow(I32F32::from_num(2), 31), Err(Error::Overflow));
+	assert_eq!(pow(I32F32::from_num(2), 30), Ok(I32F32::from_num(1 << 30)));
+
+	let big = U16F16::from_num(1 << 8);
+	assert_eq!(mul(big, big), Err(Error::Overflow));
+	assert_eq!(pow(U16F16::from_num(2), 16), Err(Error::Overflow));
+}
+
+#[test]
+fn negative_powers_keep_their_sign() {
+	assert_eq!(pow(I32F32::from_num(-2), 3), Ok(I32F32::from_num(-8)));
+	assert_eq!(pow(I32F32::from_num(-2), 4), Ok(I32F32::from_num(16)));
+}